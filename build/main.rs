@@ -71,6 +71,8 @@ fn write_commands(output: &mut File) -> Result<(), &str> {
     Ok(())
 }
 
+// Module paths are written as raw identifiers, since
+// some module names (e.g. macro) are reserved keywords.
 fn write_command(
     output: &mut File,
     module_name: &str,
@@ -79,7 +81,7 @@ fn write_command(
     output
         .write(
             format!(
-                "    commands.insert(\"{module_name}::{function_name}\", {module_name}::{function_name});\n"
+                "    commands.insert(\"{module_name}::{function_name}\", r#{module_name}::{function_name});\n"
             )
             .as_bytes(),
         )
@@ -0,0 +1,147 @@
+use crate::commands::{self, Result};
+use crate::errors::*;
+use crate::input::Key;
+use crate::models::application::{Application, Mode, ModeKey};
+use crate::util::align;
+
+pub fn push_char(app: &mut Application) -> Result {
+    let last_key = app
+        .view
+        .last_key()
+        .as_ref()
+        .context("View hasn't tracked a key press")?;
+    if let Key::Char(c) = *last_key {
+        if let Mode::Align(ref mut mode) = app.mode {
+            mode.push_char(c);
+        } else {
+            bail!("Cannot push char outside of align mode");
+        }
+    } else {
+        bail!("Last key press wasn't a character");
+    }
+    Ok(())
+}
+
+pub fn pop_char(app: &mut Application) -> Result {
+    if let Mode::Align(ref mut mode) = app.mode {
+        mode.pop_char();
+    } else {
+        bail!("Cannot pop char outside of align mode");
+    }
+    Ok(())
+}
+
+/// Pads the selected lines so that each occurrence of the entered
+/// delimiter lines up, as a single operation. Delimiters wrapped
+/// in slashes (e.g. `/=>?/`) are treated as regular expressions.
+pub fn accept_delimiter(app: &mut Application) -> Result {
+    let (input, range) = match app.mode {
+        Mode::Align(ref mode) => (mode.input.clone(), mode.range.clone()),
+        _ => bail!("Cannot accept delimiter outside of align mode"),
+    };
+    if input.is_empty() {
+        bail!("Please provide a delimiter to align on");
+    }
+    let delimiter = align::delimiter(&input)?;
+    let range = range.context("No lines to align")?;
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    let tab_width = app.preferences.borrow().tab_width(buffer.path.as_ref());
+
+    app.switch_to(ModeKey::Normal);
+    commands::selection::transform_lines(app, range, |data| {
+        let lines: Vec<&str> = data.split('\n').collect();
+        align::align(&lines, &delimiter, tab_width).join("\n")
+    })?;
+
+    commands::view::scroll_to_cursor(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands;
+    use crate::commands::test_support::{press_keys, set_up_application};
+    use crate::input::Key;
+    use crate::models::application::{Application, Mode};
+    use scribe::buffer::Position;
+
+    fn align(app: &mut Application, delimiter: &str) -> super::Result {
+        commands::selection::align(app).unwrap();
+        if let Mode::Align(ref mut mode) = app.mode {
+            mode.input = delimiter.to_string();
+        }
+
+        super::accept_delimiter(app)
+    }
+
+    #[test]
+    fn accept_delimiter_aligns_the_selected_lines() {
+        let mut app = set_up_application("amp = 1\neditor = 2\nx = 3\n");
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 1, offset: 0 });
+
+        align(&mut app, "=").unwrap();
+        let buffer = app.workspace.current_buffer.as_ref().unwrap();
+        assert_eq!(buffer.data(), "amp    = 1\neditor = 2\nx = 3\n");
+        assert!(matches!(app.mode, Mode::Normal(_)));
+    }
+
+    #[test]
+    fn align_is_bound_in_select_line_mode() {
+        let mut app = set_up_application("amp = 1\neditor = 2\nx = 3\n");
+
+        press_keys(
+            &mut app,
+            &[
+                Key::Char('V'),
+                Key::Char('j'),
+                Key::Char('='),
+                Key::Char('='),
+                Key::Enter,
+            ],
+        );
+        let buffer = app.workspace.current_buffer.as_ref().unwrap();
+        assert_eq!(buffer.data(), "amp    = 1\neditor = 2\nx = 3\n");
+        assert!(matches!(app.mode, Mode::Normal(_)));
+    }
+
+    #[test]
+    fn accept_delimiter_undoes_alignment_as_a_single_change() {
+        let mut app = set_up_application("a | b | c\nbb | c | d");
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 1, offset: 0 });
+
+        align(&mut app, "|").unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "a  | b | c\nbb | c | d"
+        );
+
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "a | b | c\nbb | c | d"
+        );
+    }
+
+    #[test]
+    fn align_requires_a_line_selection() {
+        let mut app = set_up_application("amp = 1");
+
+        let error = commands::selection::align(&mut app).unwrap_err();
+        assert_eq!(error.to_string(), "A line selection is required.");
+    }
+}
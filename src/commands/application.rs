@@ -1,6 +1,6 @@
-use crate::commands::{self, Result};
+use crate::commands::{self, Command, Result};
 use crate::errors::*;
-use crate::input::KeyMap;
+use crate::input::{Key, KeyMap};
 use crate::models::application::{Application, Mode, ModeKey};
 use crate::util;
 use log::debug;
@@ -9,20 +9,151 @@ use std::fs::{read_to_string, remove_file, File};
 use std::path::PathBuf;
 
 pub fn handle_input(app: &mut Application) -> Result {
+    // Record keys that may make up a repeatable change, noting
+    // the buffer's revision so we can tell if they've modified it.
+    let recording = !app.change_recorder.replaying
+        && (app.change_recorder.inserting()
+            || matches!(app.mode, Mode::Normal(_))
+            || (app.change_recorder.pending()
+                && matches!(app.mode, Mode::TextObject(_) | Mode::Surround(_))));
+    let mut initial_state = None;
+    if recording {
+        if let Some(key) = app.view.last_key().clone() {
+            app.change_recorder.record(key);
+        }
+        if !app.change_recorder.inserting() {
+            initial_state = app
+                .workspace
+                .current_buffer
+                .as_ref()
+                .and_then(|buffer| Some((buffer.id, app.view.buffer_revision(buffer)?)));
+        }
+    }
+
+    if let Some(buffer) = app.workspace.current_buffer.as_ref() {
+        app.undo_tree
+            .track(buffer, app.view.buffer_revision(buffer));
+    }
+    let selecting_register = matches!(app.mode, Mode::Register(_));
+
+    let result = run_key_commands(app);
+
+    // Registers only apply to the command following their selection.
+    if !selecting_register {
+        app.clipboard.clear_register();
+    }
+
+    // Keep expanded snippets' tab stops and mirrors up to date.
+    if let Some(buffer) = app.workspace.current_buffer.as_mut() {
+        app.snippets.sync(buffer);
+    }
+
+    if recording {
+        update_change_recorder(app, initial_state);
+    }
+
+    // Insert mode sessions are recorded as a single undo state,
+    // once they end and the mode handling them has been left.
+    if !app.change_recorder.replaying
+        && !matches!(app.mode, Mode::Insert | Mode::Paste | Mode::Completion(_))
+    {
+        if let Some(buffer) = app.workspace.current_buffer.as_ref() {
+            app.undo_tree
+                .record(buffer, app.view.buffer_revision(buffer));
+        }
+    }
+
+    result
+}
+
+fn run_key_commands(app: &mut Application) -> Result {
+    // Digits typed in normal mode build up a count prefix for the next
+    // command; a leading zero is left to its own key binding, though.
+    if let (Mode::Normal(ref mut mode), Some(Key::Char(character))) =
+        (&mut app.mode, app.view.last_key())
+    {
+        if let Some(digit) = character.to_digit(10) {
+            if digit > 0 || mode.count.is_some() {
+                mode.push_count_digit(digit as usize);
+
+                return Ok(());
+            }
+        }
+    }
+
+    let count = match app.mode {
+        Mode::Normal(ref mut mode) => mode.count.take(),
+        _ => None,
+    };
+
     let commands = app.view.last_key().as_ref().and_then(|key| {
         app.mode_str()
             .and_then(|mode| app.preferences.borrow().keymap().commands_for(mode, key))
     });
 
     if let Some(coms) = commands {
-        // Run all commands, stopping at the first error encountered, if any.
-        for com in coms {
-            debug!("running command");
+        match count {
+            // Repeat the commands as a single operation, stopping
+            // early if they leave normal mode (e.g. to insert text).
+            Some(count) if count > 1 && app.workspace.current_buffer.is_some() => {
+                util::group_changes(app, |app| {
+                    for _ in 0..count {
+                        run_commands(app, &coms)?;
+
+                        if !matches!(app.mode, Mode::Normal(_)) {
+                            break;
+                        }
+                    }
+
+                    Ok(())
+                })?;
+            }
+            _ => run_commands(app, &coms)?,
+        }
+    }
 
-            com(app)?;
+    Ok(())
+}
+
+// Decides whether the recorded keys amount to a change, based on the
+// resulting mode and, for normal mode keys, the buffer's initial state.
+fn update_change_recorder(app: &mut Application, initial_state: Option<(Option<usize>, usize)>) {
+    let recorder = &mut app.change_recorder;
 
-            debug!("command completed successfully");
+    match app.mode {
+        Mode::Insert | Mode::Paste | Mode::Completion(_) => recorder.start_insert_session(),
+        Mode::Normal(_) if recorder.inserting() => recorder.finish(),
+        // Wait for the text object or delimiters to which the change applies.
+        Mode::TextObject(_) | Mode::Surround(_) => (),
+        Mode::Normal(ref mode) if initial_state.is_some() => {
+            let modified = app
+                .workspace
+                .current_buffer
+                .as_ref()
+                .and_then(|buffer| Some((buffer.id, app.view.buffer_revision(buffer)?)))
+                .zip(initial_state)
+                .is_some_and(|((id, revision), (initial_id, initial_revision))| {
+                    id == initial_id && revision != initial_revision
+                });
+
+            if modified {
+                recorder.finish();
+            } else if mode.count.is_none() {
+                recorder.discard();
+            }
         }
+        _ => recorder.discard(),
+    }
+}
+
+// Runs all commands, stopping at the first error encountered, if any.
+fn run_commands(app: &mut Application, commands: &[Command]) -> Result {
+    for com in commands {
+        debug!("running command");
+
+        com(app)?;
+
+        debug!("command completed successfully");
     }
 
     Ok(())
@@ -171,6 +302,7 @@ pub fn switch_to_select_mode(app: &mut Application) -> Result {
     if let Mode::Select(ref mut mode) = app.mode {
         mode.reset(position);
     }
+    app.cursors.reset_anchors();
 
     Ok(())
 }
@@ -188,6 +320,26 @@ pub fn switch_to_select_line_mode(app: &mut Application) -> Result {
     if let Mode::SelectLine(ref mut mode) = app.mode {
         mode.reset(line);
     }
+    app.cursors.reset_anchors();
+
+    Ok(())
+}
+
+pub fn switch_to_select_block_mode(app: &mut Application) -> Result {
+    let position = *app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?
+        .cursor;
+
+    app.switch_to(ModeKey::SelectBlock);
+    if let Mode::SelectBlock(ref mut mode) = app.mode {
+        mode.reset(position);
+    }
+
+    // A block already spans several lines; it doesn't combine with other cursors.
+    app.cursors.clear();
 
     Ok(())
 }
@@ -264,6 +416,50 @@ pub fn switch_to_syntax_mode(app: &mut Application) -> Result {
     Ok(())
 }
 
+pub fn switch_to_clipboard_history_mode(app: &mut Application) -> Result {
+    // We'll need a buffer to paste into, so check before entering the mode.
+    let _ = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context("Pasting from the clipboard history requires an open buffer")?;
+    if app.clipboard.history().is_empty() {
+        bail!("The clipboard history is empty");
+    }
+
+    app.switch_to(ModeKey::ClipboardHistory);
+    let config = app.preferences.borrow().search_select_config();
+    if let Mode::ClipboardHistory(ref mut mode) = app.mode {
+        mode.reset(app.clipboard.history(), config)
+    }
+
+    commands::search_select::search(app)?;
+
+    Ok(())
+}
+
+pub fn switch_to_undo_tree_mode(app: &mut Application) -> Result {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context("Browsing undo history requires an open buffer")?;
+    app.undo_tree
+        .record(buffer, app.view.buffer_revision(buffer));
+    let current = app.undo_tree.current(buffer).unwrap_or_default();
+
+    app.switch_to(ModeKey::UndoTree);
+    let config = app.preferences.borrow().search_select_config();
+    if let Mode::UndoTree(ref mut mode) = app.mode {
+        let buffer = app.workspace.current_buffer.as_ref().context(BUFFER_MISSING)?;
+        mode.reset(app.undo_tree.states(buffer), current, config)
+    }
+
+    commands::search_select::search(app)?;
+
+    Ok(())
+}
+
 pub fn run_git_tool(app: &mut Application) -> Result {
     let mut command = app
         .preferences
@@ -370,6 +566,22 @@ pub fn suspend(app: &mut Application) -> Result {
 }
 
 pub fn exit(app: &mut Application) -> Result {
+    let modified_buffers = util::modified_buffer_paths(&mut app.workspace);
+
+    if modified_buffers.is_empty() {
+        app.switch_to(ModeKey::Exit);
+    } else {
+        // Display a confirmation prompt listing the buffers that would be lost.
+        app.switch_to(ModeKey::ConfirmExit);
+        if let Mode::ConfirmExit(ref mut mode) = app.mode {
+            mode.reset(modified_buffers);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn exit_without_saving(app: &mut Application) -> Result {
     app.switch_to(ModeKey::Exit);
 
     Ok(())
@@ -377,6 +589,8 @@ pub fn exit(app: &mut Application) -> Result {
 
 #[cfg(test)]
 mod tests {
+    use crate::commands;
+    use crate::input::Key;
     use crate::models::application::{Mode, Preferences};
     use crate::models::Application;
     use scribe::Buffer;
@@ -401,11 +615,8 @@ mod tests {
 
         let buffer_data = app.workspace.current_buffer.as_ref().unwrap().data();
         let mut lines = buffer_data.lines();
-        assert_eq!(
-            lines.nth(0),
-            Some("application::display_available_commands")
-        );
-        assert_eq!(lines.last(), Some("workspace::next_buffer"));
+        assert_eq!(lines.nth(0), Some("align::accept_delimiter"));
+        assert_eq!(lines.last(), Some("workspace::save_all"));
     }
 
     #[test]
@@ -454,6 +665,99 @@ mod tests {
         assert!(super::switch_to_path_mode(&mut app).is_err());
     }
 
+    #[test]
+    fn exit_switches_to_exit_mode_when_no_buffers_are_modified() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        app.workspace.add_buffer(Buffer::new());
+
+        super::exit(&mut app).unwrap();
+
+        assert!(matches!(app.mode, Mode::Exit));
+    }
+
+    #[test]
+    fn exit_displays_confirmation_listing_modified_buffers() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+        app.workspace.add_buffer(buffer);
+        app.workspace.add_buffer(Buffer::new());
+
+        super::exit(&mut app).unwrap();
+
+        match app.mode {
+            Mode::ConfirmExit(ref mode) => {
+                assert_eq!(mode.modified_buffers, vec![String::from("untitled")])
+            }
+            _ => panic!("Application isn't in confirm exit mode."),
+        }
+    }
+
+    #[test]
+    fn exit_without_saving_switches_to_exit_mode_with_modified_buffers() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+        app.workspace.add_buffer(buffer);
+
+        super::exit(&mut app).unwrap();
+        super::exit_without_saving(&mut app).unwrap();
+
+        assert!(matches!(app.mode, Mode::Exit));
+    }
+
+    #[test]
+    fn handle_input_repeats_commands_using_count_prefix() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\n");
+        app.workspace.add_buffer(buffer);
+
+        for key in ['1', '0', 'j'] {
+            app.view.last_key = Some(Key::Char(key));
+            super::handle_input(&mut app).unwrap();
+        }
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().cursor.line,
+            10
+        );
+        match app.mode {
+            Mode::Normal(ref mode) => assert_eq!(mode.count, None),
+            _ => panic!("Application isn't in normal mode."),
+        }
+    }
+
+    #[test]
+    fn handle_input_groups_counted_changes_as_a_single_operation() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+        app.workspace.add_buffer(buffer);
+
+        for key in ['3', '>'] {
+            app.view.last_key = Some(Key::Char(key));
+            super::handle_input(&mut app).unwrap();
+        }
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "      amp"
+        );
+
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer.as_ref().unwrap().data(), "amp");
+    }
+
+    #[test]
+    fn handle_input_leaves_leading_zero_to_its_key_binding() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+
+        app.view.last_key = Some(Key::Char('0'));
+        super::handle_input(&mut app).unwrap();
+
+        assert!(matches!(app.mode, Mode::Command(_)));
+    }
+
     #[test]
     #[serial]
     fn run_file_manager_executes_command_and_opens_path_written_to_tmp_file() {
@@ -1,13 +1,18 @@
 use crate::commands::{self, Result};
 use crate::errors::*;
 use crate::input::Key;
+use crate::models::application::modes::ShellCommandAction;
 use crate::models::application::{Application, ClipboardContent, Mode, ModeKey};
 use crate::util;
+use crate::util::column::{display_column, offset_at_column};
+use crate::util::multi_cursor;
+use crate::util::number;
 use crate::util::token::{adjacent_token_position, Direction};
 use scribe::buffer::{Buffer, Position, Range, Token};
 use std::io::Write;
 use std::mem;
 use std::process::Stdio;
+use unicode_segmentation::UnicodeSegmentation;
 
 pub fn save(app: &mut Application) -> Result {
     remove_trailing_whitespace(app)?;
@@ -44,6 +49,8 @@ pub fn save(app: &mut Application) -> Result {
                 .save()
                 .context(BUFFER_SAVE_FAILED)?;
         }
+
+        save_undo_history(app);
     } else {
         // Prompt the user to enter a path for the buffer instead of saving.
         commands::application::switch_to_path_mode(app)?;
@@ -65,6 +72,10 @@ pub fn reload(app: &mut Application) -> Result {
 }
 
 pub fn delete(app: &mut Application) -> Result {
+    multi_cursor::apply(app, delete_at_cursor)
+}
+
+fn delete_at_cursor(app: &mut Application) -> Result {
     app.workspace
         .current_buffer
         .as_mut()
@@ -119,6 +130,198 @@ pub fn copy_current_line(app: &mut Application) -> Result {
     Ok(())
 }
 
+pub fn move_line_up(app: &mut Application) -> Result {
+    let (top, bottom) = selected_lines(app)?;
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let data = buffer.data();
+    let lines = buffer_lines(&data);
+    if top == 0 || bottom >= lines.len() {
+        return Ok(());
+    }
+
+    // Move the line above the selection to below it.
+    let original_cursor = *buffer.cursor.clone();
+    buffer.start_operation_group();
+    buffer.delete_range(Range::new(
+        Position {
+            line: top - 1,
+            offset: 0,
+        },
+        Position {
+            line: top,
+            offset: 0,
+        },
+    ));
+    buffer.cursor.move_to(Position {
+        line: bottom - 1,
+        offset: lines[bottom].graphemes(true).count(),
+    });
+    buffer.insert(format!("\n{}", lines[top - 1]));
+    buffer.cursor.move_to(Position {
+        line: original_cursor.line - 1,
+        offset: original_cursor.offset,
+    });
+    if let Mode::SelectLine(ref mut mode) = app.mode {
+        mode.anchor -= 1;
+    }
+
+    end_command_group(app)
+}
+
+pub fn move_line_down(app: &mut Application) -> Result {
+    let (top, bottom) = selected_lines(app)?;
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let data = buffer.data();
+    let lines = buffer_lines(&data);
+    if bottom + 1 >= lines.len() {
+        return Ok(());
+    }
+
+    // Move the line below the selection to above it.
+    let original_cursor = *buffer.cursor.clone();
+    buffer.start_operation_group();
+    buffer.delete_range(Range::new(
+        Position {
+            line: bottom,
+            offset: lines[bottom].graphemes(true).count(),
+        },
+        Position {
+            line: bottom + 1,
+            offset: lines[bottom + 1].graphemes(true).count(),
+        },
+    ));
+    buffer.cursor.move_to(Position {
+        line: top,
+        offset: 0,
+    });
+    buffer.insert(format!("{}\n", lines[bottom + 1]));
+    buffer.cursor.move_to(Position {
+        line: original_cursor.line + 1,
+        offset: original_cursor.offset,
+    });
+    if let Mode::SelectLine(ref mut mode) = app.mode {
+        mode.anchor += 1;
+    }
+
+    end_command_group(app)
+}
+
+pub fn duplicate_line(app: &mut Application) -> Result {
+    let (top, bottom) = selected_lines(app)?;
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let data = buffer.data();
+    let lines = buffer_lines(&data);
+    if bottom >= lines.len() {
+        return Ok(());
+    }
+    let content = lines[top..=bottom].join("\n");
+    let line_count = bottom - top + 1;
+
+    // Insert the copy below the original lines, which
+    // requires a leading newline if they end the buffer.
+    let original_cursor = *buffer.cursor.clone();
+    if bottom + 1 < data.split('\n').count() {
+        buffer.cursor.move_to(Position {
+            line: bottom + 1,
+            offset: 0,
+        });
+        buffer.insert(format!("{content}\n"));
+    } else {
+        buffer.cursor.move_to(Position {
+            line: bottom,
+            offset: lines[bottom].graphemes(true).count(),
+        });
+        buffer.insert(format!("\n{content}"));
+    }
+
+    // Move the cursor and selection to the copy.
+    buffer.cursor.move_to(Position {
+        line: original_cursor.line + line_count,
+        offset: original_cursor.offset,
+    });
+    if let Mode::SelectLine(ref mut mode) = app.mode {
+        mode.anchor += line_count;
+    }
+
+    Ok(())
+}
+
+pub fn duplicate_selection(app: &mut Application) -> Result {
+    let range = match app.mode {
+        Mode::SelectLine(_) => return duplicate_line(app),
+        Mode::Select(_) => commands::selection::sel_to_range(app)?,
+        _ => bail!("A selection is required."),
+    };
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let content = buffer
+        .read(&range)
+        .context("Couldn't read selected data from buffer")?;
+
+    // Insert the copy after the original and select it.
+    let end = range.end();
+    buffer.cursor.move_to(end);
+    buffer.insert(content.clone());
+    let copy_end = match content.rsplit_once('\n') {
+        Some((leading_lines, last_line)) => Position {
+            line: end.line + leading_lines.matches('\n').count() + 1,
+            offset: last_line.graphemes(true).count(),
+        },
+        None => Position {
+            line: end.line,
+            offset: end.offset + content.graphemes(true).count(),
+        },
+    };
+    buffer.cursor.move_to(copy_end);
+    if let Mode::Select(ref mut mode) = app.mode {
+        mode.anchor = end;
+    }
+
+    Ok(())
+}
+
+// The first and last lines in select line mode, or the current line.
+fn selected_lines(app: &Application) -> std::result::Result<(usize, usize), Error> {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+
+    Ok(match app.mode {
+        Mode::SelectLine(ref mode) => (
+            mode.anchor.min(buffer.cursor.line),
+            mode.anchor.max(buffer.cursor.line),
+        ),
+        _ => (buffer.cursor.line, buffer.cursor.line),
+    })
+}
+
+// The buffer's lines, not counting the empty "line" following a trailing newline.
+fn buffer_lines(data: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = data.split('\n').collect();
+    if data.ends_with('\n') {
+        lines.pop();
+    }
+
+    lines
+}
+
 pub fn merge_next_line(app: &mut Application) -> Result {
     let buffer = app
         .workspace
@@ -178,9 +381,7 @@ pub fn merge_next_line(app: &mut Application) -> Result {
     });
     buffer.insert(merged_lines);
     buffer.cursor.move_to(target_position);
-    buffer.end_operation_group();
-
-    Ok(())
+    end_command_group(app)
 }
 
 pub fn close(app: &mut Application) -> Result {
@@ -193,13 +394,8 @@ pub fn close(app: &mut Application) -> Result {
     let confirm_mode = matches!(app.mode, Mode::Confirm(_));
 
     if unmodified || empty || confirm_mode {
-        // Clean up view-related data for the buffer.
-        app.view.forget_buffer(
-            app.workspace
-                .current_buffer
-                .as_ref()
-                .context(BUFFER_MISSING)?,
-        )?;
+        save_undo_history(app);
+        forget_current_buffer(app)?;
         app.workspace.close_current_buffer();
     } else {
         // Display a confirmation prompt before closing a modified buffer.
@@ -244,7 +440,8 @@ pub fn close_others(app: &mut Application) -> Result {
             } else if buf.modified() && !buf.data().is_empty() {
                 modified_buffer = true;
             } else {
-                app.view.forget_buffer(buf)?;
+                save_undo_history(app);
+                forget_current_buffer(app)?;
             }
         }
 
@@ -267,8 +464,8 @@ pub fn close_others(app: &mut Application) -> Result {
 }
 
 pub fn close_others_confirm(app: &mut Application) -> Result {
-    if let Some(buf) = app.workspace.current_buffer.as_ref() {
-        app.view.forget_buffer(buf)?;
+    if app.workspace.current_buffer.is_some() {
+        forget_current_buffer(app)?;
     }
     app.workspace.close_current_buffer();
     commands::application::switch_to_normal_mode(app)?;
@@ -276,8 +473,27 @@ pub fn close_others_confirm(app: &mut Application) -> Result {
     Ok(())
 }
 
+// Cleans up view-related data and undo history for the current buffer.
+fn forget_current_buffer(app: &mut Application) -> Result {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    app.view.forget_buffer(buffer)?;
+    app.undo_tree.forget(buffer);
+    app.word_counts.forget(buffer);
+
+    Ok(())
+}
+
 pub fn backspace(app: &mut Application) -> Result {
+    multi_cursor::apply(app, backspace_at_cursor)
+}
+
+fn backspace_at_cursor(app: &mut Application) -> Result {
     let mut outdent = false;
+    let pairs = auto_pairs(app);
 
     if let Some(buffer) = app.workspace.current_buffer.as_mut() {
         if buffer.cursor.offset == 0 {
@@ -295,6 +511,12 @@ pub fn backspace(app: &mut Application) -> Result {
             } else {
                 buffer.cursor.move_left();
                 buffer.delete();
+
+                // Remove the closing half of an empty pair, too.
+                let (previous, next) = adjacent_chars(current_line, buffer.cursor.offset + 1);
+                if pairs.iter().any(|&pair| Some(pair) == previous.zip(next)) {
+                    buffer.delete();
+                }
             }
         }
     } else {
@@ -308,10 +530,24 @@ pub fn backspace(app: &mut Application) -> Result {
 }
 
 pub fn insert_char(app: &mut Application) -> Result {
+    multi_cursor::apply(app, insert_char_at_cursor)?;
+    check_indent_patterns(app)
+}
+
+fn insert_char_at_cursor(app: &mut Application) -> Result {
+    let closing_char = auto_pair_closing_char(app)?;
+
     if let Some(buffer) = app.workspace.current_buffer.as_mut() {
         if let Some(Key::Char(character)) = *app.view.last_key() {
-            // TODO: Drop explicit call to to_string().
-            buffer.insert(character.to_string());
+            match closing_char {
+                // Type over the existing closing character.
+                AutoPair::Skip => (),
+                AutoPair::Insert(closing_char) => {
+                    buffer.insert(format!("{character}{closing_char}"));
+                }
+                // TODO: Drop explicit call to to_string().
+                AutoPair::None => buffer.insert(character.to_string()),
+            }
             buffer.cursor.move_right();
         } else {
             bail!("No character to insert");
@@ -319,11 +555,88 @@ pub fn insert_char(app: &mut Application) -> Result {
     } else {
         bail!(BUFFER_MISSING);
     }
+    if !matches!(app.mode, Mode::Paste) {
+        outdent_closing_line(app)?;
+    }
     commands::view::scroll_to_cursor(app)?;
 
     Ok(())
 }
 
+enum AutoPair {
+    Insert(char),
+    Skip,
+    None,
+}
+
+// Decides how the typed character is paired, if at all, based on
+// the pairs configured for the buffer and its surrounding content.
+fn auto_pair_closing_char(app: &mut Application) -> std::result::Result<AutoPair, Error> {
+    let pairs = auto_pairs(app);
+    let character = match *app.view.last_key() {
+        Some(Key::Char(character)) if !pairs.is_empty() => character,
+        _ => return Ok(AutoPair::None),
+    };
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    let data = buffer.data();
+    let current_line = data.lines().nth(buffer.cursor.line).unwrap_or("");
+    let (previous, next) = adjacent_chars(current_line, buffer.cursor.offset);
+
+    if next == Some(character) && pairs.iter().any(|&(_, close)| close == character) {
+        return Ok(AutoPair::Skip);
+    }
+
+    let Some(&(open, close)) = pairs.iter().find(|&&(open, _)| open == character) else {
+        return Ok(AutoPair::None);
+    };
+
+    // Only pair up when the closing character won't end up
+    // against a word, or a quote after one (e.g. an apostrophe).
+    let next_is_boundary = next
+        .is_none_or(|next| next.is_whitespace() || pairs.iter().any(|&(_, close)| close == next));
+    let quote_after_word = open == close && previous.is_some_and(|c| c.is_alphanumeric());
+    if !next_is_boundary || quote_after_word {
+        return Ok(AutoPair::None);
+    }
+
+    let state = app.view.line_state(buffer, buffer.cursor.line)?;
+    let lexemes = util::bracket::lexemes(
+        &data,
+        state,
+        buffer.cursor.line + 1,
+        &app.workspace.syntax_set,
+    )?;
+    if util::bracket::in_string_or_comment(&lexemes, &buffer.cursor)? {
+        return Ok(AutoPair::None);
+    }
+
+    Ok(AutoPair::Insert(close))
+}
+
+// The auto-pairs configured for the current buffer; disabled in paste mode.
+fn auto_pairs(app: &Application) -> Vec<(char, char)> {
+    match (&app.mode, app.workspace.current_buffer.as_ref()) {
+        (Mode::Paste, _) | (_, None) => Vec::new(),
+        (_, Some(buffer)) => app.preferences.borrow().auto_pairs(buffer.path.as_ref()),
+    }
+}
+
+// The characters on either side of an offset in a line.
+fn adjacent_chars(line: &str, offset: usize) -> (Option<char>, Option<char>) {
+    let mut graphemes = line.graphemes(true).skip(offset.saturating_sub(1));
+    let first_char = |grapheme: Option<&str>| grapheme.and_then(|g| g.chars().next());
+
+    if offset == 0 {
+        (None, first_char(graphemes.next()))
+    } else {
+        (first_char(graphemes.next()), first_char(graphemes.next()))
+    }
+}
+
 pub fn display_current_scope(app: &mut Application) -> Result {
     let scope_display_buffer = {
         let mut scope_stack = None;
@@ -371,56 +684,258 @@ pub fn display_current_scope(app: &mut Application) -> Result {
 /// Also performs automatic indentation, basing the indent off
 /// of the previous line's leading whitespace.
 pub fn insert_newline(app: &mut Application) -> Result {
-    if let Some(buffer) = app.workspace.current_buffer.as_mut() {
-        // Insert the newline character.
-        buffer.insert("\n");
+    multi_cursor::apply(app, insert_newline_at_cursor)?;
+    check_indent_patterns(app)
+}
 
-        match app.mode {
-            Mode::Paste => {
-                buffer.cursor.move_down();
-                buffer.cursor.move_to_start_of_line();
-            }
-            _ => {
-                // Get the cursor position before moving it to the start of the new line.
-                let position = buffer.cursor.clone();
-                buffer.cursor.move_down();
-                buffer.cursor.move_to_start_of_line();
-
-                // Get a slice of the buffer up to and including the current line.
-                let data = buffer.data();
-                let end_of_current_line = data
-                    .lines()
-                    .nth(position.line)
-                    .map(|l| (l.as_ptr() as usize) + l.len())
-                    .unwrap();
-                let offset = end_of_current_line - (data.as_str().as_ptr() as usize);
-                let (previous_content, _) = data.split_at(offset);
-
-                // Searching backwards, copy the nearest non-blank line's indent content.
-                let nearest_non_blank_line =
-                    previous_content.lines().rev().find(|line| !line.is_empty());
-                let indent_content = match nearest_non_blank_line {
-                    Some(line) => line.chars().take_while(|&c| c.is_whitespace()).collect(),
-                    None => String::new(),
-                };
-
-                // Insert and move to the end of the indent content.
-                let indent_length = indent_content.chars().count();
-                buffer.insert(indent_content);
-                buffer.cursor.move_to(Position {
-                    line: position.line + 1,
-                    offset: indent_length,
-                });
+fn insert_newline_at_cursor(app: &mut Application) -> Result {
+    // Paste mode content carries its own indentation.
+    let indent = match app.mode {
+        Mode::Paste => None,
+        _ => {
+            // The line being ended is complete, so it can close a block.
+            outdent_closing_line(app)?;
+            Some(newline_indent(app)?)
+        }
+    };
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let position = *buffer.cursor.clone();
+
+    // Insert the newline character and move to the start of the new line.
+    buffer.insert("\n");
+    buffer.cursor.move_down();
+    buffer.cursor.move_to_start_of_line();
+
+    if let Some((indent_content, closing_indent_content)) = indent {
+        // Insert and move to the end of the indent content, moving a closing
+        // bracket that followed the cursor onto a line of its own.
+        let indent_length = indent_content.chars().count();
+        match closing_indent_content {
+            Some(closing_indent_content) => {
+                buffer.insert(format!("{indent_content}\n{closing_indent_content}"))
             }
+            None => buffer.insert(indent_content),
         }
-    } else {
-        bail!(BUFFER_MISSING);
+        buffer.cursor.move_to(Position {
+            line: position.line + 1,
+            offset: indent_length,
+        });
     }
     commands::view::scroll_to_cursor(app)?;
 
     Ok(())
 }
 
+// The indentation for a line inserted at the cursor, along with the
+// indentation for a closing bracket following the cursor, if there is one
+// and it closes a block that the new line is indented within.
+fn newline_indent(app: &Application) -> std::result::Result<(String, Option<String>), Error> {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    let data = buffer.data();
+    let current_line = data.lines().nth(buffer.cursor.line).unwrap_or("");
+    let content_before_cursor: String = current_line
+        .graphemes(true)
+        .take(buffer.cursor.offset)
+        .collect();
+    let (indent_content, increased) = indent_after(app, &buffer.cursor)?;
+
+    let closes_block = current_line[content_before_cursor.len()..]
+        .trim_start()
+        .graphemes(true)
+        .next()
+        .is_some_and(util::bracket::is_closing_bracket);
+    let closing_indent_content = if increased && closes_block {
+        Some(
+            content_before_cursor
+                .chars()
+                .take_while(|&c| c.is_whitespace())
+                .collect(),
+        )
+    } else {
+        None
+    };
+
+    Ok((indent_content, closing_indent_content))
+}
+
+// The indentation for a line following the content before the specified
+// position. Searching backwards, the nearest non-blank line's indent content
+// is used, increased by a level if that line opens a block. Whether it was
+// increased is returned along with it.
+fn indent_after(
+    app: &Application,
+    position: &Position,
+) -> std::result::Result<(String, bool), Error> {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    let data = buffer.data();
+    let content_before_position: String = data
+        .lines()
+        .nth(position.line)
+        .unwrap_or("")
+        .graphemes(true)
+        .take(position.offset)
+        .collect();
+    let mut lines: Vec<&str> = data.lines().take(position.line).collect();
+    lines.push(&content_before_position);
+    let nearest_non_blank_line = lines
+        .into_iter()
+        .enumerate()
+        .rev()
+        .find(|(_, line)| !line.is_empty());
+    let Some((line_number, line)) = nearest_non_blank_line else {
+        return Ok((String::new(), false));
+    };
+    let mut indent_content: String = line.chars().take_while(|&c| c.is_whitespace()).collect();
+
+    let preferences = app.preferences.borrow();
+    let pattern = preferences
+        .increase_indent_pattern(buffer.path.as_ref())
+        .unwrap_or_default();
+    // Resume parsing from the view's cached state, rather than the buffer's start.
+    let lexemes = app
+        .view
+        .line_state(buffer, line_number)
+        .and_then(|state| {
+            util::bracket::lexemes(&data, state, line_number + 1, &app.workspace.syntax_set)
+        })
+        .ok();
+    let increased =
+        util::indent::increases_indent(lexemes.as_deref(), line_number, line, pattern.as_ref())?;
+    if increased {
+        indent_content.push_str(&preferences.tab_content(buffer.path.as_ref()));
+    }
+
+    Ok((indent_content, increased))
+}
+
+// The content of the cursor's line, read without copying the rest of the
+// buffer, unless it's the last line, which has no successor to read up to.
+fn current_line(buffer: &Buffer) -> String {
+    let line = buffer.cursor.line;
+    let range = Range::new(
+        Position { line, offset: 0 },
+        Position {
+            line: line + 1,
+            offset: 0,
+        },
+    );
+
+    match buffer.read(&range) {
+        Some(content) => content.trim_end_matches(['\r', '\n']).to_string(),
+        None => buffer.data().lines().nth(line).unwrap_or("").to_string(),
+    }
+}
+
+// Persisting undo history is best-effort; failing to write it is reported,
+// but mustn't get in the way of saving or closing the buffer.
+fn save_undo_history(app: &mut Application) {
+    if let Err(e) = util::undo_history::save(app) {
+        app.error = Some(e);
+    }
+}
+
+// Reports invalid indent patterns for the current buffer's type. They're
+// ignored while indenting, so that they don't get in the way of typing.
+fn check_indent_patterns(app: &Application) -> Result {
+    if matches!(app.mode, Mode::Paste) {
+        return Ok(());
+    }
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    let preferences = app.preferences.borrow();
+    preferences.increase_indent_pattern(buffer.path.as_ref())?;
+    preferences.decrease_indent_pattern(buffer.path.as_ref())?;
+
+    Ok(())
+}
+
+// Outdents the current line by a level once it closes a block (e.g. a closing
+// bracket typed on an otherwise blank line), provided it's still indented
+// within the block, the way a newline following the block's start would be.
+fn outdent_closing_line(app: &mut Application) -> Result {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    let line_number = buffer.cursor.line;
+    let line = current_line(buffer);
+    let line = line.as_str();
+    let pattern = app
+        .preferences
+        .borrow()
+        .decrease_indent_pattern(buffer.path.as_ref())
+        .unwrap_or_default();
+
+    // Typing a word character may still be extending a keyword the pattern
+    // matches (e.g. "end" in "endpoint"), so keywords are only considered
+    // once followed by a non-word character, or a newline.
+    let extending_word = matches!(
+        *app.view.last_key(),
+        Some(Key::Char(c)) if c.is_alphanumeric() || c == '_'
+    );
+    let pattern = pattern.filter(|_| !extending_word);
+    if !util::indent::decreases_indent(line, pattern.as_ref()) {
+        return Ok(());
+    }
+
+    let current_indent: String = line.chars().take_while(|&c| c.is_whitespace()).collect();
+    let (block_indent, _) = indent_after(
+        app,
+        &Position {
+            line: line_number,
+            offset: 0,
+        },
+    )?;
+    let tab_content = app.preferences.borrow().tab_content(buffer.path.as_ref());
+    let Some(outdented) = block_indent.strip_suffix(&tab_content) else {
+        return Ok(());
+    };
+    if current_indent != block_indent {
+        return Ok(());
+    }
+
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let cursor_offset = buffer.cursor.offset;
+    let current_indent_length = current_indent.chars().count();
+    let outdented_length = outdented.chars().count();
+    buffer.delete_range(Range::new(
+        Position {
+            line: line_number,
+            offset: outdented_length,
+        },
+        Position {
+            line: line_number,
+            offset: current_indent_length,
+        },
+    ));
+    buffer.cursor.move_to(Position {
+        line: line_number,
+        offset: cursor_offset.saturating_sub(current_indent_length - outdented_length),
+    });
+
+    Ok(())
+}
+
 pub fn indent_line(app: &mut Application) -> Result {
     let buffer = app
         .workspace
@@ -437,32 +952,25 @@ pub fn indent_line(app: &mut Application) -> Result {
         _ => *buffer.cursor.clone(),
     };
 
-    // Get the range of lines we'll outdent based on
-    // either the current selection or cursor line.
-    let lines = match app.mode {
-        Mode::SelectLine(ref mode) => {
-            if mode.anchor >= buffer.cursor.line {
-                buffer.cursor.line..mode.anchor + 1
-            } else {
-                mode.anchor..buffer.cursor.line + 1
-            }
-        }
-        _ => buffer.cursor.line..buffer.cursor.line + 1,
-    };
+    // Get the positions we'll indent from based on either the current
+    // selection or cursor line; blocks are indented from their left edge.
+    let positions = indent_positions(app)?;
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
 
-    // Move to the start of the current line and
-    // insert the content, as a single operation.
+    // Move to each position and insert the content, as a single operation.
     buffer.start_operation_group();
-    for line in lines {
-        buffer.cursor.move_to(Position { line, offset: 0 });
+    for position in positions {
+        buffer.cursor.move_to(position);
         buffer.insert(tab_content.clone());
     }
-    buffer.end_operation_group();
 
     // Move to the original position, shifted to compensate for the indent.
     buffer.cursor.move_to(target_position);
-
-    Ok(())
+    end_command_group(app)
 }
 
 pub fn outdent_line(app: &mut Application) -> Result {
@@ -476,24 +984,21 @@ pub fn outdent_line(app: &mut Application) -> Result {
     // FIXME: Determine this based on file type and/or user config.
     let data = buffer.data();
 
-    // Get the range of lines we'll outdent based on
-    // either the current selection or cursor line.
-    let lines = match app.mode {
-        Mode::SelectLine(ref mode) => {
-            if mode.anchor >= buffer.cursor.line {
-                buffer.cursor.line..mode.anchor + 1
-            } else {
-                mode.anchor..buffer.cursor.line + 1
-            }
-        }
-        _ => buffer.cursor.line..buffer.cursor.line + 1,
-    };
+    // Get the positions we'll outdent from based on either the current
+    // selection or cursor line; blocks are outdented from their left edge.
+    let positions = indent_positions(app)?;
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
 
     // Group the individual outdent operations as one.
     buffer.start_operation_group();
 
-    for line in lines {
-        if let Some(content) = data.lines().nth(line) {
+    for Position { line, offset } in positions {
+        if let Some(line_content) = data.lines().nth(line) {
+            let content: String = line_content.graphemes(true).skip(offset).collect();
             let mut space_char_count = 0;
 
             // Check for leading whitespace.
@@ -518,10 +1023,10 @@ pub fn outdent_line(app: &mut Application) -> Result {
             // if we found any, and adjust cursor accordingly.
             if space_char_count > 0 {
                 buffer.delete_range(Range::new(
-                    Position { line, offset: 0 },
+                    Position { line, offset },
                     Position {
                         line,
-                        offset: space_char_count,
+                        offset: offset + space_char_count,
                     },
                 ));
 
@@ -538,9 +1043,138 @@ pub fn outdent_line(app: &mut Application) -> Result {
     }
 
     // Finish grouping the individual outdent operations as one.
-    buffer.end_operation_group();
+    end_command_group(app)
+}
 
-    Ok(())
+// The start of each line to be indented or outdented: those in a line
+// selection, or the current line. Blocks use their left edge instead,
+// skipping lines that end before it.
+fn indent_positions(app: &Application) -> std::result::Result<Vec<Position>, Error> {
+    if let Mode::SelectBlock(_) = app.mode {
+        let buffer = app
+            .workspace
+            .current_buffer
+            .as_ref()
+            .context(BUFFER_MISSING)?;
+        let data = buffer.data();
+        let lines: Vec<&str> = data.lines().collect();
+
+        return Ok(commands::selection::block_ranges(app)?
+            .into_iter()
+            .map(|range| range.start())
+            .filter(|position| {
+                lines
+                    .get(position.line)
+                    .is_some_and(|line| line.graphemes(true).count() > position.offset)
+            })
+            .collect());
+    }
+
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    let lines = match app.mode {
+        Mode::SelectLine(ref mode) => {
+            if mode.anchor >= buffer.cursor.line {
+                buffer.cursor.line..mode.anchor + 1
+            } else {
+                mode.anchor..buffer.cursor.line + 1
+            }
+        }
+        _ => buffer.cursor.line..buffer.cursor.line + 1,
+    };
+
+    Ok(lines.map(|line| Position { line, offset: 0 }).collect())
+}
+
+pub fn increment_number(app: &mut Application) -> Result {
+    multi_cursor::apply(app, |app| change_numbers(app, 1, false))
+}
+
+pub fn decrement_number(app: &mut Application) -> Result {
+    multi_cursor::apply(app, |app| change_numbers(app, -1, false))
+}
+
+pub fn increment_numbers_progressively(app: &mut Application) -> Result {
+    change_numbers(app, 1, true)
+}
+
+pub fn decrement_numbers_progressively(app: &mut Application) -> Result {
+    change_numbers(app, -1, true)
+}
+
+// Adds the amount to the number at or after the cursor or, in select line
+// mode, to the first number on each selected line. Progressive changes add
+// the amount once more for each line, producing a sequence (e.g. 1, 2, 3).
+fn change_numbers(app: &mut Application, amount: i128, progressive: bool) -> Result {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    let original_cursor = *buffer.cursor.clone();
+    let positions: Vec<Position> = match app.mode {
+        Mode::SelectLine(ref mode) => (mode.anchor.min(original_cursor.line)
+            ..=mode.anchor.max(original_cursor.line))
+            .map(|line| Position { line, offset: 0 })
+            .collect(),
+        _ => vec![original_cursor],
+    };
+
+    util::group_changes(app, |app| {
+        let buffer = app
+            .workspace
+            .current_buffer
+            .as_mut()
+            .context(BUFFER_MISSING)?;
+        let data = buffer.data();
+        let lines: Vec<&str> = data.split('\n').collect();
+        let mut changes = 0;
+
+        for position in positions {
+            let line = position.line;
+            let Some(number) = number::find(lines[line], position.offset) else {
+                continue;
+            };
+
+            changes += 1;
+            let step = if progressive { changes } else { 1 };
+            let content = number
+                .add(amount * step)
+                .context("Can't change the number beyond its range")?;
+
+            let start = Position {
+                line,
+                offset: number.start,
+            };
+            buffer.delete_range(Range::new(
+                start,
+                Position {
+                    line,
+                    offset: number.end,
+                },
+            ));
+            buffer.cursor.move_to(start);
+            buffer.insert(content);
+        }
+
+        if changes == 0 {
+            bail!("No number found at or after the cursor");
+        }
+
+        // Lines keep their place, so the selection stays intact; a lone
+        // number is left with the cursor at its start.
+        if let Mode::SelectLine(_) = app.mode {
+            buffer.cursor.move_to(Position {
+                line: original_cursor.line,
+                offset: 0,
+            });
+        }
+
+        Ok(())
+    })
 }
 
 pub fn toggle_line_comment(app: &mut Application) -> Result {
@@ -626,12 +1260,10 @@ pub fn toggle_line_comment(app: &mut Application) -> Result {
     } else {
         remove_line_comment(buffer, &lines, &comment_prefix);
     }
-    buffer.end_operation_group();
 
     // Restore original cursor
     buffer.cursor.move_to(original_cursor);
-
-    Ok(())
+    end_command_group(app)
 }
 
 fn add_line_comment(buffer: &mut Buffer, lines: &[(usize, &str)], offset: usize, prefix: &str) {
@@ -713,6 +1345,11 @@ pub fn start_command_group(app: &mut Application) -> Result {
 }
 
 pub fn end_command_group(app: &mut Application) -> Result {
+    // A held group spans several commands; leave it open.
+    if app.command_group_held {
+        return Ok(());
+    }
+
     app.workspace
         .current_buffer
         .as_mut()
@@ -728,6 +1365,9 @@ pub fn undo(app: &mut Application) -> Result {
         .as_mut()
         .context(BUFFER_MISSING)?
         .undo();
+
+    // Undoing isn't a change worth repeating.
+    app.change_recorder.discard();
     commands::view::scroll_to_cursor(app).context("Couldn't scroll to cursor after undoing.")
 }
 
@@ -737,12 +1377,66 @@ pub fn redo(app: &mut Application) -> Result {
         .as_mut()
         .context(BUFFER_MISSING)?
         .redo();
+    app.change_recorder.discard();
+    commands::view::scroll_to_cursor(app).context("Couldn't scroll to cursor after redoing.")
+}
+
+pub fn undo_earlier(app: &mut Application) -> Result {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    app.undo_tree.earlier(buffer)?;
+    app.change_recorder.discard();
+    commands::view::scroll_to_cursor(app).context("Couldn't scroll to cursor after undoing.")
+}
+
+pub fn undo_later(app: &mut Application) -> Result {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    app.undo_tree.later(buffer)?;
+    app.change_recorder.discard();
     commands::view::scroll_to_cursor(app).context("Couldn't scroll to cursor after redoing.")
 }
 
+pub fn repeat_last_change(app: &mut Application) -> Result {
+    let keys = app.change_recorder.last_change().to_vec();
+    if keys.is_empty() {
+        bail!("No change to repeat");
+    }
+
+    // Replay the change's keys as a single operation,
+    // restoring the key that triggered the replay afterwards.
+    let last_key = app.view.last_key.take();
+    app.change_recorder.replaying = true;
+    let result = util::group_changes(app, |app| {
+        for key in keys {
+            app.view.last_key = Some(key);
+            commands::application::handle_input(app)?;
+        }
+
+        Ok(())
+    });
+    app.change_recorder.replaying = false;
+    app.view.last_key = last_key;
+
+    // Don't let the replay replace the change it repeated.
+    app.change_recorder.discard();
+
+    result
+}
+
 pub fn paste(app: &mut Application) -> Result {
+    util::group_changes(app, |app| multi_cursor::apply(app, paste_at_cursor))
+}
+
+fn paste_at_cursor(app: &mut Application) -> Result {
     let insert_below = match app.mode {
-        Mode::Select(_) | Mode::SelectLine(_) | Mode::Search(_) => {
+        Mode::Select(_) | Mode::SelectLine(_) | Mode::SelectBlock(_) | Mode::Search(_) => {
             commands::selection::delete(app)
                 .context("Couldn't delete selection prior to pasting.")?;
             false
@@ -786,6 +1480,10 @@ pub fn paste(app: &mut Application) -> Result {
                     buffer.insert(content.clone());
                 }
             }
+            ClipboardContent::BlockColumn(ref content) => {
+                let tab_width = app.preferences.borrow().tab_width(buffer.path.as_ref());
+                paste_block_column(buffer, content, tab_width);
+            }
             ClipboardContent::None => (),
         }
     } else {
@@ -796,6 +1494,48 @@ pub fn paste(app: &mut Application) -> Result {
     Ok(())
 }
 
+// Inserts each row of the content on consecutive lines, starting at the cursor's
+// on-screen column. Short lines are padded to reach it, and lines are appended
+// to the end of the buffer if there aren't enough of them.
+fn paste_block_column(buffer: &mut Buffer, content: &str, tab_width: usize) {
+    let original_cursor_position = *buffer.cursor.clone();
+    let data = buffer.data();
+    let lines: Vec<&str> = data.split('\n').collect();
+    let column = display_column(
+        lines[original_cursor_position.line],
+        original_cursor_position.offset,
+        tab_width,
+    );
+    let (existing_rows, new_rows): (Vec<_>, Vec<_>) = content
+        .split('\n')
+        .enumerate()
+        .map(|(index, row)| (original_cursor_position.line + index, row))
+        .partition(|(line, _)| *line < lines.len());
+
+    // Append missing lines first, while the last line's length is still known.
+    if !new_rows.is_empty() {
+        let last_line = lines.len() - 1;
+        let appended: String = new_rows
+            .iter()
+            .map(|(_, row)| format!("\n{}{}", " ".repeat(column), row))
+            .collect();
+        buffer.cursor.move_to(Position {
+            line: last_line,
+            offset: lines[last_line].graphemes(true).count(),
+        });
+        buffer.insert(appended);
+    }
+
+    for (line, row) in existing_rows {
+        let offset = offset_at_column(lines[line], column, tab_width);
+        let padding = column.saturating_sub(display_column(lines[line], offset, tab_width));
+        buffer.cursor.move_to(Position { line, offset });
+        buffer.insert(format!("{}{}", " ".repeat(padding), row));
+    }
+
+    buffer.cursor.move_to(original_cursor_position);
+}
+
 pub fn paste_above(app: &mut Application) -> Result {
     let buffer = app
         .workspace
@@ -819,6 +1559,21 @@ pub fn paste_above(app: &mut Application) -> Result {
     Ok(())
 }
 
+/// Prompts for a shell command, whose output is inserted at the cursor.
+pub fn insert_command_output(app: &mut Application) -> Result {
+    app.workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+
+    app.switch_to(ModeKey::ShellCommand);
+    if let Mode::ShellCommand(ref mut mode) = app.mode {
+        mode.reset(ShellCommandAction::Insert);
+    }
+
+    Ok(())
+}
+
 pub fn remove_trailing_whitespace(app: &mut Application) -> Result {
     let buffer = app
         .workspace
@@ -922,6 +1677,10 @@ pub fn ensure_trailing_newline(app: &mut Application) -> Result {
 }
 
 pub fn insert_tab(app: &mut Application) -> Result {
+    multi_cursor::apply(app, insert_tab_at_cursor)
+}
+
+fn insert_tab_at_cursor(app: &mut Application) -> Result {
     let buffer = app
         .workspace
         .current_buffer
@@ -990,15 +1749,18 @@ pub fn format(app: &mut Application) -> Result {
 #[cfg(test)]
 mod tests {
     use crate::commands;
+    use crate::commands::test_support::{press_keys, set_up_application_at};
+    use crate::input::Key;
     use crate::models::application::{ClipboardContent, Mode, Preferences};
     use crate::models::Application;
+    use crate::util;
     use scribe::buffer::Position;
     use scribe::Buffer;
     use serial_test::serial;
     use std::env;
     use std::fs::File;
     use std::io::Write;
-    use std::path::Path;
+    use std::path::{Path, PathBuf};
     use yaml_rust::yaml::YamlLoader;
 
     #[test]
@@ -1200,6 +1962,100 @@ mod tests {
         );
     }
 
+    #[test]
+    fn indent_line_works_in_select_block_mode() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("a b\na\na c");
+        buffer.cursor.move_to(Position { line: 0, offset: 2 });
+
+        // Now that we've set up the buffer, add it to the
+        // application, select a block, and call the command.
+        app.workspace.add_buffer(buffer);
+        commands::application::switch_to_select_block_mode(&mut app).unwrap();
+        commands::cursor::move_down(&mut app).unwrap();
+        commands::cursor::move_down(&mut app).unwrap();
+        super::indent_line(&mut app).unwrap();
+
+        // Ensure that lines reaching the block are indented from its left edge.
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "a   b
+a
+a   c"
+        );
+    }
+
+    #[test]
+    fn increment_number_changes_the_number_after_the_cursor() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("let amp = [0x0f, 009];");
+        buffer.cursor.move_to(Position { line: 0, offset: 4 });
+        app.workspace.add_buffer(buffer);
+
+        super::increment_number(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "let amp = [0x10, 009];"
+        );
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position {
+                line: 0,
+                offset: 11
+            }
+        );
+
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position {
+                line: 0,
+                offset: 17,
+            });
+        super::decrement_number(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "let amp = [0x10, 008];"
+        );
+    }
+
+    #[test]
+    fn increment_numbers_progressively_numbers_selected_lines_as_a_single_operation() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("A = 0,\nB = 0,\n// C\nD = 0,");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::cursor::move_to_last_line(&mut app).unwrap();
+        super::increment_numbers_progressively(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "A = 1,\nB = 2,\n// C\nD = 3,"
+        );
+
+        super::undo(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "A = 0,\nB = 0,\n// C\nD = 0,"
+        );
+    }
+
+    #[test]
+    fn increment_number_raises_an_error_without_a_number() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp 1\neditor");
+        buffer.cursor.move_to(Position { line: 1, offset: 0 });
+        app.workspace.add_buffer(buffer);
+
+        assert!(super::increment_number(&mut app).is_err());
+    }
+
     #[test]
     fn indent_line_moves_cursor_in_insert_mode() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -1687,30 +2543,129 @@ mod tests {
         commands::cursor::move_down(&mut app).unwrap();
         commands::buffer::paste(&mut app).unwrap();
 
-        // Ensure that the clipboard contents are pasted to the line below.
+        // Ensure that the clipboard contents are pasted to the line below.
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp\neditor\namp\n"
+        );
+    }
+
+    #[test]
+    fn backspace_outdents_line_if_line_is_whitespace() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\n        ");
+        buffer.cursor.move_to(Position { line: 2, offset: 8 });
+
+        // Now that we've set up the buffer, add it
+        // to the application and run the command.
+        app.workspace.add_buffer(buffer);
+        commands::buffer::backspace(&mut app).unwrap();
+
+        // Ensure that the clipboard contents are pasted to the line below.
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp\neditor\n      "
+        );
+    }
+
+    #[test]
+    fn move_line_up_swaps_the_current_line_with_the_one_above_as_a_single_operation() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer\n");
+        buffer.cursor.move_to(Position { line: 2, offset: 3 });
+        app.workspace.add_buffer(buffer);
+
+        super::move_line_up(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp\nbuffer\neditor\n"
+        );
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position { line: 1, offset: 3 }
+        );
+
+        super::undo(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp\neditor\nbuffer\n"
+        );
+    }
+
+    #[test]
+    fn move_line_down_moves_selected_lines_and_the_selection() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::cursor::move_down(&mut app).unwrap();
+        super::move_line_down(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "buffer\namp\neditor"
+        );
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().cursor.line,
+            2
+        );
+        match app.mode {
+            Mode::SelectLine(ref mode) => assert_eq!(mode.anchor, 1),
+            _ => panic!("Application isn't in select line mode."),
+        }
+
+        // Moving past the last line does nothing.
+        super::move_line_down(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "buffer\namp\neditor"
+        );
+    }
+
+    #[test]
+    fn duplicate_line_inserts_a_copy_below_the_last_line() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor");
+        buffer.cursor.move_to(Position { line: 1, offset: 2 });
+        app.workspace.add_buffer(buffer);
+
+        super::duplicate_line(&mut app).unwrap();
         assert_eq!(
             app.workspace.current_buffer.as_ref().unwrap().data(),
-            "amp\neditor\namp\n"
+            "amp\neditor\neditor"
+        );
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position { line: 2, offset: 2 }
         );
     }
 
     #[test]
-    fn backspace_outdents_line_if_line_is_whitespace() {
+    fn duplicate_selection_inserts_and_selects_a_copy_after_the_selection() {
         let mut app = Application::new(&Vec::new()).unwrap();
         let mut buffer = Buffer::new();
-        buffer.insert("amp\neditor\n        ");
-        buffer.cursor.move_to(Position { line: 2, offset: 8 });
-
-        // Now that we've set up the buffer, add it
-        // to the application and run the command.
+        buffer.insert("amp editor");
         app.workspace.add_buffer(buffer);
-        commands::buffer::backspace(&mut app).unwrap();
 
-        // Ensure that the clipboard contents are pasted to the line below.
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        commands::cursor::move_to_start_of_next_token(&mut app).unwrap();
+        super::duplicate_selection(&mut app).unwrap();
         assert_eq!(
             app.workspace.current_buffer.as_ref().unwrap().data(),
-            "amp\neditor\n      "
+            "amp amp editor"
+        );
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position { line: 0, offset: 8 }
         );
+        match app.mode {
+            Mode::Select(ref mode) => assert_eq!(mode.anchor, Position { line: 0, offset: 4 }),
+            _ => panic!("Application isn't in select mode."),
+        }
     }
 
     #[test]
@@ -1875,9 +2830,9 @@ mod tests {
             "editor"
         );
 
-        // TODO: Ensure that the operation is treated atomically.
-        // commands::buffer::undo(&mut app);
-        // assert_eq!(app.workspace.current_buffer.as_ref().unwrap().data(), "amp");
+        // Ensure that the operation is treated atomically.
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer.as_ref().unwrap().data(), "amp");
     }
 
     #[test]
@@ -1907,6 +2862,35 @@ mod tests {
         // assert_eq!(app.workspace.current_buffer.as_ref().unwrap().data(), "amp");
     }
 
+    #[test]
+    #[serial]
+    fn paste_with_block_column_content_inserts_rows_at_the_cursor_column() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\na");
+        buffer.cursor.move_to(Position { line: 0, offset: 2 });
+        app.clipboard
+            .set_content(ClipboardContent::BlockColumn("1\n2\n3".to_string()))
+            .unwrap();
+
+        // Now that we've set up the buffer,
+        // add it to the application and paste.
+        app.workspace.add_buffer(buffer);
+        commands::buffer::paste(&mut app).unwrap();
+
+        // Ensure that short lines are padded and missing lines are added.
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "am1p
+a 2
+  3"
+        );
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position { line: 0, offset: 2 }
+        );
+    }
+
     #[test]
     #[serial]
     fn paste_above_inserts_clipboard_contents_on_a_new_line_above() {
@@ -1983,6 +2967,28 @@ mod tests {
         assert!(app.workspace.current_buffer.as_ref().is_none());
     }
 
+    #[test]
+    fn close_and_close_others_stop_tracking_closed_buffers_undo_history() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        app.workspace.close_current_buffer();
+        let mut closed_buffers = Vec::new();
+        for _ in 0..3 {
+            app.workspace.add_buffer(Buffer::new());
+            let buffer = app.workspace.current_buffer.as_ref().unwrap();
+            app.undo_tree.track(buffer, None);
+            closed_buffers.push(buffer.id);
+        }
+
+        commands::buffer::close_others(&mut app).unwrap();
+        commands::buffer::close(&mut app).unwrap();
+
+        for id in closed_buffers {
+            let mut buffer = Buffer::new();
+            buffer.id = id;
+            assert!(app.undo_tree.current(&buffer).is_none());
+        }
+    }
+
     #[test]
     fn close_others_skips_confirmation_when_all_other_buffers_are_empty_or_unmodified() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -2329,4 +3335,349 @@ mod tests {
             Position { line: 2, offset: 0 }
         );
     }
+
+    #[test]
+    fn repeat_last_change_replays_a_normal_mode_change_with_its_count() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("a\nb\nc\nd\ne\nf\n");
+        util::add_buffer(buffer, &mut app).unwrap();
+
+        // Delete two lines, undo, move down a line, and repeat the deletion.
+        press_keys(&mut app, &[Key::Char('2'), Key::Char(';')]);
+        press_keys(&mut app, &[Key::Char('u'), Key::Char('j'), Key::Char('.')]);
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "a\nd\ne\nf\n"
+        );
+    }
+
+    #[test]
+    fn repeat_last_change_replays_an_insert_mode_session_as_a_single_operation() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor");
+        app.workspace.add_buffer(buffer);
+
+        // Insert a line below the first, then repeat it below the last.
+        press_keys(
+            &mut app,
+            &[Key::Char('o'), Key::Char('-'), Key::Char('-'), Key::Esc],
+        );
+        press_keys(&mut app, &[Key::Char('J'), Key::Char('.')]);
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp\n--\neditor\n--"
+        );
+
+        super::undo(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp\n--\neditor"
+        );
+    }
+
+    #[test]
+    fn repeat_last_change_raises_an_error_without_a_change() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        app.workspace.add_buffer(Buffer::new());
+
+        assert!(super::repeat_last_change(&mut app).is_err());
+    }
+
+    #[test]
+    fn insert_char_auto_pairs_and_skips_over_closing_characters() {
+        let mut app = set_up_auto_pair_application("let amp = ;");
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+
+        press_keys(
+            &mut app,
+            &"(\"a\")".chars().map(Key::Char).collect::<Vec<_>>(),
+        );
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "let amp = (\"a\");"
+        );
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position {
+                line: 0,
+                offset: 15
+            }
+        );
+    }
+
+    #[test]
+    fn insert_char_does_not_auto_pair_in_strings_comments_or_paste_mode() {
+        let mut app = set_up_auto_pair_application("let amp = \"\"; // ");
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position {
+                line: 0,
+                offset: 11,
+            });
+
+        press_keys(&mut app, &[Key::Char('[')]);
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position {
+                line: 0,
+                offset: 18,
+            });
+        press_keys(&mut app, &[Key::Char('(')]);
+        commands::application::switch_to_paste_mode(&mut app).unwrap();
+        press_keys(&mut app, &[Key::Char('{')]);
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "let amp = \"[\"; // ({"
+        );
+    }
+
+    #[test]
+    fn insert_char_auto_pairs_after_a_closed_string_at_the_end_of_a_line() {
+        let mut app = set_up_auto_pair_application("amp(\"a\"");
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 0, offset: 7 });
+
+        press_keys(&mut app, &[Key::Char('[')]);
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp(\"a\"[]"
+        );
+    }
+
+    #[test]
+    fn backspace_removes_empty_auto_pairs() {
+        let mut app = set_up_auto_pair_application("amp();");
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 0, offset: 4 });
+
+        super::backspace(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp;"
+        );
+    }
+
+    #[test]
+    fn insert_newline_indents_within_brackets_and_moves_closing_ones_to_their_own_line() {
+        let mut app = set_up_auto_pair_application("fn amp() ");
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 0, offset: 9 });
+
+        press_keys(&mut app, &[Key::Char('{'), Key::Enter]);
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "fn amp() {\n  \n}"
+        );
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position { line: 1, offset: 2 }
+        );
+    }
+
+    #[test]
+    fn insert_newline_indents_after_block_start_scopes() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.path = Some(PathBuf::from("amp.py"));
+        buffer.insert("def amp():  # editor");
+        buffer.cursor.move_to(Position {
+            line: 0,
+            offset: 20,
+        });
+        util::add_buffer(buffer, &mut app).unwrap();
+
+        super::insert_newline(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "def amp():  # editor\n  "
+        );
+    }
+
+    #[test]
+    fn insert_char_outdents_closing_brackets_on_blank_lines() {
+        let mut app = set_up_auto_pair_application("fn amp() {\n  editor();\n  ");
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 2, offset: 2 });
+
+        press_keys(&mut app, &[Key::Char('}'), Key::Char(';')]);
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "fn amp() {\n  editor();\n};"
+        );
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position { line: 2, offset: 2 }
+        );
+    }
+
+    #[test]
+    fn insert_char_outdents_lines_matching_the_decrease_indent_pattern() {
+        let mut app = set_up_indent_application("amp.rb", "def amp\n  editor\n  ");
+
+        press_keys(&mut app, &"end".chars().map(Key::Char).collect::<Vec<_>>());
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "def amp\n  editor\n  end"
+        );
+
+        // The keyword is only outdented once it's been completed.
+        press_keys(&mut app, &[Key::Enter]);
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "def amp\n  editor\nend\n"
+        );
+    }
+
+    #[test]
+    fn insert_char_outdents_keywords_followed_by_non_word_characters() {
+        let mut app = set_up_indent_application("amp.sh", "if true; then\n  ");
+
+        press_keys(&mut app, &"fi;".chars().map(Key::Char).collect::<Vec<_>>());
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "if true; then\nfi;"
+        );
+    }
+
+    #[test]
+    fn insert_char_does_not_outdent_words_prefixed_by_keywords() {
+        let cases = [
+            ("amp.sh", "if true; then\n  ", "file=amp"),
+            ("amp.rb", "def amp\n  ", "endpoint = 1"),
+            ("amp.lua", "function amp()\n  ", "end_time = 1"),
+        ];
+
+        for (path, content, typed) in cases {
+            let mut app = set_up_indent_application(path, content);
+
+            press_keys(&mut app, &typed.chars().map(Key::Char).collect::<Vec<_>>());
+
+            assert_eq!(
+                app.workspace.current_buffer.as_ref().unwrap().data(),
+                format!("{content}{typed}")
+            );
+        }
+    }
+
+    #[test]
+    fn insert_newline_reports_invalid_indent_patterns_and_keeps_indentation() {
+        let mut app = set_up_indent_application("amp.abc", "  amp");
+        let data =
+            YamlLoader::load_from_str("types:\n  abc:\n    increase_indent_pattern: '('").unwrap();
+        app.preferences
+            .replace(Preferences::new(data.into_iter().next()));
+
+        assert!(super::insert_newline(&mut app).is_err());
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "  amp\n  "
+        );
+    }
+
+    // Builds an application in insert mode, with a buffer of the specified
+    // type holding the content, with its cursor at the end of the content.
+    fn set_up_indent_application(path: &str, content: &str) -> Application {
+        let last_line = content.lines().count().saturating_sub(1);
+        let mut app = set_up_application_at(
+            content,
+            Position {
+                line: last_line,
+                offset: content.split('\n').next_back().unwrap_or("").len(),
+            },
+        );
+        app.workspace.current_buffer.as_mut().unwrap().path = Some(PathBuf::from(path));
+        app.workspace.update_current_syntax().unwrap();
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+
+        app
+    }
+
+    #[test]
+    fn undo_earlier_restores_changes_lost_to_undoing_and_editing() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+        app.workspace.add_buffer(buffer);
+
+        // Insert a line, undo it, and then delete a character.
+        press_keys(
+            &mut app,
+            &[
+                Key::Char('O'),
+                Key::Char('e'),
+                Key::Esc,
+                Key::Char('u'),
+                Key::Char('x'),
+            ],
+        );
+        assert_eq!(app.workspace.current_buffer.as_ref().unwrap().data(), "ap");
+
+        // States are revisited in the order they were reached, undo included.
+        super::undo_earlier(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer.as_ref().unwrap().data(), "amp");
+        super::undo_earlier(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "e\namp"
+        );
+
+        super::undo_later(&mut app).unwrap();
+        super::undo_later(&mut app).unwrap();
+        assert_eq!(app.workspace.current_buffer.as_ref().unwrap().data(), "ap");
+    }
+
+    fn set_up_auto_pair_application(content: &str) -> Application {
+        let mut app = set_up_application_at(
+            content,
+            Position {
+                line: 0,
+                offset: 10,
+            },
+        );
+        let data = YamlLoader::load_from_str("types:\n  rs:\n    auto_pairs: true").unwrap();
+        app.preferences
+            .replace(Preferences::new(data.into_iter().next()));
+        app.workspace.current_buffer.as_mut().unwrap().path = Some(PathBuf::from("amp.rs"));
+        app.workspace.update_current_syntax().unwrap();
+
+        app
+    }
 }
@@ -0,0 +1,192 @@
+use crate::commands::{self, Result};
+use crate::errors::*;
+use crate::models::application::{Application, Mode};
+use crate::util;
+use crate::util::case::{self, Case};
+use crate::util::multi_cursor;
+use crate::util::token::{adjacent_token_position, Direction};
+use scribe::buffer::{Buffer, Position, Range};
+use unicode_segmentation::UnicodeSegmentation;
+
+pub fn to_upper_case(app: &mut Application) -> Result {
+    multi_cursor::apply(app, |app| convert(app, Case::Upper))
+}
+
+pub fn to_lower_case(app: &mut Application) -> Result {
+    multi_cursor::apply(app, |app| convert(app, Case::Lower))
+}
+
+pub fn to_title_case(app: &mut Application) -> Result {
+    multi_cursor::apply(app, |app| convert(app, Case::Title))
+}
+
+pub fn to_snake_case(app: &mut Application) -> Result {
+    multi_cursor::apply(app, |app| convert(app, Case::Snake))
+}
+
+pub fn to_camel_case(app: &mut Application) -> Result {
+    multi_cursor::apply(app, |app| convert(app, Case::Camel))
+}
+
+pub fn to_pascal_case(app: &mut Application) -> Result {
+    multi_cursor::apply(app, |app| convert(app, Case::Pascal))
+}
+
+pub fn to_kebab_case(app: &mut Application) -> Result {
+    multi_cursor::apply(app, |app| convert(app, Case::Kebab))
+}
+
+// Converts the selection, or the token under the cursor outside of select
+// modes, as a single operation, leaving the cursor at the start of the text.
+fn convert(app: &mut Application, case: Case) -> Result {
+    let ranges = match app.mode {
+        Mode::SelectBlock(_) => commands::selection::block_ranges(app)?,
+        Mode::Select(_) | Mode::SelectLine(_) | Mode::Search(_) => {
+            vec![commands::selection::sel_to_range(app)?]
+        }
+        _ => vec![token_range(
+            app.workspace
+                .current_buffer
+                .as_mut()
+                .context(BUFFER_MISSING)?,
+        )?],
+    };
+
+    util::group_changes(app, |app| {
+        let buffer = app
+            .workspace
+            .current_buffer
+            .as_mut()
+            .context(BUFFER_MISSING)?;
+
+        // Replace from the bottom up, so that ranges don't shift one another.
+        for range in ranges.iter().rev() {
+            let content = buffer
+                .read(range)
+                .context("Couldn't read selected data from buffer")?;
+            let converted = case::convert(&content, case);
+
+            if converted != content {
+                buffer.delete_range(range.clone());
+                buffer.cursor.move_to(range.start());
+                buffer.insert(converted);
+            }
+        }
+        if let Some(range) = ranges.first() {
+            buffer.cursor.move_to(range.start());
+        }
+
+        Ok(())
+    })
+}
+
+// The identifier under the cursor. Tokens found by looking ahead of the
+// cursor and then back from there are split at words (e.g. "foo" and "Bar"),
+// so they're widened to include the identifier characters surrounding them.
+fn token_range(buffer: &mut Buffer) -> std::result::Result<Range, Error> {
+    let original_position = *buffer.cursor.clone();
+    let data = buffer.data();
+    let lines: Vec<Vec<&str>> = data
+        .split('\n')
+        .map(|line| line.graphemes(true).collect())
+        .collect();
+    let end = adjacent_token_position(buffer, true, Direction::Forward).unwrap_or(Position {
+        line: lines.len() - 1,
+        offset: lines[lines.len() - 1].len(),
+    });
+
+    buffer.cursor.move_to(end);
+    let start = adjacent_token_position(buffer, false, Direction::Backward);
+    buffer.cursor.move_to(original_position);
+    let mut start = start.context("No token found at the cursor")?;
+    let mut end = end;
+
+    let is_identifier = |position: &Position, offset: usize| {
+        lines[position.line]
+            .get(offset)
+            .and_then(|grapheme| grapheme.chars().next())
+            .is_some_and(case::is_identifier_char)
+    };
+    while start.offset > 0
+        && is_identifier(&start, start.offset)
+        && is_identifier(&start, start.offset - 1)
+    {
+        start.offset -= 1;
+    }
+    while end.offset > 0 && is_identifier(&end, end.offset - 1) && is_identifier(&end, end.offset) {
+        end.offset += 1;
+    }
+
+    Ok(Range::new(start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands;
+    use crate::models::application::Application;
+    use scribe::buffer::Position;
+    use scribe::Buffer;
+
+    #[test]
+    fn to_snake_case_converts_the_token_under_the_cursor() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("let fooBar = bazQux;");
+        buffer.cursor.move_to(Position { line: 0, offset: 6 });
+        app.workspace.add_buffer(buffer);
+
+        super::to_snake_case(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "let foo_bar = bazQux;"
+        );
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position { line: 0, offset: 4 }
+        );
+    }
+
+    #[test]
+    fn to_camel_case_converts_the_entire_identifier_under_the_cursor() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\n  text_editor_name");
+        buffer.cursor.move_to(Position { line: 1, offset: 7 });
+        app.workspace.add_buffer(buffer);
+
+        super::to_camel_case(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp\n  textEditorName"
+        );
+    }
+
+    #[test]
+    fn to_upper_case_converts_the_selection_as_a_single_operation() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp editor");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 0, offset: 7 });
+        super::to_upper_case(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "AMP EDItor"
+        );
+
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp editor"
+        );
+    }
+}
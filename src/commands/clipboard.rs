@@ -0,0 +1,74 @@
+use crate::commands::Result;
+use crate::errors::*;
+use crate::input::Key;
+use crate::models::application::{Application, Mode, ModeKey};
+
+pub fn switch_to_register_mode(app: &mut Application) -> Result {
+    app.workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    app.switch_to(ModeKey::Register);
+
+    Ok(())
+}
+
+/// Selects the named register for the next command, and returns
+/// to the mode from which register mode was entered to run it.
+pub fn select_register(app: &mut Application) -> Result {
+    if !matches!(app.mode, Mode::Register(_)) {
+        bail!("Can't select a register outside of register mode");
+    }
+    let register = match *app.view.last_key() {
+        Some(Key::Char(c)) => c,
+        _ => bail!("Registers must be named using a character"),
+    };
+    app.clipboard.select_register(register);
+    app.switch_to_previous_mode();
+
+    Ok(())
+}
+
+pub fn cancel(app: &mut Application) -> Result {
+    app.switch_to_previous_mode();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands;
+    use crate::commands::test_support::{press_keys, set_up_application};
+    use crate::input::Key;
+    use crate::models::application::{ClipboardContent, Mode};
+
+    #[test]
+    fn registers_are_used_by_the_command_following_their_selection() {
+        let mut app = set_up_application("amp\neditor\n");
+
+        // Copy the first line into a register, and
+        // then replace the clipboard's content.
+        press_keys(&mut app, &[Key::Char('"'), Key::Char('a'), Key::Char('y')]);
+        app.clipboard
+            .set_content(ClipboardContent::Inline("text".to_string()))
+            .unwrap();
+
+        press_keys(&mut app, &[Key::Char('"'), Key::Char('a'), Key::Char('p')]);
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp\namp\neditor\n"
+        );
+        assert!(matches!(app.mode, Mode::Normal(_)));
+        assert_eq!(app.clipboard.register(), None);
+    }
+
+    #[test]
+    fn select_register_returns_to_the_previous_mode() {
+        let mut app = set_up_application("amp");
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+
+        press_keys(&mut app, &[Key::Char('"'), Key::Char('a')]);
+        assert!(matches!(app.mode, Mode::Select(_)));
+        assert_eq!(app.clipboard.register(), Some('a'));
+    }
+}
@@ -0,0 +1,249 @@
+use crate::commands::{self, Result};
+use crate::errors::*;
+use crate::input::Key;
+use crate::models::application::{Application, Mode, ModeKey};
+use crate::util::completion;
+use scribe::buffer::Position;
+use std::collections::HashMap;
+use std::mem;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Completes the partial word before the cursor using words from the open
+/// buffers, inserting the only candidate, or listing them to choose from.
+pub fn complete(app: &mut Application) -> Result {
+    let other_words = other_buffer_words(app);
+    let (prefix, candidates) = find_candidates(app, &other_words)?;
+    if prefix.is_empty() {
+        bail!("No word to complete before the cursor");
+    }
+
+    match candidates.len() {
+        0 => bail!("No completions for \"{prefix}\""),
+        1 => insert_completion(app, &prefix, &candidates[0]),
+        _ => {
+            list(app, prefix, candidates, other_words);
+            Ok(())
+        }
+    }
+}
+
+pub fn select_next(app: &mut Application) -> Result {
+    if let Mode::Completion(ref mut mode) = app.mode {
+        mode.select_next();
+    } else {
+        bail!("Can't change completion selection outside of completion mode");
+    }
+
+    Ok(())
+}
+
+pub fn select_previous(app: &mut Application) -> Result {
+    if let Mode::Completion(ref mut mode) = app.mode {
+        mode.select_previous();
+    } else {
+        bail!("Can't change completion selection outside of completion mode");
+    }
+
+    Ok(())
+}
+
+/// Inserts the rest of the selected word, returning to insert mode.
+pub fn accept(app: &mut Application) -> Result {
+    let (prefix, word) = match app.mode {
+        Mode::Completion(ref mode) => (
+            mode.prefix.clone(),
+            mode.selection().context("No completion selected")?.clone(),
+        ),
+        _ => bail!("Can't accept completion outside of completion mode"),
+    };
+
+    app.switch_to(ModeKey::Insert);
+    insert_completion(app, &prefix, &word)
+}
+
+pub fn cancel(app: &mut Application) -> Result {
+    app.switch_to(ModeKey::Insert);
+
+    Ok(())
+}
+
+/// Inserts the typed character, narrowing the list down to words that
+/// still complete the one being typed, or returning to insert mode.
+pub fn insert_char(app: &mut Application) -> Result {
+    let other_words = match app.mode {
+        Mode::Completion(ref mut mode) => mem::take(&mut mode.other_words),
+        _ => bail!("Can't narrow completions outside of completion mode"),
+    };
+    app.switch_to(ModeKey::Insert);
+    commands::buffer::insert_char(app)?;
+
+    if let Some(Key::Char(_)) = *app.view.last_key() {
+        let (prefix, candidates) = find_candidates(app, &other_words)?;
+        if !prefix.is_empty() && !candidates.is_empty() {
+            list(app, prefix, candidates, other_words);
+        }
+    }
+
+    Ok(())
+}
+
+// Finds the partial word before the cursor, and the words that complete it.
+fn find_candidates(
+    app: &Application,
+    other_words: &HashMap<String, usize>,
+) -> std::result::Result<(String, Vec<String>), Error> {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    let data = buffer.data();
+    let cursor = *buffer.cursor;
+    let prefix = completion::prefix(&data, &cursor);
+    if prefix.is_empty() {
+        return Ok((prefix, Vec::new()));
+    }
+
+    let candidates = completion::candidates(&prefix, &data, &cursor, other_words);
+
+    Ok((prefix, candidates))
+}
+
+// Cycles through the workspace's buffers, counting the words in all
+// but the current one, which is selected again when finished. Counts
+// are reused for buffers that haven't changed since they were last taken.
+fn other_buffer_words(app: &mut Application) -> HashMap<String, usize> {
+    let mut words = HashMap::new();
+
+    for index in 0..app.workspace.buffer_paths().len() {
+        if index > 0 {
+            if let Some(buffer) = app.workspace.current_buffer.as_ref() {
+                let revision = app.view.buffer_revision(buffer);
+                app.word_counts.add(buffer, revision, &mut words);
+            }
+        }
+
+        app.workspace.next_buffer();
+    }
+
+    words
+}
+
+fn list(
+    app: &mut Application,
+    prefix: String,
+    candidates: Vec<String>,
+    other_words: HashMap<String, usize>,
+) {
+    app.switch_to(ModeKey::Completion);
+    if let Mode::Completion(ref mut mode) = app.mode {
+        mode.reset(prefix, candidates, other_words);
+    }
+}
+
+// Inserts the part of the word following the prefix, moving past it.
+fn insert_completion(app: &mut Application, prefix: &str, word: &str) -> Result {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let remainder = word.strip_prefix(prefix).unwrap_or_default();
+    let position = Position {
+        line: buffer.cursor.line,
+        offset: buffer.cursor.offset + remainder.graphemes(true).count(),
+    };
+    buffer.insert(remainder);
+    buffer.cursor.move_to(position);
+
+    commands::view::scroll_to_cursor(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands;
+    use crate::commands::test_support;
+    use crate::input::Key;
+    use crate::models::application::Mode;
+    use scribe::buffer::Position;
+
+    #[test]
+    fn complete_inserts_the_only_candidate() {
+        let mut app = test_support::set_up_application("amp_workspace amp_view");
+        test_support::add_buffer(
+            &mut app,
+            "amp_buffer\namp_b",
+            Position { line: 1, offset: 5 },
+        );
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+
+        commands::completion::complete(&mut app).unwrap();
+        let buffer = app.workspace.current_buffer.as_ref().unwrap();
+        assert_eq!(buffer.data(), "amp_buffer\namp_buffer");
+        assert_eq!(
+            *buffer.cursor,
+            Position {
+                line: 1,
+                offset: 10
+            }
+        );
+        assert!(matches!(app.mode, Mode::Insert));
+    }
+
+    #[test]
+    fn complete_lists_candidates_from_every_buffer_to_choose_from() {
+        let mut app = test_support::set_up_application("amp_workspace amp_view");
+        test_support::add_buffer(&mut app, "amp_buffer\namp", Position { line: 1, offset: 3 });
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+
+        commands::completion::complete(&mut app).unwrap();
+        if let Mode::Completion(ref mode) = app.mode {
+            assert_eq!(
+                mode.results().collect::<Vec<_>>(),
+                vec!["amp_buffer", "amp_view", "amp_workspace"]
+            );
+        } else {
+            panic!("Not in completion mode");
+        }
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp_buffer\namp"
+        );
+
+        commands::completion::select_next(&mut app).unwrap();
+        commands::completion::accept(&mut app).unwrap();
+        let buffer = app.workspace.current_buffer.as_ref().unwrap();
+        assert_eq!(buffer.data(), "amp_buffer\namp_view");
+        assert_eq!(*buffer.cursor, Position { line: 1, offset: 8 });
+        assert!(matches!(app.mode, Mode::Insert));
+    }
+
+    #[test]
+    fn insert_char_narrows_candidates_using_the_collected_words() {
+        let mut app = test_support::set_up_application("amp_workspace amp_view");
+        test_support::add_buffer(&mut app, "amp_buffer\namp", Position { line: 1, offset: 3 });
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+
+        commands::completion::complete(&mut app).unwrap();
+        test_support::press_keys(&mut app, &[Key::Char('_'), Key::Char('w')]);
+        if let Mode::Completion(ref mode) = app.mode {
+            assert_eq!(mode.results().collect::<Vec<_>>(), vec!["amp_workspace"]);
+        } else {
+            panic!("Not in completion mode");
+        }
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp_buffer\namp_w"
+        );
+    }
+
+    #[test]
+    fn complete_fails_without_candidates() {
+        let mut app = test_support::set_up_application("amp_workspace amp_view");
+        test_support::add_buffer(&mut app, "editor", Position { line: 0, offset: 6 });
+        commands::application::switch_to_insert_mode(&mut app).unwrap();
+
+        let error = commands::completion::complete(&mut app).unwrap_err();
+        assert_eq!(error.to_string(), "No completions for \"editor\"");
+    }
+}
@@ -1,68 +1,83 @@
 use super::{application, buffer};
 use crate::commands::{self, Result};
 use crate::errors::*;
-use crate::models::application::Application;
+use crate::models::application::{Application, Mode, SecondaryCursor};
+use crate::util::bracket;
+use crate::util::multi_cursor;
 use crate::util::token::{adjacent_token_position, Direction};
-use scribe::buffer::Position;
+use scribe::buffer::{Distance, Position, Range};
+use unicode_segmentation::UnicodeSegmentation;
 
 pub fn move_up(app: &mut Application) -> Result {
-    app.workspace
-        .current_buffer
-        .as_mut()
-        .context(BUFFER_MISSING)?
-        .cursor
-        .move_up();
-    commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    multi_cursor::apply(app, |app| {
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .context(BUFFER_MISSING)?
+            .cursor
+            .move_up();
+        commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    })
 }
 
 pub fn move_down(app: &mut Application) -> Result {
-    app.workspace
-        .current_buffer
-        .as_mut()
-        .context(BUFFER_MISSING)?
-        .cursor
-        .move_down();
-    commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    multi_cursor::apply(app, |app| {
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .context(BUFFER_MISSING)?
+            .cursor
+            .move_down();
+        commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    })
 }
 
 pub fn move_left(app: &mut Application) -> Result {
-    app.workspace
-        .current_buffer
-        .as_mut()
-        .context(BUFFER_MISSING)?
-        .cursor
-        .move_left();
-    commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    multi_cursor::apply(app, |app| {
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .context(BUFFER_MISSING)?
+            .cursor
+            .move_left();
+        commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    })
 }
 
 pub fn move_right(app: &mut Application) -> Result {
-    app.workspace
-        .current_buffer
-        .as_mut()
-        .context(BUFFER_MISSING)?
-        .cursor
-        .move_right();
-    commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    multi_cursor::apply(app, |app| {
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .context(BUFFER_MISSING)?
+            .cursor
+            .move_right();
+        commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    })
 }
 
 pub fn move_to_start_of_line(app: &mut Application) -> Result {
-    app.workspace
-        .current_buffer
-        .as_mut()
-        .context(BUFFER_MISSING)?
-        .cursor
-        .move_to_start_of_line();
-    commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    multi_cursor::apply(app, |app| {
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .context(BUFFER_MISSING)?
+            .cursor
+            .move_to_start_of_line();
+        commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    })
 }
 
 pub fn move_to_end_of_line(app: &mut Application) -> Result {
-    app.workspace
-        .current_buffer
-        .as_mut()
-        .context(BUFFER_MISSING)?
-        .cursor
-        .move_to_end_of_line();
-    commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    multi_cursor::apply(app, |app| {
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .context(BUFFER_MISSING)?
+            .cursor
+            .move_to_end_of_line();
+        commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    })
 }
 
 pub fn move_to_first_line(app: &mut Application) -> Result {
@@ -86,6 +101,10 @@ pub fn move_to_last_line(app: &mut Application) -> Result {
 }
 
 pub fn move_to_first_word_of_line(app: &mut Application) -> Result {
+    multi_cursor::apply(app, move_cursor_to_first_word_of_line)
+}
+
+fn move_cursor_to_first_word_of_line(app: &mut Application) -> Result {
     if let Some(buffer) = app.workspace.current_buffer.as_mut() {
         let data = buffer.data();
         let current_line = data
@@ -170,38 +189,73 @@ pub fn insert_with_newline_above(app: &mut Application) -> Result {
 }
 
 pub fn move_to_start_of_previous_token(app: &mut Application) -> Result {
-    if let Some(buffer) = app.workspace.current_buffer.as_mut() {
-        let position = adjacent_token_position(buffer, false, Direction::Backward)
-            .context("Couldn't find previous token")?;
-
-        buffer.cursor.move_to(position);
-    } else {
-        bail!(BUFFER_MISSING);
-    }
-    commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    multi_cursor::apply(app, |app| {
+        if let Some(buffer) = app.workspace.current_buffer.as_mut() {
+            let position = adjacent_token_position(buffer, false, Direction::Backward)
+                .context("Couldn't find previous token")?;
+
+            buffer.cursor.move_to(position);
+        } else {
+            bail!(BUFFER_MISSING);
+        }
+        commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    })
 }
 
 pub fn move_to_start_of_next_token(app: &mut Application) -> Result {
-    if let Some(buffer) = app.workspace.current_buffer.as_mut() {
-        let position = adjacent_token_position(buffer, false, Direction::Forward)
-            .context("Couldn't find next token")?;
-
-        buffer.cursor.move_to(position);
-    } else {
-        bail!(BUFFER_MISSING);
-    }
-    commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    multi_cursor::apply(app, |app| {
+        if let Some(buffer) = app.workspace.current_buffer.as_mut() {
+            let position = adjacent_token_position(buffer, false, Direction::Forward)
+                .context("Couldn't find next token")?;
+
+            buffer.cursor.move_to(position);
+        } else {
+            bail!(BUFFER_MISSING);
+        }
+        commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    })
 }
 
 pub fn move_to_end_of_current_token(app: &mut Application) -> Result {
-    if let Some(buffer) = app.workspace.current_buffer.as_mut() {
-        let position = adjacent_token_position(buffer, true, Direction::Forward)
-            .context("Couldn't find next token")?;
+    multi_cursor::apply(app, |app| {
+        if let Some(buffer) = app.workspace.current_buffer.as_mut() {
+            let position = adjacent_token_position(buffer, true, Direction::Forward)
+                .context("Couldn't find next token")?;
+
+            buffer.cursor.move_to(position);
+        } else {
+            bail!(BUFFER_MISSING);
+        }
+        commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
+    })
+}
 
-        buffer.cursor.move_to(position);
-    } else {
-        bail!(BUFFER_MISSING);
-    }
+pub fn move_to_matching_bracket(app: &mut Application) -> Result {
+    let position = {
+        let buffer = app
+            .workspace
+            .current_buffer
+            .as_ref()
+            .context(BUFFER_MISSING)?;
+        // Closing brackets can be paired with any line before them.
+        let data = buffer.data();
+        let state = app.view.line_state(buffer, 0)?;
+        let lexemes = bracket::lexemes(
+            &data,
+            state,
+            buffer.line_count(),
+            &app.workspace.syntax_set,
+        )?;
+
+        bracket::matching_bracket(&lexemes, &buffer.cursor)?.context("No matching bracket found")?
+    };
+
+    app.workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?
+        .cursor
+        .move_to(position);
     commands::view::scroll_to_cursor(app).context(SCROLL_TO_CURSOR_FAILED)
 }
 
@@ -210,11 +264,192 @@ pub fn append_to_current_token(app: &mut Application) -> Result {
     application::switch_to_insert_mode(app)
 }
 
+pub fn add_cursor_at_next_match(app: &mut Application) -> Result {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    let anchor = match app.mode {
+        Mode::Select(ref mode) => mode.anchor,
+        _ => bail!("A selection is required."),
+    };
+    let selection = Range::new(anchor, *buffer.cursor);
+    let query = buffer
+        .read(&selection)
+        .filter(|query| !query.is_empty())
+        .context("A selection is required.")?;
+    let distance = Distance::of_str(&query);
+
+    // Gather the existing selections, so we can skip their matches
+    // and search for the next match beyond the last of them.
+    let mut selections = vec![selection.clone()];
+    for cursor in app.cursors.for_buffer(buffer) {
+        selections.push(Range::new(cursor.anchor, cursor.position));
+    }
+    let search_start = selections.iter().map(|selection| selection.end()).fold(
+        selection.end(),
+        |end, candidate| {
+            if candidate > end {
+                candidate
+            } else {
+                end
+            }
+        },
+    );
+    let available: Vec<Position> = buffer
+        .search(&query)
+        .into_iter()
+        .filter(|start| !selections.iter().any(|s| s.start() == *start))
+        .collect();
+
+    // Wrap around to the start of the buffer if there are no later matches.
+    let start = available
+        .iter()
+        .find(|start| **start >= search_start)
+        .or_else(|| available.first())
+        .copied()
+        .context("No other matches found")?;
+    let end = start + distance;
+
+    // Mirror the direction of the primary selection.
+    let cursor = if anchor > *buffer.cursor {
+        SecondaryCursor {
+            position: start,
+            anchor: end,
+        }
+    } else {
+        SecondaryCursor {
+            position: end,
+            anchor: start,
+        }
+    };
+    app.cursors.add(buffer, cursor);
+
+    Ok(())
+}
+
+pub fn add_cursors_to_selected_lines(app: &mut Application) -> Result {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    let anchor = match app.mode {
+        Mode::SelectLine(ref mode) => mode.anchor,
+        _ => bail!("A line selection is required."),
+    };
+    let data = buffer.data();
+    let lines: Vec<&str> = data.lines().collect();
+    let cursor_line = buffer.cursor.line;
+    let offset = buffer.cursor.offset;
+
+    // Add a cursor to every other selected line, using the primary
+    // cursor's offset, or the end of the line if it's too short.
+    for line in anchor.min(cursor_line)..=anchor.max(cursor_line) {
+        if line == cursor_line {
+            continue;
+        }
+
+        let line_length = lines
+            .get(line)
+            .map(|content| content.graphemes(true).count())
+            .unwrap_or(0);
+        let position = Position {
+            line,
+            offset: offset.min(line_length),
+        };
+        app.cursors.add(buffer, SecondaryCursor::new(position));
+    }
+
+    application::switch_to_normal_mode(app)
+}
+
+pub fn add_cursors_at_search_results(app: &mut Application) -> Result {
+    let (selection, results) = match app.mode {
+        Mode::Search(ref mode) => {
+            let results = mode.results.as_ref().context(NO_SEARCH_RESULTS)?;
+            let selection = results.selection().context(NO_SEARCH_RESULTS)?.clone();
+
+            (selection, results.iter().cloned().collect::<Vec<Range>>())
+        }
+        _ => bail!("Can't add cursors at search results outside of search mode"),
+    };
+
+    // Select the current result, and then add a selection for each of the others.
+    app.workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?
+        .cursor
+        .move_to(selection.start());
+    application::switch_to_select_mode(app)?;
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    buffer.cursor.move_to(selection.end());
+
+    for result in results.into_iter().filter(|result| *result != selection) {
+        app.cursors.add(
+            buffer,
+            SecondaryCursor {
+                position: result.end(),
+                anchor: result.start(),
+            },
+        );
+    }
+
+    Ok(())
+}
+
+pub fn remove_secondary_cursors(app: &mut Application) -> Result {
+    app.cursors.clear();
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::models::application::Application;
+    use crate::util;
     use scribe::buffer::Position;
     use scribe::Buffer;
+    use std::path::PathBuf;
+
+    #[test]
+    fn move_to_matching_bracket_jumps_between_pairs() {
+        // Use a Rust buffer, so that the string's bracket is ignored.
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.path = Some(PathBuf::from("amp.rs"));
+        buffer.insert("amp(\"(\", [1])");
+        util::add_buffer(buffer, &mut app).unwrap();
+
+        super::move_to_matching_bracket(&mut app).unwrap_err();
+
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 0, offset: 3 });
+        super::move_to_matching_bracket(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position {
+                line: 0,
+                offset: 12
+            }
+        );
+
+        super::move_to_matching_bracket(&mut app).unwrap();
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position { line: 0, offset: 3 }
+        );
+    }
 
     #[test]
     fn move_to_first_word_of_line_works() {
@@ -396,6 +631,88 @@ mod tests {
         });
     }
 
+    #[test]
+    fn add_cursors_to_selected_lines_supports_editing_them_as_a_single_operation() {
+        let mut app = set_up_application("amp\neditor\nx\n");
+
+        // Select the first three lines, ending on the second column.
+        crate::commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 2, offset: 1 });
+        super::add_cursors_to_selected_lines(&mut app).unwrap();
+
+        // Insert a character at each cursor.
+        crate::commands::application::switch_to_insert_mode(&mut app).unwrap();
+        app.view.last_key = Some(crate::input::Key::Char('-'));
+        crate::commands::buffer::insert_char(&mut app).unwrap();
+        crate::commands::application::switch_to_normal_mode(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "a-mp\ne-ditor\nx-\n"
+        );
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position { line: 2, offset: 2 }
+        );
+
+        // Ensure the edits are reverted together.
+        crate::commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp\neditor\nx\n"
+        );
+    }
+
+    #[test]
+    fn add_cursor_at_next_match_selects_the_next_occurrence() {
+        let mut app = set_up_application("amp amp\namp\n");
+
+        // Select the first "amp", and add the next two occurrences.
+        crate::commands::application::switch_to_select_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 0, offset: 3 });
+        super::add_cursor_at_next_match(&mut app).unwrap();
+        super::add_cursor_at_next_match(&mut app).unwrap();
+
+        // Ensure there are no further matches to add.
+        assert!(super::add_cursor_at_next_match(&mut app).is_err());
+
+        // Replace each of the selections.
+        crate::commands::selection::delete(&mut app).unwrap();
+        crate::commands::application::switch_to_insert_mode(&mut app).unwrap();
+        app.view.last_key = Some(crate::input::Key::Char('x'));
+        crate::commands::buffer::insert_char(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "x x\nx\n"
+        );
+    }
+
+    #[test]
+    fn remove_secondary_cursors_works() {
+        let mut app = set_up_application("amp\neditor\n");
+        let buffer = app.workspace.current_buffer.as_ref().unwrap();
+        app.cursors.add(
+            buffer,
+            crate::models::application::SecondaryCursor::new(Position { line: 1, offset: 0 }),
+        );
+
+        super::remove_secondary_cursors(&mut app).unwrap();
+
+        let buffer = app.workspace.current_buffer.as_ref().unwrap();
+        assert!(app.cursors.for_buffer(buffer).is_empty());
+    }
+
     fn set_up_application(content: &str) -> Application {
         let mut app = Application::new(&Vec::new()).unwrap();
         let mut buffer = Buffer::new();
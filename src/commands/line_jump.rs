@@ -118,7 +118,7 @@ mod tests {
 
         // Ensure that we're in normal mode.
         assert!(match app.mode {
-            crate::models::application::Mode::Normal => true,
+            crate::models::application::Mode::Normal(_) => true,
             _ => false,
         });
     }
@@ -150,7 +150,7 @@ mod tests {
 
         // Ensure that we're in normal mode.
         assert!(match app.mode {
-            crate::models::application::Mode::Normal => true,
+            crate::models::application::Mode::Normal(_) => true,
             _ => false,
         });
     }
@@ -179,7 +179,7 @@ mod tests {
 
         // Ensure that we're in normal mode.
         assert!(match app.mode {
-            crate::models::application::Mode::Normal => true,
+            crate::models::application::Mode::Normal(_) => true,
             _ => false,
         });
     }
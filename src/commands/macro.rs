@@ -0,0 +1,135 @@
+use crate::commands::{self, Result};
+use crate::errors::*;
+use crate::input::Key;
+use crate::models::application::modes::MacroAction;
+use crate::models::application::{Application, Mode, ModeKey};
+use crate::util;
+
+pub fn start_recording(app: &mut Application) -> Result {
+    if let Some(register) = app.macros.recording() {
+        bail!("Already recording macro \"{register}\"");
+    }
+
+    switch_to_macro_register_mode(app, MacroAction::Record)
+}
+
+pub fn stop_recording(app: &mut Application) -> Result {
+    // The key that triggered this command was recorded; drop it.
+    app.macros.discard_last_key();
+    app.macros
+        .stop_recording()
+        .context("Not recording a macro")?;
+
+    app.macros.save()
+}
+
+pub fn play(app: &mut Application) -> Result {
+    switch_to_macro_register_mode(app, MacroAction::Play)
+}
+
+pub fn select_register(app: &mut Application) -> Result {
+    let action = match app.mode {
+        Mode::MacroRegister(ref mode) => mode.action,
+        _ => bail!("Can't select a macro register outside of macro register mode"),
+    };
+    let register = match *app.view.last_key() {
+        Some(Key::Char(c)) => c,
+        _ => bail!("Macro registers must be named using a character"),
+    };
+    commands::application::switch_to_normal_mode(app)?;
+
+    match action {
+        MacroAction::Record => app.macros.start_recording(register),
+        MacroAction::Play => play_register(app, register)?,
+    }
+
+    Ok(())
+}
+
+fn switch_to_macro_register_mode(app: &mut Application, action: MacroAction) -> Result {
+    app.workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    app.switch_to(ModeKey::MacroRegister);
+    if let Mode::MacroRegister(ref mut mode) = app.mode {
+        mode.reset(action);
+    }
+
+    Ok(())
+}
+
+// Replays the register's keys as though they were typed, grouping any
+// changes as a single operation. Macros can play others, but not themselves.
+fn play_register(app: &mut Application, register: char) -> Result {
+    if app.macros.playing.contains(&register) {
+        bail!("Macro \"{register}\" can't play itself");
+    }
+    let keys = app
+        .macros
+        .get(register)
+        .with_context(|| format!("No macro recorded in register \"{register}\""))?
+        .to_vec();
+
+    let last_key = app.view.last_key.take();
+    app.macros.playing.push(register);
+    let result = util::group_changes(app, |app| {
+        for key in keys {
+            app.view.last_key = Some(key);
+            commands::application::handle_input(app)?;
+        }
+
+        Ok(())
+    });
+    app.macros.playing.pop();
+    app.view.last_key = last_key;
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands;
+    use crate::commands::test_support::{press_keys, set_up_application};
+    use crate::input::Key;
+    use crate::models::application::Mode;
+
+    #[test]
+    fn recorded_macros_can_be_played_back() {
+        let mut app = set_up_application("amp\neditor\n");
+
+        // Record a macro that indents a line and moves down.
+        press_keys(&mut app, &[Key::Char('('), Key::Char('a')]);
+        press_keys(&mut app, &[Key::Char('>'), Key::Char('j')]);
+        press_keys(&mut app, &[Key::Char(')')]);
+        assert_eq!(
+            app.macros.get('a'),
+            Some(&[Key::Char('>'), Key::Char('j')][..])
+        );
+
+        // Play it back on the second line.
+        press_keys(&mut app, &[Key::Char('@'), Key::Char('a')]);
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "  amp\n  editor\n"
+        );
+        assert!(matches!(app.mode, Mode::Normal(_)));
+    }
+
+    #[test]
+    fn play_raises_an_error_for_an_empty_register() {
+        let mut app = set_up_application("amp\n");
+
+        commands::r#macro::play(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('z'));
+
+        assert!(super::select_register(&mut app).is_err());
+    }
+
+    #[test]
+    fn stop_recording_raises_an_error_when_not_recording() {
+        let mut app = set_up_application("amp\n");
+
+        assert!(super::stop_recording(&mut app).is_err());
+    }
+}
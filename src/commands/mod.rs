@@ -1,19 +1,30 @@
 use crate::models::application::Application;
 use std::collections::HashMap;
 
+pub mod align;
 pub mod application;
 pub mod buffer;
+pub mod case;
+pub mod clipboard;
+pub mod completion;
 pub mod confirm;
 pub mod cursor;
 pub mod git;
 pub mod jump;
 pub mod line_jump;
+pub mod r#macro;
 pub mod open;
 pub mod path;
 pub mod preferences;
 pub mod search;
 pub mod search_select;
 pub mod selection;
+pub mod shell_command;
+pub mod snippet;
+pub mod surround;
+pub mod text_object;
+#[cfg(test)]
+mod test_support;
 pub mod view;
 pub mod workspace;
 
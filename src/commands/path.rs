@@ -87,7 +87,7 @@ mod tests {
             Some(PathBuf::from("new_path"))
         );
 
-        if let Mode::Normal = app.mode {
+        if let Mode::Normal(_) = app.mode {
         } else {
             panic!("Not in normal mode");
         }
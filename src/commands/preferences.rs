@@ -1,5 +1,5 @@
 use crate::commands::Result;
-use crate::models::application::{Application, Preferences};
+use crate::models::application::{Application, Preferences, Snippets};
 use crate::util;
 
 pub fn edit(app: &mut Application) -> Result {
@@ -8,5 +8,10 @@ pub fn edit(app: &mut Application) -> Result {
 }
 
 pub fn reload(app: &mut Application) -> Result {
-    app.preferences.borrow_mut().reload()
+    app.preferences.borrow_mut().reload()?;
+    let backend = app.preferences.borrow().clipboard_backend()?;
+    app.clipboard.set_backend(backend, app.view.terminal());
+    app.snippets = Snippets::load(Preferences::snippets_path()?)?;
+
+    Ok(())
 }
@@ -4,6 +4,8 @@ use crate::input::Key;
 use crate::models::application::modes::open::DisplayablePath;
 use crate::models::application::modes::{PopSearchToken, SearchSelectMode};
 use crate::models::application::{Application, Mode, ModeKey};
+use crate::util;
+use std::path::PathBuf;
 
 pub fn accept(app: &mut Application) -> Result {
     match app.mode {
@@ -18,26 +20,14 @@ pub fn accept(app: &mut Application) -> Result {
                 bail!("No buffer selected");
             }
 
-            for DisplayablePath(path) in mode.selections() {
-                let syntax_definition = app
-                    .preferences
-                    .borrow()
-                    .syntax_definition_name(path)
-                    .and_then(|name| app.workspace.syntax_set.find_syntax_by_name(&name).cloned());
+            let paths: Vec<PathBuf> = mode
+                .selections()
+                .into_iter()
+                .map(|DisplayablePath(path)| path.clone())
+                .collect();
 
-                app.workspace
-                    .open_buffer(path)
-                    .context("Couldn't open a buffer for the specified path.")?;
-
-                let buffer = app.workspace.current_buffer.as_mut().unwrap();
-
-                // Only override the default syntax definition if the user provided
-                // a valid one in their preferences.
-                if syntax_definition.is_some() {
-                    buffer.syntax_definition = syntax_definition;
-                }
-
-                app.view.initialize_buffer(buffer)?;
+            for path in paths {
+                util::open_buffer(&path, app)?;
             }
         }
         Mode::Theme(ref mut mode) => {
@@ -69,6 +59,21 @@ pub fn accept(app: &mut Application) -> Result {
                 .context(BUFFER_MISSING)?;
             buffer.syntax_definition = syntax;
         }
+        Mode::ClipboardHistory(ref mut mode) => {
+            let index = mode.selection().context("No clipboard entry selected")?.index;
+            app.clipboard.restore(index)?;
+            app.switch_to(ModeKey::Normal);
+            commands::buffer::paste(app)?;
+        }
+        Mode::UndoTree(ref mut mode) => {
+            let number = mode.selection().context("No undo state selected")?.number;
+            let buffer = app
+                .workspace
+                .current_buffer
+                .as_mut()
+                .context(BUFFER_MISSING)?;
+            app.undo_tree.jump(buffer, number)?;
+        }
         _ => bail!("Can't accept selection outside of search select mode."),
     }
 
@@ -85,6 +90,8 @@ pub fn search(app: &mut Application) -> Result {
         Mode::Theme(ref mut mode) => mode.search(),
         Mode::SymbolJump(ref mut mode) => mode.search(),
         Mode::Syntax(ref mut mode) => mode.search(),
+        Mode::ClipboardHistory(ref mut mode) => mode.search(),
+        Mode::UndoTree(ref mut mode) => mode.search(),
         _ => bail!("Can't search outside of search select mode."),
     };
 
@@ -98,6 +105,8 @@ pub fn select_next(app: &mut Application) -> Result {
         Mode::Theme(ref mut mode) => mode.select_next(),
         Mode::SymbolJump(ref mut mode) => mode.select_next(),
         Mode::Syntax(ref mut mode) => mode.select_next(),
+        Mode::ClipboardHistory(ref mut mode) => mode.select_next(),
+        Mode::UndoTree(ref mut mode) => mode.select_next(),
         _ => bail!("Can't change selection outside of search select mode."),
     }
 
@@ -111,6 +120,8 @@ pub fn select_previous(app: &mut Application) -> Result {
         Mode::Theme(ref mut mode) => mode.select_previous(),
         Mode::SymbolJump(ref mut mode) => mode.select_previous(),
         Mode::Syntax(ref mut mode) => mode.select_previous(),
+        Mode::ClipboardHistory(ref mut mode) => mode.select_previous(),
+        Mode::UndoTree(ref mut mode) => mode.select_previous(),
         _ => bail!("Can't change selection outside of search select mode."),
     }
 
@@ -124,6 +135,8 @@ pub fn enable_insert(app: &mut Application) -> Result {
         Mode::Theme(ref mut mode) => mode.set_insert_mode(true),
         Mode::SymbolJump(ref mut mode) => mode.set_insert_mode(true),
         Mode::Syntax(ref mut mode) => mode.set_insert_mode(true),
+        Mode::ClipboardHistory(ref mut mode) => mode.set_insert_mode(true),
+        Mode::UndoTree(ref mut mode) => mode.set_insert_mode(true),
         _ => bail!("Can't change search insert state outside of search select mode"),
     }
 
@@ -137,6 +150,8 @@ pub fn disable_insert(app: &mut Application) -> Result {
         Mode::Theme(ref mut mode) => mode.set_insert_mode(false),
         Mode::SymbolJump(ref mut mode) => mode.set_insert_mode(false),
         Mode::Syntax(ref mut mode) => mode.set_insert_mode(false),
+        Mode::ClipboardHistory(ref mut mode) => mode.set_insert_mode(false),
+        Mode::UndoTree(ref mut mode) => mode.set_insert_mode(false),
         _ => bail!("Can't change search insert state outside of search select mode"),
     }
 
@@ -151,6 +166,8 @@ pub fn push_search_char(app: &mut Application) -> Result {
             Mode::Theme(ref mut mode) => mode.push_search_char(c),
             Mode::SymbolJump(ref mut mode) => mode.push_search_char(c),
             Mode::Syntax(ref mut mode) => mode.push_search_char(c),
+            Mode::ClipboardHistory(ref mut mode) => mode.push_search_char(c),
+            Mode::UndoTree(ref mut mode) => mode.push_search_char(c),
             _ => bail!("Can't push search character outside of search select mode"),
         }
     }
@@ -166,6 +183,8 @@ pub fn pop_search_token(app: &mut Application) -> Result {
         Mode::Theme(ref mut mode) => mode.pop_search_token(),
         Mode::SymbolJump(ref mut mode) => mode.pop_search_token(),
         Mode::Syntax(ref mut mode) => mode.pop_search_token(),
+        Mode::ClipboardHistory(ref mut mode) => mode.pop_search_token(),
+        Mode::UndoTree(ref mut mode) => mode.pop_search_token(),
         _ => bail!("Can't pop search token outside of search select mode"),
     }
 
@@ -180,6 +199,8 @@ pub fn step_back(app: &mut Application) -> Result {
         Mode::Theme(ref mut mode) => mode.results().count() > 0 && !mode.query().is_empty(),
         Mode::SymbolJump(ref mut mode) => mode.results().count() > 0 && !mode.query().is_empty(),
         Mode::Syntax(ref mut mode) => mode.results().count() > 0 && !mode.query().is_empty(),
+        Mode::ClipboardHistory(ref mut mode) => mode.results().count() > 0 && !mode.query().is_empty(),
+        Mode::UndoTree(ref mut mode) => mode.results().count() > 0 && !mode.query().is_empty(),
         _ => bail!("Can't pop search token outside of search select mode"),
     };
 
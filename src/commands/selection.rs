@@ -1,12 +1,24 @@
 use super::application;
 use crate::commands::{self, Result};
 use crate::errors::*;
-use crate::models::application::{Application, ClipboardContent, Mode};
+use crate::models::application::modes::ShellCommandAction;
+use crate::models::application::{Application, ClipboardContent, Mode, ModeKey, SecondaryCursor};
 use crate::util;
 use crate::util::reflow::Reflow;
-use scribe::buffer::{LineRange, Range};
+use scribe::buffer::{Buffer, LineRange, Position, Range};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use unicode_segmentation::UnicodeSegmentation;
 
 pub fn delete(app: &mut Application) -> Result {
+    if let Mode::SelectBlock(_) = app.mode {
+        return delete_block(app);
+    }
+
+    util::multi_cursor::apply(app, delete_selection)
+}
+
+fn delete_selection(app: &mut Application) -> Result {
     let rng = sel_to_range(app)?;
     let buf = app.workspace.current_buffer.as_mut().unwrap();
     buf.delete_range(rng.clone());
@@ -15,6 +27,26 @@ pub fn delete(app: &mut Application) -> Result {
     Ok(())
 }
 
+fn delete_block(app: &mut Application) -> Result {
+    let ranges = block_ranges(app)?;
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+
+    // Delete from the bottom up, so that rows don't shift one another.
+    buffer.start_operation_group();
+    for range in ranges.iter().rev() {
+        buffer.delete_range(range.clone());
+    }
+    if let Some(range) = ranges.first() {
+        buffer.cursor.move_to(range.start());
+    }
+
+    commands::buffer::end_command_group(app)
+}
+
 pub fn copy_and_delete(app: &mut Application) -> Result {
     let _ = copy_to_clipboard(app);
     delete(app)
@@ -22,7 +54,24 @@ pub fn copy_and_delete(app: &mut Application) -> Result {
 
 pub fn change(app: &mut Application) -> Result {
     let _ = copy_to_clipboard(app);
+
+    // Block changes are typed on every row, using a cursor for each of them.
+    let rows = match app.mode {
+        Mode::SelectBlock(_) => block_ranges(app)?,
+        _ => Vec::new(),
+    };
     delete(app)?;
+    if let Some(buffer) = app.workspace.current_buffer.as_ref() {
+        for range in rows.iter().skip(1) {
+            app.cursors.add(
+                buffer,
+                SecondaryCursor {
+                    position: range.start(),
+                    anchor: range.start(),
+                },
+            );
+        }
+    }
     application::switch_to_insert_mode(app)?;
     commands::view::scroll_to_cursor(app)
 }
@@ -61,43 +110,336 @@ pub fn justify(app: &mut Application) -> Result {
 
     buffer.start_operation_group();
     Reflow::new(buffer, range, limit)?.apply()?;
-    buffer.end_operation_group();
+    commands::buffer::end_command_group(app)?;
     application::switch_to_normal_mode(app)
 }
 
+pub fn toggle_block_comment(app: &mut Application) -> Result {
+    util::multi_cursor::apply(app, toggle_selection_block_comment)
+}
+
+// Wraps the selection in the buffer type's block comment delimiters or, if
+// it's already wrapped in them, unwraps it. Whitespace surrounding the
+// selected text (e.g. indentation or a trailing newline) is left outside.
+fn toggle_selection_block_comment(app: &mut Application) -> Result {
+    let range = sel_to_range(app)?;
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let (prefix, suffix) = {
+        let path = buffer.path.as_ref().context(BUFFER_PATH_MISSING)?;
+        let preferences = app.preferences.borrow();
+
+        preferences
+            .block_comment_prefix(path)
+            .zip(preferences.block_comment_suffix(path))
+            .context("No block comment delimiters for the current buffer")?
+    };
+
+    let content = buffer
+        .read(&range)
+        .context("Couldn't read selected data from buffer")?;
+    let leading_content = content.trim_start();
+    let body = leading_content.trim_end();
+    if body.is_empty() {
+        bail!("Can't comment a blank selection");
+    }
+    let start = advance(
+        range.start(),
+        &content[..content.len() - leading_content.len()],
+    );
+    let body_range = Range::new(start, advance(start, body));
+
+    let replacement = match body
+        .strip_prefix(prefix.as_str())
+        .and_then(|body| body.strip_suffix(suffix.as_str()))
+    {
+        Some(comment) => {
+            // Remove the spaces added when the comment was created.
+            let comment = comment.strip_prefix(' ').unwrap_or(comment);
+            comment.strip_suffix(' ').unwrap_or(comment).to_string()
+        }
+        None => format!("{prefix} {body} {suffix}"),
+    };
+
+    buffer.start_operation_group();
+    buffer.delete_range(body_range);
+    buffer.cursor.move_to(start);
+    buffer.insert(replacement);
+    commands::buffer::end_command_group(app)
+}
+
+// The position reached by moving past the text from the specified position.
+fn advance(mut position: Position, text: &str) -> Position {
+    for grapheme in text.graphemes(true) {
+        if grapheme == "\n" {
+            position.line += 1;
+            position.offset = 0;
+        } else {
+            position.offset += 1;
+        }
+    }
+
+    position
+}
+
+pub fn sort_lines(app: &mut Application) -> Result {
+    transform_selected_lines(app, |lines| lines.sort())
+}
+
+pub fn sort_lines_case_insensitive(app: &mut Application) -> Result {
+    transform_selected_lines(app, |lines| lines.sort_by_key(|line| line.to_lowercase()))
+}
+
+pub fn sort_lines_numerically(app: &mut Application) -> Result {
+    transform_selected_lines(app, |lines| {
+        // Lines without a leading number are kept in order, after the rest.
+        lines.sort_by(|a, b| match (leading_number(a), leading_number(b)) {
+            (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        })
+    })
+}
+
+pub fn sort_lines_in_reverse(app: &mut Application) -> Result {
+    transform_selected_lines(app, |lines| lines.sort_by(|a, b| b.cmp(a)))
+}
+
+pub fn unique_lines(app: &mut Application) -> Result {
+    transform_selected_lines(app, |lines| {
+        let mut seen = HashSet::new();
+        lines.retain(|line| seen.insert(*line));
+    })
+}
+
+pub fn reverse_lines(app: &mut Application) -> Result {
+    transform_selected_lines(app, |lines| lines.reverse())
+}
+
+/// Prompts for a delimiter on which to align the selected lines.
+pub fn align(app: &mut Application) -> Result {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let range = match app.mode {
+        Mode::SelectLine(ref mode) => {
+            util::inclusive_range(&LineRange::new(mode.anchor, buffer.cursor.line), buffer)
+        }
+        _ => bail!("A line selection is required."),
+    };
+
+    app.switch_to(ModeKey::Align);
+    if let Mode::Align(ref mut mode) = app.mode {
+        mode.reset(range);
+    }
+
+    Ok(())
+}
+
+// Replaces the lines selected in select line mode with their transformed
+// equivalent, as a single operation.
+fn transform_selected_lines<F>(app: &mut Application, transform: F) -> Result
+where
+    F: FnOnce(&mut Vec<&str>),
+{
+    // These commands are run from command mode, so return
+    // to the line selection it was entered from, if any.
+    if let (Mode::Command(_), ModeKey::SelectLine) = (&app.mode, app.previous_mode()) {
+        app.switch_to_previous_mode();
+    }
+
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let range = match app.mode {
+        Mode::SelectLine(ref mode) => {
+            util::inclusive_range(&LineRange::new(mode.anchor, buffer.cursor.line), buffer)
+        }
+        _ => bail!("A line selection is required."),
+    };
+
+    transform_lines(app, range, |data| {
+        let mut lines: Vec<&str> = data.split('\n').collect();
+        transform(&mut lines);
+        lines.join("\n")
+    })
+}
+
+/// Replaces the lines in the range with their transformed equivalent, as a
+/// single operation. A trailing newline isn't considered part of the last
+/// line, so it stays in place.
+pub fn transform_lines<F>(
+    app: &mut Application,
+    range: Range,
+    transform: F,
+) -> std::result::Result<(), Error>
+where
+    F: FnOnce(&str) -> String,
+{
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let content = buffer
+        .read(&range)
+        .context("Couldn't read selected data from buffer")?;
+    let (data, trailing_newline) = match content.strip_suffix('\n') {
+        Some(data) => (data, "\n"),
+        None => (content.as_str(), ""),
+    };
+
+    let transformed_content = transform(data) + trailing_newline;
+    if transformed_content == content {
+        return Ok(());
+    }
+
+    let cursor_position = *buffer.cursor.clone();
+    buffer.start_operation_group();
+    buffer.delete_range(range.clone());
+    buffer.cursor.move_to(range.start());
+    buffer.insert(transformed_content);
+    if !buffer.cursor.move_to(cursor_position) {
+        // Removing lines can leave the cursor beyond the end of the buffer.
+        buffer.cursor.move_to_last_line();
+    }
+
+    commands::buffer::end_command_group(app)
+}
+
+// Parses the number at the start of a line, ignoring leading whitespace.
+fn leading_number(line: &str) -> Option<f64> {
+    let line = line.trim_start();
+    let length = line
+        .char_indices()
+        .take_while(|&(index, character)| {
+            character.is_ascii_digit() || character == '.' || (index == 0 && character == '-')
+        })
+        .count();
+
+    line[..length].parse().ok()
+}
+
 fn copy_to_clipboard(app: &mut Application) -> Result {
     let buffer = app
         .workspace
         .current_buffer
         .as_mut()
         .context(BUFFER_MISSING)?;
+    let secondary_cursors = app.cursors.for_buffer(buffer).to_vec();
 
     match app.mode {
         Mode::Select(ref select_mode) => {
             let cursor_position = *buffer.cursor.clone();
-            let selected_range = Range::new(cursor_position, select_mode.anchor);
-
-            let data = buffer
-                .read(&selected_range)
-                .context("Couldn't read selected data from buffer")?;
+            let mut selected_ranges = vec![Range::new(cursor_position, select_mode.anchor)];
+            selected_ranges.extend(
+                secondary_cursors
+                    .iter()
+                    .map(|cursor| Range::new(cursor.position, cursor.anchor)),
+            );
+
+            let data = read_ranges(buffer, selected_ranges, "\n")?;
             app.clipboard.set_content(ClipboardContent::Inline(data))?;
         }
         Mode::SelectLine(ref mode) => {
-            let selected_range =
-                util::inclusive_range(&LineRange::new(mode.anchor, buffer.cursor.line), buffer);
+            let mut selected_ranges = vec![util::inclusive_range(
+                &LineRange::new(mode.anchor, buffer.cursor.line),
+                buffer,
+            )];
+            for cursor in secondary_cursors.iter() {
+                selected_ranges.push(util::inclusive_range(
+                    &LineRange::new(cursor.anchor.line, cursor.position.line),
+                    buffer,
+                ));
+            }
 
-            let data = buffer
-                .read(&selected_range)
-                .context("Couldn't read selected data from buffer")?;
+            let data = read_ranges(buffer, selected_ranges, "")?;
             app.clipboard.set_content(ClipboardContent::Block(data))?;
         }
+        Mode::SelectBlock(ref mode) => {
+            let tab_width = app.preferences.borrow().tab_width(buffer.path.as_ref());
+            let selected_ranges = mode.to_ranges(&buffer.cursor, &buffer.data(), tab_width);
+
+            let data = read_ranges(buffer, selected_ranges, "\n")?;
+            app.clipboard
+                .set_content(ClipboardContent::BlockColumn(data))?;
+        }
         _ => bail!("Can't copy data to clipboard outside of select modes"),
     };
 
     Ok(())
 }
 
-fn sel_to_range(app: &mut Application) -> std::result::Result<Range, Error> {
+// Reads the specified ranges in document order, joined by the separator.
+// There's typically a single range, unless there are multiple cursors.
+fn read_ranges(
+    buffer: &Buffer,
+    mut ranges: Vec<Range>,
+    separator: &str,
+) -> std::result::Result<String, Error> {
+    ranges.sort_by(|a, b| a.start().partial_cmp(&b.start()).unwrap_or(Ordering::Equal));
+    ranges.dedup();
+
+    ranges
+        .iter()
+        .map(|range| {
+            buffer
+                .read(range)
+                .context("Couldn't read selected data from buffer")
+        })
+        .collect::<std::result::Result<Vec<_>, Error>>()
+        .map(|data| data.join(separator))
+}
+
+/// The selected portion of each line in select block mode, from top to bottom.
+pub fn block_ranges(app: &Application) -> std::result::Result<Vec<Range>, Error> {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+
+    match app.mode {
+        Mode::SelectBlock(ref mode) => {
+            let tab_width = app.preferences.borrow().tab_width(buffer.path.as_ref());
+            Ok(mode.to_ranges(&buffer.cursor, &buffer.data(), tab_width))
+        }
+        _ => bail!("A block selection is required."),
+    }
+}
+
+/// Prompts for a shell command through which to filter the selection,
+/// or the entire buffer outside of select modes.
+pub fn filter_through_command(app: &mut Application) -> Result {
+    let range = match app.mode {
+        Mode::SelectBlock(_) => bail!("Block selections can't be filtered"),
+        Mode::Select(_) | Mode::SelectLine(_) | Mode::Search(_) => Some(sel_to_range(app)?),
+        _ => {
+            app.workspace
+                .current_buffer
+                .as_ref()
+                .context(BUFFER_MISSING)?;
+            None
+        }
+    };
+
+    app.switch_to(ModeKey::ShellCommand);
+    if let Mode::ShellCommand(ref mut mode) = app.mode {
+        mode.reset(ShellCommandAction::Filter(range));
+    }
+
+    Ok(())
+}
+
+pub fn sel_to_range(app: &mut Application) -> std::result::Result<Range, Error> {
     let buf = app
         .workspace
         .current_buffer
@@ -126,7 +468,8 @@ fn sel_to_range(app: &mut Application) -> std::result::Result<Range, Error> {
 #[cfg(test)]
 mod tests {
     use crate::commands;
-    use crate::models::application::{Application, Mode};
+    use crate::models::application::modes::SearchSelectMode;
+    use crate::models::application::{Application, ClipboardContent, Mode, SecondaryCursor};
     use scribe::buffer::Position;
     use scribe::Buffer;
 
@@ -209,6 +552,59 @@ mod tests {
         )
     }
 
+    #[test]
+    fn delete_removes_the_block_in_select_block_mode() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\ne\tditor\nbuffer");
+        buffer.cursor.move_to(Position { line: 0, offset: 1 });
+
+        // Now that we've set up the buffer, add it to the
+        // application, select a block, and call the command.
+        app.workspace.add_buffer(buffer);
+        commands::application::switch_to_select_block_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 2, offset: 3 });
+        commands::selection::delete(&mut app).unwrap();
+
+        // Ensure that each row is removed, counting tabs by their width.
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            String::from("a\neitor\nbfer")
+        );
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position { line: 0, offset: 1 }
+        );
+    }
+
+    #[test]
+    fn copy_in_select_block_mode_copies_each_row_as_block_column_content() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer");
+        buffer.cursor.move_to(Position { line: 0, offset: 1 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_block_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 2, offset: 3 });
+        commands::selection::copy(&mut app).unwrap();
+
+        match *app.clipboard.get_content() {
+            ClipboardContent::BlockColumn(ref content) => assert_eq!(content, "mp\ndi\nuf"),
+            _ => panic!("Clipboard doesn't contain block column content."),
+        }
+    }
+
     #[test]
     fn delete_removes_the_current_result_in_search_mode() {
         let mut app = Application::new(&Vec::new()).unwrap();
@@ -236,4 +632,227 @@ mod tests {
             String::from("amp\nitor\nbuffer")
         )
     }
+
+    #[test]
+    fn copy_joins_secondary_selections_in_document_order() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer");
+        app.workspace.add_buffer(buffer);
+
+        // Select "amp" and add the start of the last line as a secondary selection.
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 0, offset: 3 });
+        let buffer = app.workspace.current_buffer.as_ref().unwrap();
+        app.cursors.add(
+            buffer,
+            SecondaryCursor {
+                position: Position { line: 2, offset: 0 },
+                anchor: Position { line: 2, offset: 3 },
+            },
+        );
+        commands::selection::copy(&mut app).unwrap();
+
+        match *app.clipboard.get_content() {
+            ClipboardContent::Inline(ref content) => assert_eq!(content, "amp\nbuf"),
+            _ => panic!("Clipboard doesn't contain inline content."),
+        }
+    }
+
+    #[test]
+    fn delete_removes_all_results_after_adding_cursors_at_search_results() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\namp");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_search_mode(&mut app).unwrap();
+        if let Mode::Search(ref mut mode) = app.mode {
+            mode.input = Some("amp".into());
+        }
+        commands::search::accept_query(&mut app).unwrap();
+        commands::cursor::add_cursors_at_search_results(&mut app).unwrap();
+        commands::selection::delete(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            String::from("\neditor\n")
+        )
+    }
+
+    #[test]
+    fn sort_lines_sorts_the_selected_lines_as_a_single_operation() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("zeta\ncharlie\nBravo\nalpha\n");
+        buffer.cursor.move_to(Position { line: 1, offset: 0 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::cursor::move_down(&mut app).unwrap();
+        commands::cursor::move_down(&mut app).unwrap();
+        super::sort_lines(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "zeta\nBravo\nalpha\ncharlie\n"
+        );
+
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "zeta\ncharlie\nBravo\nalpha\n"
+        );
+    }
+
+    #[test]
+    fn sort_lines_sorts_the_line_selection_command_mode_was_entered_from() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("zeta\ncharlie\nBravo\nalpha\n");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::cursor::move_down(&mut app).unwrap();
+        commands::application::switch_to_command_mode(&mut app).unwrap();
+        if let Mode::Command(ref mut mode) = app.mode {
+            for c in "selection::sort_lines".chars() {
+                mode.push_search_char(c);
+            }
+            mode.search();
+        }
+        commands::search_select::accept(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "charlie\nzeta\nBravo\nalpha\n"
+        );
+        assert!(matches!(app.mode, Mode::Normal(_)));
+    }
+
+    #[test]
+    fn sort_lines_variants_order_lines_without_a_trailing_newline() {
+        let sorts: [(commands::Command, &str); 3] = [
+            (super::sort_lines_case_insensitive, "10 A\n2 a\nb\nC"),
+            (super::sort_lines_numerically, "2 a\n10 A\nb\nC"),
+            (super::sort_lines_in_reverse, "b\nC\n2 a\n10 A"),
+        ];
+
+        for (sort, expected) in sorts {
+            let mut app = Application::new(&Vec::new()).unwrap();
+            let mut buffer = Buffer::new();
+            buffer.insert("b\n10 A\nC\n2 a");
+            app.workspace.add_buffer(buffer);
+
+            commands::application::switch_to_select_line_mode(&mut app).unwrap();
+            commands::cursor::move_to_last_line(&mut app).unwrap();
+            sort(&mut app).unwrap();
+            assert_eq!(
+                app.workspace.current_buffer.as_ref().unwrap().data(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn unique_lines_removes_later_duplicates_of_selected_lines() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\namp\neditor");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::cursor::move_to_last_line(&mut app).unwrap();
+        super::unique_lines(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp\neditor"
+        );
+    }
+
+    #[test]
+    fn reverse_lines_reverses_the_selected_lines() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp\neditor\nbuffer");
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::cursor::move_down(&mut app).unwrap();
+        super::reverse_lines(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "editor\namp\nbuffer"
+        );
+    }
+
+    #[test]
+    fn toggle_block_comment_wraps_selected_lines_inside_their_indentation() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("<div>\n  <p>amp</p>\n  <p>editor</p>\n</div>\n");
+        buffer.path = Some("index.html".into());
+        buffer.cursor.move_to(Position { line: 1, offset: 0 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::cursor::move_down(&mut app).unwrap();
+        super::toggle_block_comment(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "<div>\n  <!-- <p>amp</p>\n  <p>editor</p> -->\n</div>\n"
+        );
+
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "<div>\n  <p>amp</p>\n  <p>editor</p>\n</div>\n"
+        );
+    }
+
+    #[test]
+    fn toggle_block_comment_unwraps_a_commented_selection() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("a { /* color: red; */ }");
+        buffer.path = Some("style.css".into());
+        buffer.cursor.move_to(Position { line: 0, offset: 4 });
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position {
+                line: 0,
+                offset: 21,
+            });
+        super::toggle_block_comment(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "a { color: red; }"
+        );
+    }
+
+    #[test]
+    fn toggle_block_comment_fails_without_delimiters_for_the_buffer_type() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+        buffer.path = Some("notes.txt".into());
+        app.workspace.add_buffer(buffer);
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        assert!(super::toggle_block_comment(&mut app).is_err());
+        assert_eq!(app.workspace.current_buffer.as_ref().unwrap().data(), "amp");
+    }
 }
@@ -0,0 +1,312 @@
+use crate::commands::{self, Result};
+use crate::errors::*;
+use crate::input::Key;
+use crate::models::application::modes::ShellCommandAction;
+use crate::models::application::{Application, Mode, ModeKey};
+use crate::util;
+use scribe::buffer::Range;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+pub fn push_char(app: &mut Application) -> Result {
+    let last_key = app
+        .view
+        .last_key()
+        .as_ref()
+        .context("View hasn't tracked a key press")?;
+    if let Key::Char(c) = *last_key {
+        if let Mode::ShellCommand(ref mut mode) = app.mode {
+            mode.push_char(c);
+        } else {
+            bail!("Cannot push char outside of shell command mode");
+        }
+    } else {
+        bail!("Last key press wasn't a character");
+    }
+    Ok(())
+}
+
+pub fn pop_char(app: &mut Application) -> Result {
+    if let Mode::ShellCommand(ref mut mode) = app.mode {
+        mode.pop_char();
+    } else {
+        bail!("Cannot pop char outside of shell command mode");
+    }
+    Ok(())
+}
+
+/// Runs the entered shell command in the workspace directory, using its output
+/// as described by the mode's action, as a single operation. The prompt is
+/// left open if the command fails, so that it can be fixed.
+pub fn accept_command(app: &mut Application) -> Result {
+    let (command, action) = match app.mode {
+        Mode::ShellCommand(ref mode) => (mode.input.clone(), mode.action.clone()),
+        _ => bail!("Cannot accept command outside of shell command mode"),
+    };
+    if command.trim().is_empty() {
+        bail!("Please provide a command to run");
+    }
+
+    match action {
+        ShellCommandAction::Insert => insert_output(app, &command)?,
+        ShellCommandAction::Filter(range) => filter(app, &command, range)?,
+    }
+
+    commands::view::scroll_to_cursor(app)
+}
+
+// Inserts the command's output at the cursor, without the trailing
+// newline most commands add, so that it doesn't split the line.
+fn insert_output(app: &mut Application, command: &str) -> std::result::Result<(), Error> {
+    app.workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    let mut output = run(command, String::new(), &app.workspace.path)?;
+    if output.ends_with('\n') {
+        output.pop();
+    }
+
+    app.switch_to(ModeKey::Normal);
+    util::group_changes(app, |app| {
+        let buffer = app
+            .workspace
+            .current_buffer
+            .as_mut()
+            .context(BUFFER_MISSING)?;
+        buffer.insert(output);
+
+        Ok(())
+    })
+}
+
+// Pipes the range (or the entire buffer) through
+// the command, replacing it with the command's output.
+fn filter(
+    app: &mut Application,
+    command: &str,
+    range: Option<Range>,
+) -> std::result::Result<(), Error> {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let input = match range {
+        Some(ref range) => buffer
+            .read(range)
+            .context("Couldn't read selected data from buffer")?,
+        None => buffer.data(),
+    };
+    let mut output = run(command, input.clone(), &app.workspace.path)?;
+
+    // Commands tend to terminate their output with a newline;
+    // drop it if the input didn't have one (e.g. part of a line).
+    if !input.ends_with('\n') && output.ends_with('\n') {
+        output.pop();
+    }
+
+    app.switch_to(ModeKey::Normal);
+    util::group_changes(app, |app| {
+        let buffer = app
+            .workspace
+            .current_buffer
+            .as_mut()
+            .context(BUFFER_MISSING)?;
+
+        match range {
+            Some(range) if output != input => {
+                buffer.delete_range(range.clone());
+                buffer.cursor.move_to(range.start());
+                buffer.insert(output);
+            }
+            Some(_) => (),
+            None => buffer.replace(output),
+        }
+
+        Ok(())
+    })
+}
+
+// Runs the command using the shell, returning its output, or its
+// error output as an error if it exits with a non-zero status.
+fn run(command: &str, input: String, dir: &Path) -> std::result::Result<String, Error> {
+    let mut process = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to spawn shell command")?;
+
+    // Write from another thread, so that large
+    // output doesn't block the command's progress.
+    let mut stdin = process.stdin.take().context("Failed to open stdin")?;
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    let output = process
+        .wait_with_output()
+        .context("Failed to read stdout")?;
+    let _ = writer.join();
+
+    if output.status.success() {
+        String::from_utf8(output.stdout).context("Failed to parse command output as UTF8")
+    } else {
+        let error = String::from_utf8_lossy(&output.stderr);
+        if error.trim().is_empty() {
+            bail!("Shell command failed with {}", output.status);
+        }
+
+        bail!("{}", error.trim())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands;
+    use crate::commands::test_support::set_up_application;
+    use crate::models::application::{Application, Mode};
+    use scribe::buffer::Position;
+
+    fn filter(app: &mut Application, command: &str) -> super::Result {
+        commands::selection::filter_through_command(app).unwrap();
+        accept(app, command)
+    }
+
+    fn insert_output(app: &mut Application, command: &str) -> super::Result {
+        commands::buffer::insert_command_output(app).unwrap();
+        accept(app, command)
+    }
+
+    fn accept(app: &mut Application, command: &str) -> super::Result {
+        if let Mode::ShellCommand(ref mut mode) = app.mode {
+            mode.input = command.to_string();
+        }
+
+        super::accept_command(app)
+    }
+
+    #[test]
+    fn accept_command_replaces_selected_lines_with_the_output() {
+        let mut app = set_up_application("amp\nc\nb\na\n");
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 1, offset: 0 });
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        commands::cursor::move_down(&mut app).unwrap();
+        commands::cursor::move_down(&mut app).unwrap();
+
+        filter(&mut app, "sort").unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp\na\nb\nc\n"
+        );
+        assert!(matches!(app.mode, Mode::Normal(_)));
+
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp\nc\nb\na\n"
+        );
+    }
+
+    #[test]
+    fn accept_command_keeps_partial_lines_free_of_trailing_newlines() {
+        let mut app = set_up_application("amp editor");
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 0, offset: 3 });
+
+        filter(&mut app, "tr a-z A-Z").unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "AMP editor"
+        );
+    }
+
+    #[test]
+    fn accept_command_filters_the_buffer_outside_of_select_modes() {
+        let mut app = set_up_application("amp\neditor\n");
+
+        filter(&mut app, "tr a-z A-Z").unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "AMP\nEDITOR\n"
+        );
+    }
+
+    #[test]
+    fn accept_command_reports_errors_and_stays_in_shell_command_mode() {
+        let mut app = set_up_application("amp\n");
+
+        let error = filter(&mut app, "echo 'bad input' >&2; exit 1").unwrap_err();
+        assert_eq!(error.to_string(), "bad input");
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp\n"
+        );
+        assert!(matches!(app.mode, Mode::ShellCommand(_)));
+    }
+
+    #[test]
+    fn accept_command_inserts_the_output_at_the_cursor() {
+        let mut app = set_up_application("amp editor");
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 0, offset: 4 });
+
+        insert_output(&mut app, "printf 'text '").unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp text editor"
+        );
+        assert!(matches!(app.mode, Mode::Normal(_)));
+
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp editor"
+        );
+    }
+
+    #[test]
+    fn accept_command_inserts_output_mid_line_without_its_trailing_newline() {
+        let mut app = set_up_application("amp editor");
+        app.workspace
+            .current_buffer
+            .as_mut()
+            .unwrap()
+            .cursor
+            .move_to(Position { line: 0, offset: 4 });
+
+        insert_output(&mut app, "echo 'text '").unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "amp text editor"
+        );
+    }
+
+    #[test]
+    fn accept_command_runs_commands_in_the_workspace_directory() {
+        let mut app = set_up_application("");
+
+        insert_output(&mut app, "pwd").unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            app.workspace.path.to_string_lossy()
+        );
+    }
+}
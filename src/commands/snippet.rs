@@ -0,0 +1,139 @@
+use crate::commands::{self, Result};
+use crate::errors::*;
+use crate::models::application::Application;
+use crate::util;
+use crate::util::snippet::Snippet;
+use scribe::buffer::{Position, Range};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Replaces the word before the cursor with the snippet it triggers for the
+/// buffer's file type, moving the cursor to the snippet's first tab stop.
+/// The replacement is undone as a single operation.
+pub fn expand(app: &mut Application) -> Result {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let data = buffer.data();
+    let line = data.lines().nth(buffer.cursor.line).unwrap_or_default();
+    let preceding: String = line.graphemes(true).take(buffer.cursor.offset).collect();
+    let trigger = preceding
+        .rsplit(char::is_whitespace)
+        .next()
+        .unwrap_or_default();
+    if trigger.is_empty() {
+        bail!("No snippet trigger before the cursor");
+    }
+
+    let Some(template) = app.snippets.get(buffer.path.as_ref(), trigger) else {
+        bail!("No \"{trigger}\" snippet for this file type");
+    };
+    let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+    let snippet = Snippet::parse(template, &indent)?;
+
+    let start = Position {
+        line: buffer.cursor.line,
+        offset: buffer.cursor.offset - trigger.graphemes(true).count(),
+    };
+    util::group_changes(app, |app| {
+        let buffer = app
+            .workspace
+            .current_buffer
+            .as_mut()
+            .context(BUFFER_MISSING)?;
+        buffer.delete_range(Range::new(start, *buffer.cursor));
+        buffer.cursor.move_to(start);
+        app.snippets.expand(buffer, snippet);
+
+        Ok(())
+    })?;
+
+    commands::view::scroll_to_cursor(app)
+}
+
+/// Moves the cursor to the expanded snippet's next tab stop,
+/// or inserts a tab if there isn't a snippet being filled in.
+pub fn next_stop(app: &mut Application) -> Result {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    if !app.snippets.next_stop(buffer) {
+        return commands::buffer::insert_tab(app);
+    }
+
+    commands::view::scroll_to_cursor(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands;
+    use crate::commands::test_support;
+    use scribe::buffer::Position;
+    use std::path::PathBuf;
+
+    #[test]
+    fn expand_replaces_the_trigger_with_an_indented_snippet() {
+        let mut app =
+            test_support::set_up_application_at("    test\n", Position { line: 0, offset: 8 });
+        app.workspace.current_buffer.as_mut().unwrap().path = Some(PathBuf::from("amp.rs"));
+        app.snippets
+            .add("rs", "test", "#[test]\nfn ${1:name}() {\n    $0\n}\n");
+
+        commands::snippet::expand(&mut app).unwrap();
+        let buffer = app.workspace.current_buffer.as_ref().unwrap();
+        assert_eq!(
+            buffer.data(),
+            "    #[test]\n    fn name() {\n        \n    }\n"
+        );
+        assert_eq!(*buffer.cursor, Position { line: 1, offset: 7 });
+
+        commands::snippet::next_stop(&mut app).unwrap();
+        let buffer = app.workspace.current_buffer.as_ref().unwrap();
+        assert_eq!(*buffer.cursor, Position { line: 2, offset: 8 });
+    }
+
+    #[test]
+    fn expand_is_undone_as_a_single_operation() {
+        let mut app =
+            test_support::set_up_application_at("    test\n", Position { line: 0, offset: 8 });
+        app.workspace.current_buffer.as_mut().unwrap().path = Some(PathBuf::from("amp.rs"));
+        app.snippets
+            .add("rs", "test", "#[test]\nfn ${1:name}() {\n    $0\n}\n");
+
+        commands::snippet::expand(&mut app).unwrap();
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "    test\n"
+        );
+    }
+
+    #[test]
+    fn expand_fails_without_a_matching_snippet() {
+        let mut app = test_support::set_up_application_at("tests", Position { line: 0, offset: 5 });
+        app.workspace.current_buffer.as_mut().unwrap().path = Some(PathBuf::from("amp.rs"));
+        app.snippets
+            .add("rs", "test", "#[test]\nfn ${1:name}() {\n    $0\n}\n");
+
+        let error = commands::snippet::expand(&mut app).unwrap_err();
+        assert_eq!(error.to_string(), "No \"tests\" snippet for this file type");
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "tests"
+        );
+    }
+
+    #[test]
+    fn next_stop_inserts_a_tab_without_an_expanded_snippet() {
+        let mut app = test_support::set_up_application("amp");
+
+        commands::snippet::next_stop(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "  amp"
+        );
+    }
+}
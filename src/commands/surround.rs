@@ -0,0 +1,336 @@
+use crate::commands::{self, Result};
+use crate::errors::*;
+use crate::input::Key;
+use crate::models::application::modes::SurroundAction;
+use crate::models::application::{Application, Mode, ModeKey};
+use crate::util;
+use crate::util::text_object::{self, TextObject};
+use scribe::buffer::{LineRange, Position, Range};
+
+pub fn add(app: &mut Application) -> Result {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let (range, linewise) = match app.mode {
+        Mode::Select(ref mode) => (Range::new(mode.anchor, *buffer.cursor), false),
+        Mode::SelectLine(ref mode) => (
+            util::inclusive_range(&LineRange::new(mode.anchor, buffer.cursor.line), buffer),
+            true,
+        ),
+        _ => bail!("A selection is required."),
+    };
+
+    switch_to_surround_mode(app, SurroundAction::Add, Some(range), linewise)
+}
+
+pub fn change(app: &mut Application) -> Result {
+    switch_to_surround_mode(app, SurroundAction::Change, None, false)
+}
+
+pub fn delete(app: &mut Application) -> Result {
+    switch_to_surround_mode(app, SurroundAction::Delete, None, false)
+}
+
+pub fn accept_delimiter(app: &mut Application) -> Result {
+    let character = match *app.view.last_key() {
+        Some(Key::Char(c)) => c,
+        _ => bail!("Last key press wasn't a character"),
+    };
+    let mode = match app.mode {
+        Mode::Surround(ref mut mode) => mode,
+        _ => bail!("Can't accept a delimiter outside of surround mode"),
+    };
+
+    // Characters make up the tag name, once we're prompting for one.
+    if let Some(ref mut tag) = mode.tag {
+        tag.push(character);
+
+        return Ok(());
+    }
+
+    let action = mode.action;
+    match (action, mode.range.clone()) {
+        (SurroundAction::Add, Some(range)) | (SurroundAction::Change, Some(range)) => {
+            if character == 't' {
+                mode.tag = Some(String::new());
+
+                return Ok(());
+            }
+            let (open, close) = delimiters(character)?;
+
+            surround(app, action, range, &open, &close)
+        }
+        (SurroundAction::Change, None) => {
+            let range = surrounding_pair(app, character)?;
+            if let Mode::Surround(ref mut mode) = app.mode {
+                mode.range = Some(range);
+            }
+
+            Ok(())
+        }
+        (SurroundAction::Delete, _) => {
+            let range = surrounding_pair(app, character)?;
+
+            surround(app, action, range, "", "")
+        }
+        (SurroundAction::Add, None) => bail!("Nothing to surround"),
+    }
+}
+
+pub fn accept_tag(app: &mut Application) -> Result {
+    let (action, range, tag) = match app.mode {
+        Mode::Surround(ref mut mode) => (
+            mode.action,
+            mode.range.clone().context("Nothing to surround")?,
+            mode.tag.take().context("No tag to accept")?,
+        ),
+        _ => bail!("Can't accept a tag outside of surround mode"),
+    };
+
+    // Attributes are only included in the opening tag.
+    let name = tag
+        .split_whitespace()
+        .next()
+        .context("A tag name is required")?;
+    let (open, close) = (format!("<{tag}>"), format!("</{name}>"));
+
+    surround(app, action, range, &open, &close)
+}
+
+pub fn cancel(app: &mut Application) -> Result {
+    app.switch_to_previous_mode();
+
+    Ok(())
+}
+
+pub fn pop_tag_char(app: &mut Application) -> Result {
+    if let Mode::Surround(ref mut mode) = app.mode {
+        if let Some(ref mut tag) = mode.tag {
+            tag.pop();
+        }
+    }
+
+    Ok(())
+}
+
+fn switch_to_surround_mode(
+    app: &mut Application,
+    action: SurroundAction,
+    range: Option<Range>,
+    linewise: bool,
+) -> Result {
+    app.workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    app.switch_to(ModeKey::Surround);
+    if let Mode::Surround(ref mut mode) = app.mode {
+        mode.reset(action, range, linewise);
+    }
+
+    Ok(())
+}
+
+// Maps a key to the delimiters it represents.
+fn delimiters(character: char) -> std::result::Result<(String, String), Error> {
+    match TextObject::from_char(character) {
+        Some(TextObject::Pair(open, close)) => Ok((open.to_string(), close.to_string())),
+        Some(TextObject::Quote(quote)) => Ok((quote.to_string(), quote.to_string())),
+        _ => bail!("Can't surround with \"{character}\""),
+    }
+}
+
+// Finds the pair around the cursor represented by the key, including its delimiters.
+fn surrounding_pair(app: &mut Application, character: char) -> std::result::Result<Range, Error> {
+    let object = match TextObject::from_char(character) {
+        Some(TextObject::Paragraph) | None => bail!("\"{character}\" isn't a delimiter"),
+        Some(object) => object,
+    };
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+
+    text_object::find(&buffer.data(), &buffer.cursor, object, true)
+        .context("Couldn't find a surrounding pair")
+}
+
+// Surrounds the range with delimiters as a single operation, replacing the
+// existing (single-character) delimiters at its edges if we're not adding.
+fn surround(
+    app: &mut Application,
+    action: SurroundAction,
+    range: Range,
+    open: &str,
+    close: &str,
+) -> Result {
+    let linewise = match app.mode {
+        Mode::Surround(ref mode) => mode.linewise && action == SurroundAction::Add,
+        _ => false,
+    };
+
+    util::group_changes(app, |app| {
+        let buffer = app
+            .workspace
+            .current_buffer
+            .as_mut()
+            .context(BUFFER_MISSING)?;
+        let (start, end) = (range.start(), range.end());
+
+        // Work backwards, so that the start position isn't shifted.
+        if action == SurroundAction::Add {
+            buffer.cursor.move_to(end);
+            if !linewise {
+                buffer.insert(close);
+            } else if end.offset == 0 {
+                buffer.insert(format!("{close}\n"));
+            } else {
+                // The selection ends on the last line, without a newline.
+                buffer.insert(format!("\n{close}"));
+            }
+
+            buffer.cursor.move_to(start);
+            if linewise {
+                buffer.insert(format!("{open}\n"));
+            } else {
+                buffer.insert(open);
+            }
+        } else {
+            let last = Position {
+                line: end.line,
+                offset: end.offset - 1,
+            };
+            buffer.delete_range(Range::new(last, end));
+            if !close.is_empty() {
+                buffer.cursor.move_to(last);
+                buffer.insert(close);
+            }
+
+            let first = Position {
+                line: start.line,
+                offset: start.offset + 1,
+            };
+            buffer.delete_range(Range::new(start, first));
+            if !open.is_empty() {
+                buffer.cursor.move_to(start);
+                buffer.insert(open);
+            }
+        }
+        buffer.cursor.move_to(start);
+
+        Ok(())
+    })?;
+
+    commands::application::switch_to_normal_mode(app)?;
+    commands::view::scroll_to_cursor(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands;
+    use crate::commands::test_support::{press_keys, set_up_application_at};
+    use crate::input::Key;
+    use crate::models::application::Mode;
+    use scribe::buffer::Position;
+
+    #[test]
+    fn add_surrounds_the_selection_with_brackets() {
+        let mut app = set_up_application_at(
+            "let amp = value;",
+            Position {
+                line: 0,
+                offset: 10,
+            },
+        );
+
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        commands::cursor::move_to_end_of_current_token(&mut app).unwrap();
+        super::add(&mut app).unwrap();
+        press_keys(&mut app, &[Key::Char(')')]);
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "let amp = (value);"
+        );
+        assert!(matches!(app.mode, Mode::Normal(_)));
+
+        // The change is undone as a single operation.
+        commands::buffer::undo(&mut app).unwrap();
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "let amp = value;"
+        );
+    }
+
+    #[test]
+    fn add_surrounds_selected_lines_with_a_tag() {
+        let mut app = set_up_application_at("<p>amp</p>\n", Position { line: 0, offset: 0 });
+
+        commands::application::switch_to_select_line_mode(&mut app).unwrap();
+        super::add(&mut app).unwrap();
+        press_keys(&mut app, &[Key::Char('t')]);
+        press_keys(
+            &mut app,
+            &"div class=\"a\"".chars().map(Key::Char).collect::<Vec<_>>(),
+        );
+        super::pop_tag_char(&mut app).unwrap();
+        press_keys(&mut app, &[Key::Char('"')]);
+        super::accept_tag(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "<div class=\"a\">\n<p>amp</p>\n</div>\n"
+        );
+    }
+
+    #[test]
+    fn change_replaces_the_surrounding_pair() {
+        let mut app = set_up_application_at("call(\"amp\")", Position { line: 0, offset: 7 });
+
+        super::change(&mut app).unwrap();
+        press_keys(&mut app, &[Key::Char('"'), Key::Char('[')]);
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "call([amp])"
+        );
+    }
+
+    #[test]
+    fn delete_removes_the_surrounding_pair() {
+        let mut app = set_up_application_at("Some(amp)", Position { line: 0, offset: 6 });
+
+        super::delete(&mut app).unwrap();
+        press_keys(&mut app, &[Key::Char('b')]);
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "Someamp"
+        );
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position { line: 0, offset: 4 }
+        );
+    }
+
+    #[test]
+    fn change_can_be_repeated() {
+        let mut app = set_up_application_at("'a' 'b'", Position { line: 0, offset: 1 });
+
+        for key in ['T', '\'', '"'] {
+            app.view.last_key = Some(Key::Char(key));
+            commands::application::handle_input(&mut app).unwrap();
+        }
+        commands::cursor::move_to_end_of_line(&mut app).unwrap();
+        commands::cursor::move_left(&mut app).unwrap();
+        commands::buffer::repeat_last_change(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "\"a\" \"b\""
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Fixtures shared by the command modules' tests.
+
+use crate::commands;
+use crate::input::Key;
+use crate::models::Application;
+use crate::util;
+use scribe::buffer::Position;
+use scribe::Buffer;
+
+/// Builds an application with a buffer holding the content,
+/// with its cursor at the start of the buffer.
+pub fn set_up_application(content: &str) -> Application {
+    set_up_application_at(content, Position::default())
+}
+
+/// Builds an application with a buffer holding the content,
+/// with its cursor at the specified position.
+pub fn set_up_application_at(content: &str, cursor: Position) -> Application {
+    let mut app = Application::new(&Vec::new()).unwrap();
+    add_buffer(&mut app, content, cursor);
+
+    app
+}
+
+/// Adds and selects an initialized buffer holding the content,
+/// with its cursor at the specified position.
+pub fn add_buffer(app: &mut Application, content: &str, cursor: Position) {
+    let mut buffer = Buffer::new();
+    buffer.insert(content);
+    buffer.cursor.move_to(cursor);
+    util::add_buffer(buffer, app).unwrap();
+}
+
+/// Handles each key as though it had been pressed,
+/// including its capture by an active macro recording.
+pub fn press_keys(app: &mut Application, keys: &[Key]) {
+    for key in keys {
+        app.macros.record(key.clone());
+        app.view.last_key = Some(key.clone());
+        commands::application::handle_input(app).unwrap();
+    }
+}
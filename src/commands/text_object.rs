@@ -0,0 +1,188 @@
+use crate::commands::{self, Result};
+use crate::errors::*;
+use crate::input::Key;
+use crate::models::application::modes::TextObjectAction;
+use crate::models::application::{Application, Mode, ModeKey};
+use crate::util::text_object::{self, TextObject};
+
+pub fn select_inside(app: &mut Application) -> Result {
+    switch_to_text_object_mode(app, TextObjectAction::Select, false)
+}
+
+pub fn select_around(app: &mut Application) -> Result {
+    switch_to_text_object_mode(app, TextObjectAction::Select, true)
+}
+
+pub fn change_inside(app: &mut Application) -> Result {
+    switch_to_text_object_mode(app, TextObjectAction::Change, false)
+}
+
+pub fn change_around(app: &mut Application) -> Result {
+    switch_to_text_object_mode(app, TextObjectAction::Change, true)
+}
+
+pub fn delete_inside(app: &mut Application) -> Result {
+    switch_to_text_object_mode(app, TextObjectAction::Delete, false)
+}
+
+pub fn delete_around(app: &mut Application) -> Result {
+    switch_to_text_object_mode(app, TextObjectAction::Delete, true)
+}
+
+pub fn cancel(app: &mut Application) -> Result {
+    app.switch_to_previous_mode();
+
+    Ok(())
+}
+
+pub fn apply(app: &mut Application) -> Result {
+    let (action, around) = match app.mode {
+        Mode::TextObject(ref mode) => (mode.action, mode.around),
+        _ => bail!("Can't apply a text object outside of text object mode"),
+    };
+    let object = match *app.view.last_key() {
+        Some(Key::Char(c)) => TextObject::from_char(c),
+        _ => None,
+    }
+    .context("Unknown text object")?;
+
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let range = match text_object::find(&buffer.data(), &buffer.cursor, object, around) {
+        Some(range) => range,
+        None => {
+            // Leave the mode, so the error isn't mistaken for a prompt.
+            app.switch_to_previous_mode();
+            bail!("Couldn't find a surrounding text object");
+        }
+    };
+
+    // Select the text object, replacing any existing selection.
+    buffer.cursor.move_to(range.start());
+    commands::application::switch_to_select_mode(app)?;
+    app.workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?
+        .cursor
+        .move_to(range.end());
+
+    match action {
+        TextObjectAction::Select => Ok(()),
+        TextObjectAction::Change => commands::selection::change(app),
+        TextObjectAction::Delete => {
+            commands::selection::copy_and_delete(app)?;
+            commands::application::switch_to_normal_mode(app)
+        }
+    }
+}
+
+fn switch_to_text_object_mode(
+    app: &mut Application,
+    action: TextObjectAction,
+    around: bool,
+) -> Result {
+    app.workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    app.switch_to(ModeKey::TextObject);
+    if let Mode::TextObject(ref mut mode) = app.mode {
+        mode.reset(action, around);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands;
+    use crate::commands::test_support::set_up_application_at;
+    use crate::input::Key;
+    use crate::models::application::Mode;
+    use scribe::buffer::Position;
+
+    #[test]
+    fn change_inside_replaces_bracket_contents_and_enters_insert_mode() {
+        let mut app = set_up_application_at("call(a, b)", Position { line: 0, offset: 6 });
+
+        super::change_inside(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('('));
+        super::apply(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "call()"
+        );
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position { line: 0, offset: 5 }
+        );
+        assert!(matches!(app.mode, Mode::Insert));
+    }
+
+    #[test]
+    fn delete_around_removes_quotes_and_returns_to_normal_mode() {
+        let mut app = set_up_application_at("say \"amp\" now", Position { line: 0, offset: 6 });
+
+        super::delete_around(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('"'));
+        super::apply(&mut app).unwrap();
+
+        assert_eq!(
+            app.workspace.current_buffer.as_ref().unwrap().data(),
+            "say  now"
+        );
+        assert!(matches!(app.mode, Mode::Normal(_)));
+    }
+
+    #[test]
+    fn select_inside_replaces_the_existing_selection() {
+        let mut app = set_up_application_at("{ amp }", Position { line: 0, offset: 0 });
+
+        commands::application::switch_to_select_mode(&mut app).unwrap();
+        commands::cursor::move_right(&mut app).unwrap();
+        commands::cursor::move_right(&mut app).unwrap();
+        super::select_inside(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('}'));
+        super::apply(&mut app).unwrap();
+
+        match app.mode {
+            Mode::Select(ref mode) => assert_eq!(mode.anchor, Position { line: 0, offset: 1 }),
+            _ => panic!("Application isn't in select mode."),
+        }
+        assert_eq!(
+            *app.workspace.current_buffer.as_ref().unwrap().cursor,
+            Position { line: 0, offset: 6 }
+        );
+    }
+
+    #[test]
+    fn apply_returns_to_the_previous_mode_when_no_object_is_found() {
+        let mut app = set_up_application_at("amp", Position { line: 0, offset: 1 });
+
+        super::delete_inside(&mut app).unwrap();
+        app.view.last_key = Some(Key::Char('['));
+
+        assert!(super::apply(&mut app).is_err());
+        assert!(matches!(app.mode, Mode::Normal(_)));
+    }
+
+    #[test]
+    fn delete_around_can_be_repeated() {
+        let mut app = set_up_application_at("(a) (b)", Position { line: 0, offset: 1 });
+
+        for key in ['X', '('] {
+            app.view.last_key = Some(Key::Char(key));
+            commands::application::handle_input(&mut app).unwrap();
+        }
+        commands::cursor::move_right(&mut app).unwrap();
+        commands::cursor::move_right(&mut app).unwrap();
+        commands::buffer::repeat_last_change(&mut app).unwrap();
+
+        assert_eq!(app.workspace.current_buffer.as_ref().unwrap().data(), " ");
+    }
+}
@@ -1,5 +1,6 @@
-use crate::commands::Result;
-use crate::models::application::Application;
+use crate::commands::{self, Result};
+use crate::errors::*;
+use crate::models::application::{Application, Mode, ModeKey};
 use crate::util;
 use scribe::Buffer;
 
@@ -12,3 +13,129 @@ pub fn next_buffer(app: &mut Application) -> Result {
 pub fn new_buffer(app: &mut Application) -> Result {
     util::add_buffer(Buffer::new(), app)
 }
+
+pub fn save_all(app: &mut Application) -> Result {
+    let mut pathless_buffers = 0;
+
+    // Step through every buffer, saving those with modifications. Buffers
+    // without a path are skipped, since prompting for each would interrupt
+    // the process; we report them once we're back at the original buffer.
+    for _ in 0..app.workspace.buffer_paths().len() {
+        let (modified, has_path) = app
+            .workspace
+            .current_buffer
+            .as_ref()
+            .map(|buf| (buf.modified(), buf.path.is_some()))
+            .context(BUFFER_MISSING)?;
+
+        if modified {
+            if has_path {
+                commands::buffer::save(app)?;
+            } else {
+                pathless_buffers += 1;
+            }
+        }
+
+        app.workspace.next_buffer();
+    }
+
+    if pathless_buffers > 0 {
+        bail!("{pathless_buffers} modified buffer(s) without a path couldn't be saved");
+    }
+
+    Ok(())
+}
+
+pub fn close_all(app: &mut Application) -> Result {
+    let confirm_mode = matches!(app.mode, Mode::Confirm(_));
+
+    if !confirm_mode && !util::modified_buffer_paths(&mut app.workspace).is_empty() {
+        // Display a confirmation prompt before closing modified buffers.
+        app.switch_to(ModeKey::Confirm);
+        if let Mode::Confirm(ref mut mode) = app.mode {
+            mode.command = close_all
+        }
+
+        return Ok(());
+    }
+
+    // Clean up view-related data and close buffers until none remain.
+    while let Some(buf) = app.workspace.current_buffer.as_ref() {
+        app.view.forget_buffer(buf)?;
+        app.workspace.close_current_buffer();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::application::{Application, Mode};
+    use scribe::Buffer;
+    use std::env;
+    use std::fs::{self, read_to_string};
+
+    #[test]
+    fn save_all_saves_every_modified_buffer() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let paths = [
+            env::temp_dir().join("amp_save_all_first"),
+            env::temp_dir().join("amp_save_all_second"),
+        ];
+
+        for path in paths.iter() {
+            let mut buffer = Buffer::new();
+            buffer.path = Some(path.clone());
+            buffer.insert("amp");
+            app.workspace.add_buffer(buffer);
+        }
+
+        super::save_all(&mut app).unwrap();
+
+        for path in paths.iter() {
+            assert_eq!(read_to_string(path).unwrap(), "amp\n");
+            fs::remove_file(path).unwrap();
+        }
+        assert!(!app.workspace.current_buffer.as_ref().unwrap().modified());
+    }
+
+    #[test]
+    fn save_all_reports_modified_buffers_without_paths() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+        app.workspace.add_buffer(buffer);
+
+        assert!(super::save_all(&mut app).is_err());
+        assert!(matches!(app.mode, Mode::Normal(_)));
+    }
+
+    #[test]
+    fn close_all_closes_unmodified_buffers() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        app.workspace.add_buffer(Buffer::new());
+        app.workspace.add_buffer(Buffer::new());
+
+        super::close_all(&mut app).unwrap();
+
+        assert!(app.workspace.current_buffer.is_none());
+    }
+
+    #[test]
+    fn close_all_displays_confirmation_when_a_buffer_is_modified() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("amp");
+        app.workspace.add_buffer(buffer);
+        app.workspace.add_buffer(Buffer::new());
+
+        super::close_all(&mut app).unwrap();
+
+        assert!(matches!(app.mode, Mode::Confirm(_)));
+        assert!(app.workspace.current_buffer.is_some());
+
+        // Confirming should close everything.
+        crate::commands::confirm::confirm_command(&mut app).unwrap();
+        assert!(app.workspace.current_buffer.is_none());
+    }
+}
@@ -186,6 +186,11 @@ fn parse_mode_key_bindings(
 ///   ctrl-r becomes Key::Ctrl('r')
 ///
 fn parse_key(data: &str) -> Result<Key> {
+    // A lone hyphen is a key in its own right, rather than a modifier separator.
+    if data == "-" {
+        return Ok(Key::Char('-'));
+    }
+
     let mut key_components = data.split('-');
     let component = key_components
         .next()
@@ -318,6 +323,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn keymap_correctly_parses_yaml_hyphen_keybindings() {
+        // Build the keymap
+        let yaml_data = "normal:\n  \"-\": cursor::move_up";
+        let yaml = YamlLoader::load_from_str(yaml_data).unwrap();
+        let keymap = KeyMap::from(&yaml[0].as_hash().unwrap()).unwrap();
+
+        let command = keymap
+            .commands_for("normal", &Key::Char('-'))
+            .expect("Keymap doesn't contain command");
+        assert_eq!(
+            (command[0] as *const usize),
+            (commands::cursor::move_up as *const usize)
+        );
+    }
+
     #[test]
     fn keymap_correctly_parses_yaml_control_keybindings() {
         // Build the keymap
@@ -443,6 +464,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn keymap_cancels_exit_confirmation_with_ctrl_c() {
+        let keymap = KeyMap::default().unwrap();
+
+        let command = keymap
+            .commands_for("confirm_exit", &Key::Ctrl('c'))
+            .expect("Keymap doesn't contain command");
+        assert_eq!(
+            (command[0] as *const usize),
+            (commands::application::switch_to_normal_mode as *const usize)
+        );
+    }
+
     #[test]
     fn keymap_correctly_merges_keybindings() {
         let yaml_data = "normal:\n  k: cursor::move_up\n  j: cursor::move_down";
@@ -0,0 +1,78 @@
+use crate::input::Key;
+use std::mem;
+
+/// Records the keys behind the most recent change to a buffer, so that it can
+/// be repeated. A change is made up of a normal mode key (including any count
+/// prefix) whose commands modify the buffer, or one that switches to insert
+/// mode, along with every key pressed before returning to normal mode.
+#[derive(Default)]
+pub struct ChangeRecorder {
+    keys: Vec<Key>,
+    last_change: Vec<Key>,
+    inserting: bool,
+    pub replaying: bool,
+}
+
+impl ChangeRecorder {
+    pub fn new() -> ChangeRecorder {
+        ChangeRecorder::default()
+    }
+
+    /// Whether the keys being recorded belong to an insert mode session.
+    pub fn inserting(&self) -> bool {
+        self.inserting
+    }
+
+    /// Whether any keys have been recorded towards the next change.
+    pub fn pending(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    pub fn record(&mut self, key: Key) {
+        self.keys.push(key);
+    }
+
+    /// Marks the recorded keys as an unfinished change,
+    /// continuing until normal mode is restored.
+    pub fn start_insert_session(&mut self) {
+        self.inserting = true;
+    }
+
+    /// Keeps the recorded keys as the last change. Does nothing if they've
+    /// been discarded, so that the previous change is preserved.
+    pub fn finish(&mut self) {
+        if !self.keys.is_empty() {
+            self.last_change = mem::take(&mut self.keys);
+        }
+        self.inserting = false;
+    }
+
+    /// Drops the recorded keys; they don't amount to a repeatable change.
+    pub fn discard(&mut self) {
+        self.keys.clear();
+        self.inserting = false;
+    }
+
+    pub fn last_change(&self) -> &[Key] {
+        &self.last_change
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChangeRecorder;
+    use crate::input::Key;
+
+    #[test]
+    fn finish_preserves_the_last_change_when_keys_have_been_discarded() {
+        let mut recorder = ChangeRecorder::new();
+        recorder.record(Key::Char('d'));
+        recorder.finish();
+
+        recorder.record(Key::Char('u'));
+        recorder.discard();
+        recorder.finish();
+
+        assert_eq!(recorder.last_change(), &[Key::Char('d')]);
+    }
+}
@@ -1,35 +1,76 @@
 use crate::errors::*;
+use crate::view::Terminal;
 use cli_clipboard::{ClipboardContext, ClipboardProvider};
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::str::FromStr;
+use std::sync::Arc;
 
-/// In-app content can be captured in both regular and full-line selection
+// The number of copied/deleted entries kept in the clipboard history.
+const HISTORY_SIZE: usize = 50;
+
+/// In-app content can be captured in regular, full-line and block selection
 /// modes. This type describes the structure of said content, based on the
-/// context in which it was captured. When OS-level clipboard contents are
+/// context in which it was captured; block column content holds one row per
+/// line, separated by newlines. When OS-level clipboard contents are
 /// used, they are always represented as inline, as we cannot infer block
 /// style without the copy context.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ClipboardContent {
     Inline(String),
     Block(String),
+    BlockColumn(String),
     None,
 }
 
 impl ClipboardContent {
     pub fn text(&self) -> Option<&str> {
         match self {
-            ClipboardContent::Inline(ref content) | ClipboardContent::Block(ref content) => {
-                Some(content)
-            }
+            ClipboardContent::Inline(ref content)
+            | ClipboardContent::Block(ref content)
+            | ClipboardContent::BlockColumn(ref content) => Some(content),
             _ => None,
         }
     }
 }
 
+/// Where copied content is sent, beyond the in-app clipboard: the system
+/// clipboard, or the terminal (using OSC 52 escape sequences, which also
+/// work over SSH). The internal backend keeps content within the app.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipboardBackend {
+    Osc52,
+    System,
+    Internal,
+}
+
+impl FromStr for ClipboardBackend {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<ClipboardBackend> {
+        match value {
+            "osc52" => Ok(ClipboardBackend::Osc52),
+            "system" => Ok(ClipboardBackend::System),
+            "internal" => Ok(ClipboardBackend::Internal),
+            _ => bail!("Unknown clipboard \"{value}\"; use osc52, system or internal"),
+        }
+    }
+}
+
 /// Qualifies in-app copy/paste content with structural information, and
 /// synchronizes said content with the OS-level clipboard (preferring it
 /// in scenarios where it differs from the in-app equivalent).
+///
+/// Content is also kept in a history of recent entries, and can be stored in
+/// named registers instead, which are used when one has been selected for the
+/// next copy or paste. Registers don't affect the system clipboard.
 pub struct Clipboard {
     content: ClipboardContent,
+    history: VecDeque<ClipboardContent>,
+    registers: HashMap<char, ClipboardContent>,
+    register: Option<char>,
     system_clipboard: Option<ClipboardContext>,
+    terminal: Option<Arc<Box<dyn Terminal + Sync + Send + 'static>>>,
 }
 
 impl Default for Clipboard {
@@ -48,14 +89,54 @@ impl Clipboard {
 
         Clipboard {
             content: ClipboardContent::None,
+            history: VecDeque::new(),
+            registers: HashMap::new(),
+            register: None,
             system_clipboard,
+            terminal: None,
+        }
+    }
+
+    /// Switches to the specified backend. Without one, the system clipboard
+    /// is used, falling back to the terminal when there isn't one available
+    /// (e.g. without a display server, as is usually the case over SSH).
+    pub fn set_backend(
+        &mut self,
+        backend: Option<ClipboardBackend>,
+        terminal: Arc<Box<dyn Terminal + Sync + Send + 'static>>,
+    ) {
+        self.system_clipboard = None;
+        self.terminal = None;
+
+        match backend {
+            Some(ClipboardBackend::System) => {
+                self.system_clipboard = ClipboardProvider::new().ok();
+            }
+            Some(ClipboardBackend::Osc52) => self.terminal = Some(terminal),
+            Some(ClipboardBackend::Internal) => (),
+            None => {
+                self.system_clipboard = ClipboardProvider::new()
+                    .ok()
+                    .filter(|_| display_available());
+                if self.system_clipboard.is_none() {
+                    self.terminal = Some(terminal);
+                }
+            }
         }
     }
 
     /// Returns the in-app clipboard content. However, if in-app content
     /// differs from the system clipboard, the system clipboard content will
     /// be saved to the in-app clipboard as inline data and returned instead.
+    /// If a register has been selected, its content is returned instead.
     pub fn get_content(&mut self) -> &ClipboardContent {
+        if let Some(register) = self.register {
+            return self
+                .registers
+                .get(&register)
+                .unwrap_or(&ClipboardContent::None);
+        }
+
         let new_system_content = self
             .system_clipboard
             .as_mut()
@@ -65,17 +146,31 @@ impl Clipboard {
             .map(ClipboardContent::Inline); // external content is always inline
 
         if let Some(content) = new_system_content {
+            self.add_to_history(content.clone());
             self.content = content;
         }
 
         &self.content
     }
 
-    // Updates the in-app and system clipboards with the specified content.
+    // Updates the in-app and system clipboards with the specified content,
+    // or the selected register, adding it to the history either way.
     pub fn set_content(&mut self, content: ClipboardContent) -> Result<()> {
+        self.add_to_history(content.clone());
+
+        if let Some(register) = self.register {
+            self.registers.insert(register, content);
+
+            return Ok(());
+        }
+
         // Update the in-app clipboard.
         self.content = content;
 
+        if let (Some(terminal), Some(text)) = (self.terminal.as_ref(), self.content.text()) {
+            terminal.set_clipboard(text);
+        }
+
         if let (Some(clip), Some(text)) = (self.system_clipboard.as_mut(), self.content.text()) {
             if let Err(_) = clip.set_contents(text.to_owned()) {
                 self.system_clipboard = ClipboardProvider::new()
@@ -87,14 +182,127 @@ impl Clipboard {
         Ok(())
     }
 
+    /// Recently copied and deleted content, most recent first.
+    pub fn history(&self) -> &VecDeque<ClipboardContent> {
+        &self.history
+    }
+
+    /// Makes the specified history entry the clipboard content once again.
+    pub fn restore(&mut self, index: usize) -> Result<()> {
+        let content = self
+            .history
+            .get(index)
+            .cloned()
+            .context("Clipboard history entry not found")?;
+
+        self.set_content(content)
+    }
+
+    /// Uses the named register, rather than the clipboard,
+    /// for content until the selection is cleared.
+    pub fn select_register(&mut self, register: char) {
+        self.register = Some(register);
+    }
+
+    pub fn clear_register(&mut self) {
+        self.register = None;
+    }
+
+    pub fn register(&self) -> Option<char> {
+        self.register
+    }
+
+    // Adds the content to the front of the history,
+    // moving it there if it's already been added.
+    fn add_to_history(&mut self, content: ClipboardContent) {
+        if content.text().is_none_or(str::is_empty) {
+            return;
+        }
+
+        self.history.retain(|entry| *entry != content);
+        self.history.push_front(content);
+        self.history.truncate(HISTORY_SIZE);
+    }
+
     fn system_content_differs(internal_content: &ClipboardContent, system_content: &str) -> bool {
         internal_content.text() != Some(system_content)
     }
 }
 
+// The system clipboard relies on a display server outside of macOS.
+fn display_available() -> bool {
+    cfg!(target_os = "macos")
+        || env::var_os("DISPLAY").is_some()
+        || env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Clipboard, ClipboardContent};
+    use super::{Clipboard, ClipboardBackend, ClipboardContent, HISTORY_SIZE};
+    use crate::view::build_terminal;
+
+    #[test]
+    fn internal_backend_ignores_the_system_clipboard() {
+        let mut clipboard = Clipboard::new();
+        clipboard.set_backend(Some(ClipboardBackend::Internal), build_terminal().unwrap());
+        clipboard
+            .set_content(ClipboardContent::Block("amp\n".to_string()))
+            .unwrap();
+
+        assert!(clipboard.system_clipboard.is_none());
+        assert!(clipboard.terminal.is_none());
+        assert_eq!(
+            clipboard.get_content(),
+            &ClipboardContent::Block("amp\n".to_string())
+        );
+    }
+
+    #[test]
+    fn set_content_keeps_recent_entries_and_their_structure_in_the_history() {
+        let mut clipboard = Clipboard::new();
+        clipboard
+            .set_content(ClipboardContent::Block("amp\n".to_string()))
+            .unwrap();
+        clipboard
+            .set_content(ClipboardContent::Inline("editor".to_string()))
+            .unwrap();
+        clipboard
+            .set_content(ClipboardContent::Block("amp\n".to_string()))
+            .unwrap();
+
+        assert_eq!(
+            clipboard.history(),
+            &[
+                ClipboardContent::Block("amp\n".to_string()),
+                ClipboardContent::Inline("editor".to_string())
+            ]
+        );
+
+        for index in 0..HISTORY_SIZE {
+            clipboard
+                .set_content(ClipboardContent::Inline(index.to_string()))
+                .unwrap();
+        }
+        assert_eq!(clipboard.history().len(), HISTORY_SIZE);
+    }
+
+    #[test]
+    fn selected_registers_are_used_instead_of_the_clipboard() {
+        let mut clipboard = Clipboard::new();
+        clipboard.select_register('a');
+        clipboard
+            .set_content(ClipboardContent::Block("amp\n".to_string()))
+            .unwrap();
+        assert_eq!(
+            clipboard.get_content(),
+            &ClipboardContent::Block("amp\n".to_string())
+        );
+
+        assert_eq!(clipboard.history().len(), 1);
+
+        clipboard.select_register('b');
+        assert_eq!(clipboard.get_content(), &ClipboardContent::None);
+    }
 
     #[test]
     fn system_content_differs_ignores_inline_vs_block_when_text_matches() {
@@ -0,0 +1,153 @@
+use scribe::buffer::{Buffer, Position, Range};
+
+/// A cursor tracked in addition to a buffer's own (primary) cursor. Its
+/// anchor describes the other end of its selection while in select modes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SecondaryCursor {
+    pub position: Position,
+    pub anchor: Position,
+}
+
+impl SecondaryCursor {
+    pub fn new(position: Position) -> SecondaryCursor {
+        SecondaryCursor {
+            position,
+            anchor: position,
+        }
+    }
+}
+
+/// Secondary cursors used for multi-cursor editing. They're tied to the
+/// buffer in which they were added, so switching buffers hides them, and
+/// adding a cursor to another buffer discards them.
+#[derive(Default)]
+pub struct Cursors {
+    buffer_id: Option<usize>,
+    cursors: Vec<SecondaryCursor>,
+}
+
+impl Cursors {
+    pub fn new() -> Cursors {
+        Cursors::default()
+    }
+
+    /// Returns the secondary cursors belonging to the specified buffer.
+    pub fn for_buffer(&self, buffer: &Buffer) -> &[SecondaryCursor] {
+        if self.buffer_id.is_some() && self.buffer_id == buffer.id {
+            &self.cursors
+        } else {
+            &[]
+        }
+    }
+
+    /// Adds a secondary cursor to the specified buffer. Cursors positioned
+    /// on top of an existing one (including the primary cursor) are ignored.
+    pub fn add(&mut self, buffer: &Buffer, cursor: SecondaryCursor) {
+        if self.buffer_id != buffer.id {
+            self.cursors.clear();
+            self.buffer_id = buffer.id;
+        }
+
+        let occupied = cursor.position == *buffer.cursor
+            || self.cursors.iter().any(|c| c.position == cursor.position);
+
+        if !occupied {
+            self.cursors.push(cursor);
+        }
+    }
+
+    /// Replaces the specified buffer's secondary cursors.
+    pub fn replace(&mut self, buffer: &Buffer, cursors: Vec<SecondaryCursor>) {
+        self.clear();
+
+        for cursor in cursors {
+            self.add(buffer, cursor);
+        }
+    }
+
+    /// Removes and returns the specified buffer's secondary cursors.
+    pub fn take(&mut self, buffer: &Buffer) -> Vec<SecondaryCursor> {
+        let cursors = self.for_buffer(buffer).to_vec();
+        self.clear();
+
+        cursors
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer_id = None;
+        self.cursors.clear();
+    }
+
+    /// Collapses each cursor's selection to its current position.
+    pub fn reset_anchors(&mut self) {
+        for cursor in self.cursors.iter_mut() {
+            cursor.anchor = cursor.position;
+        }
+    }
+
+    /// Ranges covering the character under each of the specified
+    /// buffer's secondary cursors, suitable for highlighting.
+    pub fn highlights(&self, buffer: &Buffer) -> Vec<Range> {
+        self.for_buffer(buffer)
+            .iter()
+            .map(|cursor| {
+                Range::new(
+                    cursor.position,
+                    Position {
+                        line: cursor.position.line,
+                        offset: cursor.position.offset + 1,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cursors, SecondaryCursor};
+    use scribe::buffer::Position;
+    use scribe::Buffer;
+
+    #[test]
+    fn add_ignores_cursors_positioned_on_existing_cursors() {
+        let mut buffer = Buffer::new();
+        buffer.id = Some(0);
+        buffer.insert("amp\neditor");
+        let mut cursors = Cursors::new();
+
+        // Add a cursor on top of the primary cursor and two identical ones.
+        cursors.add(&buffer, SecondaryCursor::new(Position::new()));
+        cursors.add(
+            &buffer,
+            SecondaryCursor::new(Position { line: 1, offset: 0 }),
+        );
+        cursors.add(
+            &buffer,
+            SecondaryCursor::new(Position { line: 1, offset: 0 }),
+        );
+
+        assert_eq!(
+            cursors.for_buffer(&buffer),
+            &[SecondaryCursor::new(Position { line: 1, offset: 0 })]
+        );
+    }
+
+    #[test]
+    fn for_buffer_ignores_cursors_added_to_other_buffers() {
+        let mut buffer = Buffer::new();
+        buffer.id = Some(0);
+        buffer.insert("amp\neditor");
+        let mut other_buffer = Buffer::new();
+        other_buffer.id = Some(1);
+        let mut cursors = Cursors::new();
+
+        cursors.add(
+            &buffer,
+            SecondaryCursor::new(Position { line: 1, offset: 0 }),
+        );
+
+        assert!(cursors.for_buffer(&other_buffer).is_empty());
+        assert_eq!(cursors.for_buffer(&buffer).len(), 1);
+    }
+}
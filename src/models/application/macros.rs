@@ -0,0 +1,229 @@
+use crate::errors::*;
+use crate::input::Key;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use yaml_rust::yaml::{Hash, Yaml};
+use yaml_rust::{YamlEmitter, YamlLoader};
+
+/// Keyboard macros, recorded into and played back from named registers.
+/// Macros loaded from a file are written back to it whenever one is recorded.
+#[derive(Default)]
+pub struct Macros {
+    path: Option<PathBuf>,
+    registers: HashMap<char, Vec<Key>>,
+    recording: Option<(char, Vec<Key>)>,
+    pub playing: Vec<char>,
+}
+
+impl Macros {
+    /// Builds an empty, in-memory set of macros.
+    pub fn new() -> Macros {
+        Macros::default()
+    }
+
+    /// Loads macros from the specified YAML file, if it exists. Either way,
+    /// recorded macros will be saved to it.
+    pub fn load(path: PathBuf) -> Result<Macros> {
+        let mut macros = Macros::new();
+
+        if path.exists() {
+            let data = fs::read_to_string(&path).context("Couldn't read macros file")?;
+            let document = YamlLoader::load_from_str(&data)
+                .context("Couldn't parse macros file")?
+                .into_iter()
+                .next();
+
+            if let Some(Yaml::Hash(registers)) = document {
+                for (register, keys) in registers {
+                    let register = register
+                        .as_str()
+                        .and_then(|name| name.chars().next())
+                        .context("Couldn't parse macro register name")?;
+                    let keys = keys
+                        .as_vec()
+                        .context("Couldn't parse macro keys")?
+                        .iter()
+                        .map(|key| key.as_str().and_then(parse_key))
+                        .collect::<Option<Vec<Key>>>()
+                        .with_context(|| format!("Couldn't parse keys for macro \"{register}\""))?;
+
+                    macros.registers.insert(register, keys);
+                }
+            }
+        }
+        macros.path = Some(path);
+
+        Ok(macros)
+    }
+
+    /// Writes the macros to the file they were loaded from, if any.
+    pub fn save(&self) -> Result<()> {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return Ok(()),
+        };
+
+        // Sort registers so that the file is stable across saves.
+        let mut registers = Hash::new();
+        for (register, keys) in self.registers.iter().collect::<BTreeMap<_, _>>() {
+            registers.insert(
+                Yaml::String(register.to_string()),
+                Yaml::Array(
+                    keys.iter()
+                        .map(|key| Yaml::String(format_key(key)))
+                        .collect(),
+                ),
+            );
+        }
+
+        let mut data = String::new();
+        YamlEmitter::new(&mut data)
+            .dump(&Yaml::Hash(registers))
+            .context("Couldn't serialize macros")?;
+        fs::write(path, data).context("Couldn't write macros file")
+    }
+
+    /// The file the macros are saved to, if any.
+    #[cfg(test)]
+    pub fn path(&self) -> Option<&PathBuf> {
+        self.path.as_ref()
+    }
+
+    /// The register being recorded into, if any.
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|(register, _)| *register)
+    }
+
+    pub fn start_recording(&mut self, register: char) {
+        self.recording = Some((register, Vec::new()));
+    }
+
+    /// Adds a key to the macro being recorded, if any.
+    pub fn record(&mut self, key: Key) {
+        if let Some((_, ref mut keys)) = self.recording {
+            keys.push(key);
+        }
+    }
+
+    /// Stores the recorded keys in their register,
+    /// returning its name, if we were recording.
+    pub fn stop_recording(&mut self) -> Option<char> {
+        let (register, keys) = self.recording.take()?;
+        self.registers.insert(register, keys);
+
+        Some(register)
+    }
+
+    /// Drops the most recently recorded key, if any.
+    pub fn discard_last_key(&mut self) {
+        if let Some((_, ref mut keys)) = self.recording {
+            keys.pop();
+        }
+    }
+
+    pub fn get(&self, register: char) -> Option<&[Key]> {
+        self.registers.get(&register).map(|keys| keys.as_slice())
+    }
+}
+
+// Serializes keys using the names from the keymap. Characters are written
+// as-is; being a single character, they can't be confused with names.
+fn format_key(key: &Key) -> String {
+    match *key {
+        Key::Backspace => "backspace".to_string(),
+        Key::Left => "left".to_string(),
+        Key::Right => "right".to_string(),
+        Key::Up => "up".to_string(),
+        Key::Down => "down".to_string(),
+        Key::Home => "home".to_string(),
+        Key::End => "end".to_string(),
+        Key::PageUp => "page_up".to_string(),
+        Key::PageDown => "page_down".to_string(),
+        Key::Delete => "delete".to_string(),
+        Key::Insert => "insert".to_string(),
+        Key::Esc => "escape".to_string(),
+        Key::Tab => "tab".to_string(),
+        Key::Enter => "enter".to_string(),
+        Key::AnyChar => "_".to_string(),
+        Key::Char(c) => c.to_string(),
+        Key::Ctrl(c) => format!("ctrl-{c}"),
+    }
+}
+
+fn parse_key(data: &str) -> Option<Key> {
+    let mut characters = data.chars();
+    if let (Some(c), None) = (characters.next(), characters.next()) {
+        return Some(Key::Char(c));
+    }
+
+    Some(match data {
+        "backspace" => Key::Backspace,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "page_up" => Key::PageUp,
+        "page_down" => Key::PageDown,
+        "delete" => Key::Delete,
+        "insert" => Key::Insert,
+        "escape" => Key::Esc,
+        "tab" => Key::Tab,
+        "enter" => Key::Enter,
+        _ => {
+            let mut characters = data.strip_prefix("ctrl-")?.chars();
+            match (characters.next(), characters.next()) {
+                (Some(c), None) => Key::Ctrl(c),
+                _ => return None,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Macros;
+    use crate::input::Key;
+    use std::env;
+
+    #[test]
+    fn stop_recording_stores_recorded_keys_in_register() {
+        let mut macros = Macros::new();
+        macros.start_recording('a');
+        macros.record(Key::Char('j'));
+        macros.record(Key::Char('q'));
+        macros.discard_last_key();
+
+        assert_eq!(macros.stop_recording(), Some('a'));
+        assert_eq!(macros.get('a'), Some(&[Key::Char('j')][..]));
+        assert_eq!(macros.recording(), None);
+    }
+
+    #[test]
+    fn save_and_load_round_trip_macros() {
+        let path = env::temp_dir().join("amp_macros_round_trip.yml");
+        let _ = std::fs::remove_file(&path);
+        let keys = [
+            Key::Char('-'),
+            Key::Char(' '),
+            Key::Char('"'),
+            Key::Ctrl('a'),
+            Key::Esc,
+            Key::Enter,
+        ];
+
+        let mut macros = Macros::load(path.clone()).unwrap();
+        macros.start_recording('a');
+        for key in keys.iter() {
+            macros.record(key.clone());
+        }
+        macros.stop_recording();
+        macros.save().unwrap();
+
+        let macros = Macros::load(path.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(macros.get('a'), Some(&keys[..]));
+    }
+}
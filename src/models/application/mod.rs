@@ -1,14 +1,26 @@
+mod change_recorder;
 mod clipboard;
+mod cursors;
 mod event;
+mod macros;
 pub mod modes;
 mod preferences;
+mod snippets;
 mod syntax_loader;
+mod undo_tree;
+mod word_counts;
 
 // Published API
-pub use self::clipboard::ClipboardContent;
+pub use self::change_recorder::ChangeRecorder;
+pub use self::clipboard::{ClipboardBackend, ClipboardContent};
+pub use self::cursors::{Cursors, SecondaryCursor};
 pub use self::event::Event;
+pub use self::macros::Macros;
 pub use self::modes::{Mode, ModeKey};
 pub use self::preferences::Preferences;
+pub use self::snippets::Snippets;
+pub use self::undo_tree::{UndoState, UndoTree};
+pub use self::word_counts::WordCounts;
 
 use self::clipboard::Clipboard;
 use self::modes::*;
@@ -35,6 +47,13 @@ pub struct Application {
     pub workspace: Workspace,
     pub view: View,
     pub clipboard: Clipboard,
+    pub cursors: Cursors,
+    pub command_group_held: bool,
+    pub change_recorder: ChangeRecorder,
+    pub undo_tree: UndoTree,
+    pub macros: Macros,
+    pub snippets: Snippets,
+    pub word_counts: WordCounts,
     pub repository: Option<Repository>,
     pub error: Option<Error>,
     pub preferences: Rc<RefCell<Preferences>>,
@@ -59,11 +78,18 @@ impl Application {
         let mut app = Application {
             current_mode: ModeKey::Normal,
             previous_mode: ModeKey::Normal,
-            mode: Mode::Normal,
+            mode: Mode::Normal(NormalMode::new()),
             modes: HashMap::new(),
             workspace,
             view,
             clipboard,
+            cursors: Cursors::new(),
+            command_group_held: false,
+            change_recorder: ChangeRecorder::new(),
+            undo_tree: UndoTree::new(),
+            macros: Macros::new(),
+            snippets: Snippets::new(),
+            word_counts: WordCounts::new(),
             repository: Repository::discover(env::current_dir()?).ok(),
             error: None,
             preferences,
@@ -72,6 +98,9 @@ impl Application {
         };
 
         app.create_modes()?;
+        app.configure_clipboard();
+        app.load_macros(user_macros_path());
+        app.load_snippets(user_snippets_path());
 
         Ok(app)
     }
@@ -106,15 +135,61 @@ impl Application {
                 &mut self.view,
                 &self.error,
             ),
+            Mode::ConfirmExit(ref mode) => presenters::modes::confirm_exit::display(
+                &mut self.workspace,
+                mode,
+                &mut self.view,
+                &self.error,
+            ),
+            Mode::ClipboardHistory(ref mut mode) => presenters::modes::search_select::display(
+                &mut self.workspace,
+                mode,
+                &mut self.view,
+                &self.error,
+            ),
             Mode::Command(ref mut mode) => presenters::modes::search_select::display(
                 &mut self.workspace,
                 mode,
                 &mut self.view,
                 &self.error,
             ),
-            Mode::Insert => {
-                presenters::modes::insert::display(&mut self.workspace, &mut self.view, &self.error)
-            }
+            Mode::MacroRegister(ref mode) => presenters::modes::macro_register::display(
+                &mut self.workspace,
+                mode,
+                &mut self.view,
+                &self.error,
+            ),
+            Mode::Register(ref mode) => presenters::modes::register::display(
+                &mut self.workspace,
+                mode,
+                &mut self.view,
+                &self.error,
+            ),
+            Mode::Surround(ref mode) => presenters::modes::surround::display(
+                &mut self.workspace,
+                mode,
+                &mut self.view,
+                &self.error,
+            ),
+            Mode::TextObject(ref mode) => presenters::modes::text_object::display(
+                &mut self.workspace,
+                mode,
+                &mut self.view,
+                &self.error,
+            ),
+            Mode::Completion(ref mode) => presenters::modes::completion::display(
+                &mut self.workspace,
+                mode,
+                &self.cursors,
+                &mut self.view,
+                &self.error,
+            ),
+            Mode::Insert => presenters::modes::insert::display(
+                &mut self.workspace,
+                &self.cursors,
+                &mut self.view,
+                &self.error,
+            ),
             Mode::Open(ref mut mode) => presenters::modes::open::display(
                 &mut self.workspace,
                 mode,
@@ -139,9 +214,24 @@ impl Application {
                 &mut self.view,
                 &self.error,
             ),
-            Mode::Paste => {
-                presenters::modes::paste::display(&mut self.workspace, &mut self.view, &self.error)
-            }
+            Mode::Paste => presenters::modes::paste::display(
+                &mut self.workspace,
+                &self.cursors,
+                &mut self.view,
+                &self.error,
+            ),
+            Mode::ShellCommand(ref mode) => presenters::modes::shell_command::display(
+                &mut self.workspace,
+                mode,
+                &mut self.view,
+                &self.error,
+            ),
+            Mode::Align(ref mode) => presenters::modes::align::display(
+                &mut self.workspace,
+                mode,
+                &mut self.view,
+                &self.error,
+            ),
             Mode::Path(ref mode) => presenters::modes::path::display(
                 &mut self.workspace,
                 mode,
@@ -160,20 +250,38 @@ impl Application {
                 &mut self.view,
                 &self.error,
             ),
+            Mode::UndoTree(ref mut mode) => presenters::modes::search_select::display(
+                &mut self.workspace,
+                mode,
+                &mut self.view,
+                &self.error,
+            ),
             Mode::Select(ref mode) => presenters::modes::select::display(
+                &mut self.workspace,
+                &self.cursors,
+                mode,
+                &mut self.view,
+                &self.error,
+            ),
+            Mode::SelectBlock(ref mode) => presenters::modes::select_block::display(
                 &mut self.workspace,
                 mode,
+                &self.preferences.borrow(),
                 &mut self.view,
                 &self.error,
             ),
             Mode::SelectLine(ref mode) => presenters::modes::select_line::display(
                 &mut self.workspace,
+                &self.cursors,
                 mode,
                 &mut self.view,
                 &self.error,
             ),
-            Mode::Normal => presenters::modes::normal::display(
+            Mode::Normal(ref mode) => presenters::modes::normal::display(
                 &mut self.workspace,
+                &self.cursors,
+                mode,
+                self.macros.recording(),
                 &mut self.view,
                 &self.repository,
                 &self.error,
@@ -224,6 +332,7 @@ impl Application {
     fn handle_event(&mut self, event: Event) {
         match event {
             Event::Key(key) => {
+                self.macros.record(key.clone());
                 self.view.last_key = Some(key);
                 self.error = commands::application::handle_input(self).err();
             }
@@ -277,15 +386,38 @@ impl Application {
                     Some("search_select")
                 }
             }
-            Mode::Normal => Some("normal"),
+            Mode::ClipboardHistory(ref mode) => {
+                if mode.insert_mode() {
+                    Some("search_select_insert")
+                } else {
+                    Some("search_select")
+                }
+            }
+            Mode::UndoTree(ref mode) => {
+                if mode.insert_mode() {
+                    Some("search_select_insert")
+                } else {
+                    Some("search_select")
+                }
+            }
+            Mode::Normal(_) => Some("normal"),
             Mode::Paste => Some("paste"),
+            Mode::ShellCommand(_) => Some("shell_command"),
+            Mode::Align(_) => Some("align"),
             Mode::Path(_) => Some("path"),
             Mode::Confirm(_) => Some("confirm"),
+            Mode::ConfirmExit(_) => Some("confirm_exit"),
+            Mode::Completion(_) => Some("completion"),
             Mode::Insert => Some("insert"),
             Mode::Jump(_) => Some("jump"),
             Mode::LineJump(_) => Some("line_jump"),
+            Mode::MacroRegister(_) => Some("macro_register"),
+            Mode::Register(_) => Some("register"),
             Mode::Select(_) => Some("select"),
+            Mode::SelectBlock(_) => Some("select_block"),
             Mode::SelectLine(_) => Some("select_line"),
+            Mode::Surround(_) => Some("surround"),
+            Mode::TextObject(_) => Some("text_object"),
             Mode::Search(ref mode) => {
                 if mode.insert_mode() {
                     Some("search_insert")
@@ -326,11 +458,16 @@ impl Application {
         self.switch_to(self.previous_mode);
     }
 
+    pub fn previous_mode(&self) -> ModeKey {
+        self.previous_mode
+    }
+
     fn create_modes(&mut self) -> Result<()> {
         // Do the easy ones first.
         self.modes.insert(ModeKey::Exit, Mode::Exit);
         self.modes.insert(ModeKey::Insert, Mode::Insert);
-        self.modes.insert(ModeKey::Normal, Mode::Normal);
+        self.modes
+            .insert(ModeKey::Normal, Mode::Normal(NormalMode::new()));
         self.modes.insert(ModeKey::Paste, Mode::Paste);
 
         self.modes.insert(
@@ -339,16 +476,38 @@ impl Application {
                 self.preferences.borrow().search_select_config(),
             )),
         );
+        self.modes
+            .insert(ModeKey::Completion, Mode::Completion(CompletionMode::new()));
         self.modes.insert(
             ModeKey::Confirm,
             Mode::Confirm(ConfirmMode::new(
                 commands::application::switch_to_normal_mode,
             )),
         );
+        self.modes.insert(
+            ModeKey::ConfirmExit,
+            Mode::ConfirmExit(ConfirmExitMode::new()),
+        );
         self.modes
             .insert(ModeKey::Jump, Mode::Jump(JumpMode::new(0)));
         self.modes
             .insert(ModeKey::LineJump, Mode::LineJump(LineJumpMode::new()));
+        self.modes.insert(
+            ModeKey::MacroRegister,
+            Mode::MacroRegister(MacroRegisterMode::new()),
+        );
+        self.modes
+            .insert(ModeKey::Register, Mode::Register(RegisterMode::new()));
+        self.modes.insert(
+            ModeKey::ShellCommand,
+            Mode::ShellCommand(ShellCommandMode::new()),
+        );
+        self.modes
+            .insert(ModeKey::Align, Mode::Align(AlignMode::new()));
+        self.modes
+            .insert(ModeKey::Surround, Mode::Surround(SurroundMode::new()));
+        self.modes
+            .insert(ModeKey::TextObject, Mode::TextObject(TextObjectMode::new()));
         self.modes
             .insert(ModeKey::LineJump, Mode::LineJump(LineJumpMode::new()));
         self.modes.insert(
@@ -366,6 +525,10 @@ impl Application {
             ModeKey::Select,
             Mode::Select(SelectMode::new(Position::default())),
         );
+        self.modes.insert(
+            ModeKey::SelectBlock,
+            Mode::SelectBlock(SelectBlockMode::new(Position::default())),
+        );
         self.modes.insert(
             ModeKey::SelectLine,
             Mode::SelectLine(SelectLineMode::new(0)),
@@ -376,6 +539,12 @@ impl Application {
                 self.preferences.borrow().search_select_config(),
             )?),
         );
+        self.modes.insert(
+            ModeKey::ClipboardHistory,
+            Mode::ClipboardHistory(ClipboardHistoryMode::new(
+                self.preferences.borrow().search_select_config(),
+            )),
+        );
         self.modes.insert(
             ModeKey::Syntax,
             Mode::Syntax(SyntaxMode::new(
@@ -388,9 +557,41 @@ impl Application {
                 self.preferences.borrow().search_select_config(),
             )),
         );
+        self.modes.insert(
+            ModeKey::UndoTree,
+            Mode::UndoTree(UndoTreeMode::new(
+                self.preferences.borrow().search_select_config(),
+            )),
+        );
 
         Ok(())
     }
+
+    // Malformed macros are reported and left unloaded, rather than preventing
+    // startup, so that recording a macro doesn't overwrite them.
+    fn configure_clipboard(&mut self) {
+        // Fall back to the default backend, reporting an invalid preference.
+        let backend = self.preferences.borrow().clipboard_backend();
+        let backend = backend.unwrap_or_else(|e| {
+            self.error = Some(e);
+            None
+        });
+        self.clipboard.set_backend(backend, self.view.terminal());
+    }
+
+    fn load_macros(&mut self, path: Result<PathBuf>) {
+        match path.and_then(Macros::load) {
+            Ok(macros) => self.macros = macros,
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    fn load_snippets(&mut self, path: Result<PathBuf>) {
+        match path.and_then(Snippets::load) {
+            Ok(snippets) => self.snippets = snippets,
+            Err(e) => self.error = Some(e),
+        }
+    }
 }
 
 fn initialize_preferences() -> Rc<RefCell<Preferences>> {
@@ -485,6 +686,44 @@ fn user_syntax_path() -> Result<PathBuf> {
     Ok(PathBuf::from("tests/fixtures/user_syntaxes"))
 }
 
+#[cfg(not(test))]
+fn user_macros_path() -> Result<PathBuf> {
+    Preferences::macros_path()
+}
+
+// Give each application its own macros file, so that tests
+// don't read or overwrite the user's macros, or each other's.
+#[cfg(test)]
+fn user_macros_path() -> Result<PathBuf> {
+    use std::process;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static APPLICATIONS: AtomicUsize = AtomicUsize::new(0);
+    let application = APPLICATIONS.fetch_add(1, Ordering::Relaxed);
+
+    Ok(env::temp_dir().join(format!("amp_macros_{}_{application}.yml", process::id())))
+}
+
+#[cfg(not(test))]
+fn user_snippets_path() -> Result<PathBuf> {
+    Preferences::snippets_path()
+}
+
+#[cfg(test)]
+fn user_snippets_path() -> Result<PathBuf> {
+    Ok(PathBuf::from("tests/fixtures/user_snippets.yml"))
+}
+
+// Remove each application's macros file, lest tests leave them behind.
+#[cfg(test)]
+impl Drop for Application {
+    fn drop(&mut self) {
+        if let Some(path) = self.macros.path() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::preferences::Preferences;
@@ -495,11 +734,48 @@ mod tests {
     use scribe::Buffer;
     use std::cell::RefCell;
     use std::env;
+    use std::fs;
     use std::path::Path;
     use std::rc::Rc;
     use std::sync::mpsc;
     use yaml_rust::YamlLoader;
 
+    #[test]
+    fn load_macros_reports_malformed_macros() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let path = env::temp_dir().join("amp_malformed_macros.yml");
+        fs::write(&path, "a: not a list of keys").unwrap();
+
+        app.load_macros(Ok(path));
+        assert_eq!(
+            app.error.as_ref().map(|e| e.to_string()),
+            Some(String::from("Couldn't parse macro keys"))
+        );
+    }
+
+    #[test]
+    fn load_snippets_reports_malformed_snippets() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let path = env::temp_dir().join("amp_malformed_snippets.yml");
+        fs::write(&path, "rs: not a map of snippets").unwrap();
+
+        app.load_snippets(Ok(path));
+        assert_eq!(
+            app.error.as_ref().map(|e| e.to_string()),
+            Some(String::from("Couldn't parse snippets for \"rs\""))
+        );
+    }
+
+    #[test]
+    fn configure_clipboard_reports_invalid_backends() {
+        let mut app = Application::new(&Vec::new()).unwrap();
+        let data = YamlLoader::load_from_str("clipboard: x11").unwrap();
+        app.preferences = Rc::new(RefCell::new(Preferences::new(data.into_iter().next())));
+
+        app.configure_clipboard();
+        assert!(app.error.is_some());
+    }
+
     #[test]
     fn application_uses_file_arguments_to_load_contents_into_buffers_when_files_exist() {
         let application =
@@ -670,7 +946,7 @@ mod tests {
         let mut app = Application::new(&Vec::new()).unwrap();
 
         assert_eq!(app.current_mode, ModeKey::Normal);
-        assert!(matches!(app.mode, Mode::Normal));
+        assert!(matches!(app.mode, Mode::Normal(_)));
 
         app.switch_to(ModeKey::Exit);
 
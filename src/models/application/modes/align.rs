@@ -0,0 +1,35 @@
+use scribe::buffer::Range;
+use std::fmt;
+
+/// Prompts for a delimiter on which to align the lines
+/// selected before entering the mode.
+#[derive(Default)]
+pub struct AlignMode {
+    pub input: String,
+    pub range: Option<Range>,
+}
+
+impl AlignMode {
+    pub fn new() -> AlignMode {
+        AlignMode::default()
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.input.push(c);
+    }
+
+    pub fn pop_char(&mut self) {
+        self.input.pop();
+    }
+
+    pub fn reset(&mut self, range: Range) {
+        self.input.clear();
+        self.range = Some(range);
+    }
+}
+
+impl fmt::Display for AlignMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ALIGN")
+    }
+}
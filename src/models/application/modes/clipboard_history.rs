@@ -0,0 +1,177 @@
+use crate::models::application::modes::{SearchSelectConfig, SearchSelectMode};
+use crate::models::application::ClipboardContent;
+use crate::util::SelectableVec;
+use fragment;
+use fragment::matching::AsStr;
+use std::fmt;
+use std::slice::Iter;
+use unicode_segmentation::UnicodeSegmentation;
+
+const EXCERPT_LENGTH: usize = 60;
+
+pub struct ClipboardHistoryMode {
+    insert: bool,
+    input: String,
+    entries: Vec<ClipboardEntry>,
+    results: SelectableVec<ClipboardEntry>,
+    config: SearchSelectConfig,
+}
+
+/// A clipboard history entry, described by the kind of selection it was
+/// copied from and its first line; its text is used to search for it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClipboardEntry {
+    pub index: usize,
+    pub description: String,
+    text: String,
+}
+
+impl ClipboardEntry {
+    pub fn new(index: usize, content: &ClipboardContent) -> ClipboardEntry {
+        let kind = match content {
+            ClipboardContent::Block(_) => "lines",
+            ClipboardContent::BlockColumn(_) => "block",
+            _ => "text",
+        };
+        let text = content.text().unwrap_or_default().to_string();
+        let line = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default();
+        let mut excerpt: String = line.graphemes(true).take(EXCERPT_LENGTH).collect();
+        if excerpt.len() < line.len() {
+            excerpt.push_str("...");
+        }
+        let lines = text.lines().count();
+        if lines > 1 {
+            excerpt.push_str(&format!(" (+{} lines)", lines - 1));
+        }
+
+        ClipboardEntry {
+            index,
+            description: format!("{kind:<5}  {excerpt}"),
+            text,
+        }
+    }
+}
+
+impl fmt::Display for ClipboardEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", &self.description)
+    }
+}
+
+impl AsStr for ClipboardEntry {
+    fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+impl ClipboardHistoryMode {
+    pub fn new(config: SearchSelectConfig) -> ClipboardHistoryMode {
+        ClipboardHistoryMode {
+            insert: true,
+            input: String::new(),
+            entries: Vec::new(),
+            results: SelectableVec::new(Vec::new()),
+            config,
+        }
+    }
+
+    /// Lists the history's entries, in the order they're provided.
+    pub fn reset<'a, T>(&mut self, history: T, config: SearchSelectConfig)
+    where
+        T: IntoIterator<Item = &'a ClipboardContent>,
+    {
+        self.input.clear();
+        self.insert = true;
+        self.entries = history
+            .into_iter()
+            .enumerate()
+            .map(|(index, content)| ClipboardEntry::new(index, content))
+            .collect();
+        self.results = SelectableVec::new(Vec::new());
+        self.config = config;
+    }
+}
+
+impl fmt::Display for ClipboardHistoryMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CLIPBOARD")
+    }
+}
+
+impl SearchSelectMode for ClipboardHistoryMode {
+    type Item = ClipboardEntry;
+
+    fn search(&mut self) {
+        // Find the entries we're looking for using the query.
+        let results = if self.input.is_empty() {
+            self.entries
+                .iter()
+                .take(self.config.max_results)
+                .cloned()
+                .collect()
+        } else {
+            fragment::matching::find(&self.input, &self.entries, self.config.max_results)
+                .into_iter()
+                .map(|i| i.clone())
+                .collect()
+        };
+
+        self.results = SelectableVec::new(results);
+    }
+
+    fn query(&mut self) -> &mut String {
+        &mut self.input
+    }
+
+    fn insert_mode(&self) -> bool {
+        self.insert
+    }
+
+    fn set_insert_mode(&mut self, insert_mode: bool) {
+        self.insert = insert_mode;
+    }
+
+    fn results(&self) -> Iter<'_, ClipboardEntry> {
+        self.results.iter()
+    }
+
+    fn selection(&self) -> Option<&ClipboardEntry> {
+        self.results.selection()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.results.selected_index()
+    }
+
+    fn select_previous(&mut self) {
+        self.results.select_previous();
+    }
+
+    fn select_next(&mut self) {
+        self.results.select_next();
+    }
+
+    fn config(&self) -> &SearchSelectConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClipboardEntry;
+    use crate::models::application::ClipboardContent;
+
+    #[test]
+    fn entries_describe_the_kind_and_first_line_of_content() {
+        let entry =
+            ClipboardEntry::new(0, &ClipboardContent::Block("\n  amp\neditor\n".to_string()));
+        assert_eq!(entry.description, "lines  amp (+2 lines)");
+
+        let entry = ClipboardEntry::new(1, &ClipboardContent::Inline("amp".to_string()));
+        assert_eq!(entry.description, "text   amp");
+    }
+}
@@ -0,0 +1,61 @@
+use crate::util::SelectableVec;
+use std::collections::HashMap;
+use std::fmt;
+use std::slice::Iter;
+
+/// Lists words completing the partial one before the cursor, entered from
+/// (and returning to) insert mode. The other buffers' words are collected
+/// when listing starts, and kept to narrow the list as the word is typed.
+#[derive(Default)]
+pub struct CompletionMode {
+    pub prefix: String,
+    pub other_words: HashMap<String, usize>,
+    results: SelectableVec<String>,
+}
+
+impl CompletionMode {
+    pub fn new() -> CompletionMode {
+        CompletionMode::default()
+    }
+
+    pub fn reset(
+        &mut self,
+        prefix: String,
+        candidates: Vec<String>,
+        other_words: HashMap<String, usize>,
+    ) {
+        self.prefix = prefix;
+        self.other_words = other_words;
+        self.results = SelectableVec::new(candidates);
+    }
+
+    pub fn results(&self) -> Iter<'_, String> {
+        self.results.iter()
+    }
+
+    pub fn selection(&self) -> Option<&String> {
+        self.results.selection()
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.results.selected_index()
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.results.is_empty() {
+            self.results.select_previous();
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.results.is_empty() {
+            self.results.select_next();
+        }
+    }
+}
+
+impl fmt::Display for CompletionMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "COMPLETE")
+    }
+}
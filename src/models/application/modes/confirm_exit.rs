@@ -0,0 +1,22 @@
+use std::fmt;
+
+#[derive(Default)]
+pub struct ConfirmExitMode {
+    pub modified_buffers: Vec<String>,
+}
+
+impl ConfirmExitMode {
+    pub fn new() -> ConfirmExitMode {
+        ConfirmExitMode::default()
+    }
+
+    pub fn reset(&mut self, modified_buffers: Vec<String>) {
+        self.modified_buffers = modified_buffers;
+    }
+}
+
+impl fmt::Display for ConfirmExitMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "EXIT")
+    }
+}
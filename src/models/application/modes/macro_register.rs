@@ -0,0 +1,30 @@
+use std::fmt;
+
+/// What to do with the register chosen in macro register mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MacroAction {
+    #[default]
+    Record,
+    Play,
+}
+
+#[derive(Default)]
+pub struct MacroRegisterMode {
+    pub action: MacroAction,
+}
+
+impl MacroRegisterMode {
+    pub fn new() -> MacroRegisterMode {
+        MacroRegisterMode::default()
+    }
+
+    pub fn reset(&mut self, action: MacroAction) {
+        self.action = action;
+    }
+}
+
+impl fmt::Display for MacroRegisterMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MACRO")
+    }
+}
@@ -1,66 +1,112 @@
+mod align;
+mod clipboard_history;
 mod command;
+mod completion;
 mod confirm;
+mod confirm_exit;
 pub mod jump;
 mod line_jump;
+mod macro_register;
+mod normal;
 pub mod open;
 mod path;
+mod register;
 mod search;
 mod search_select;
 mod select;
+mod select_block;
 mod select_line;
+mod shell_command;
+mod surround;
 mod symbol_jump;
 mod syntax;
+mod text_object;
 mod theme;
+mod undo_tree;
 
 pub enum Mode {
+    Align(AlignMode),
+    ClipboardHistory(ClipboardHistoryMode),
     Command(CommandMode),
+    Completion(CompletionMode),
     Confirm(ConfirmMode),
+    ConfirmExit(ConfirmExitMode),
     Exit,
     Insert,
     Jump(JumpMode),
     LineJump(LineJumpMode),
-    Normal,
+    MacroRegister(MacroRegisterMode),
+    Normal(NormalMode),
     Open(OpenMode),
     Paste,
     Path(PathMode),
+    Register(RegisterMode),
     Search(SearchMode),
     Select(SelectMode),
+    SelectBlock(SelectBlockMode),
     SelectLine(SelectLineMode),
+    ShellCommand(ShellCommandMode),
+    Surround(SurroundMode),
     SymbolJump(SymbolJumpMode),
     Syntax(SyntaxMode),
+    TextObject(TextObjectMode),
     Theme(ThemeMode),
+    UndoTree(UndoTreeMode),
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum ModeKey {
+    Align,
+    ClipboardHistory,
     Command,
+    Completion,
     Confirm,
+    ConfirmExit,
     Exit,
     Insert,
     Jump,
     LineJump,
+    MacroRegister,
     Normal,
     Open,
     Paste,
     Path,
+    Register,
     Search,
     Select,
+    SelectBlock,
     SelectLine,
+    ShellCommand,
+    Surround,
     SymbolJump,
     Syntax,
+    TextObject,
     Theme,
+    UndoTree,
 }
 
+pub use self::align::AlignMode;
+pub use self::clipboard_history::ClipboardHistoryMode;
 pub use self::command::CommandMode;
+pub use self::completion::CompletionMode;
 pub use self::confirm::ConfirmMode;
+pub use self::confirm_exit::ConfirmExitMode;
 pub use self::jump::JumpMode;
 pub use self::line_jump::LineJumpMode;
+pub use self::macro_register::{MacroAction, MacroRegisterMode};
+pub use self::normal::NormalMode;
 pub use self::open::OpenMode;
 pub use self::path::PathMode;
+pub use self::register::RegisterMode;
 pub use self::search::SearchMode;
 pub use self::search_select::{PopSearchToken, SearchSelectConfig, SearchSelectMode};
 pub use self::select::SelectMode;
+pub use self::select_block::SelectBlockMode;
 pub use self::select_line::SelectLineMode;
+pub use self::shell_command::{ShellCommandAction, ShellCommandMode};
+pub use self::surround::{SurroundAction, SurroundMode};
 pub use self::symbol_jump::SymbolJumpMode;
 pub use self::syntax::SyntaxMode;
+pub use self::text_object::{TextObjectAction, TextObjectMode};
 pub use self::theme::ThemeMode;
+pub use self::undo_tree::UndoTreeMode;
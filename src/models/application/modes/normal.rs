@@ -0,0 +1,35 @@
+#[derive(Default)]
+pub struct NormalMode {
+    pub count: Option<usize>,
+}
+
+impl NormalMode {
+    pub fn new() -> NormalMode {
+        NormalMode::default()
+    }
+
+    /// Appends a digit to the count prefix applied to the next command.
+    pub fn push_count_digit(&mut self, digit: usize) {
+        self.count = Some(
+            self.count
+                .unwrap_or(0)
+                .saturating_mul(10)
+                .saturating_add(digit),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NormalMode;
+
+    #[test]
+    fn push_count_digit_builds_a_decimal_count() {
+        let mut mode = NormalMode::new();
+        mode.push_count_digit(1);
+        mode.push_count_digit(0);
+        mode.push_count_digit(5);
+
+        assert_eq!(mode.count, Some(105));
+    }
+}
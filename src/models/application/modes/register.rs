@@ -0,0 +1,16 @@
+use std::fmt;
+
+#[derive(Default)]
+pub struct RegisterMode {}
+
+impl RegisterMode {
+    pub fn new() -> RegisterMode {
+        RegisterMode::default()
+    }
+}
+
+impl fmt::Display for RegisterMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "REGISTER")
+    }
+}
@@ -0,0 +1,95 @@
+use crate::util::column::{display_column, offset_at_column};
+use scribe::buffer::{Position, Range};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A rectangular selection, spanning the lines and (on-screen) columns
+/// between the anchor and the cursor, which is excluded like in select mode.
+pub struct SelectBlockMode {
+    pub anchor: Position,
+}
+
+impl SelectBlockMode {
+    pub fn new(anchor: Position) -> SelectBlockMode {
+        SelectBlockMode { anchor }
+    }
+
+    pub fn reset(&mut self, anchor: Position) {
+        self.anchor = anchor;
+    }
+
+    /// The selected portion of each line, from top to bottom. Lines ending
+    /// before the left edge of the block yield an empty range at their end.
+    pub fn to_ranges(&self, cursor: &Position, data: &str, tab_width: usize) -> Vec<Range> {
+        let lines: Vec<&str> = data.lines().collect();
+        let line = |number: usize| lines.get(number).copied().unwrap_or("");
+
+        let anchor_column = display_column(line(self.anchor.line), self.anchor.offset, tab_width);
+        let cursor_column = display_column(line(cursor.line), cursor.offset, tab_width);
+        let (left, right) = if anchor_column <= cursor_column {
+            (anchor_column, cursor_column)
+        } else {
+            (cursor_column, anchor_column)
+        };
+        let (top, bottom) = if self.anchor.line <= cursor.line {
+            (self.anchor.line, cursor.line)
+        } else {
+            (cursor.line, self.anchor.line)
+        };
+
+        (top..=bottom)
+            .map(|number| {
+                let content = line(number);
+                let start = offset_at_column(content, left, tab_width);
+
+                // Include the grapheme displayed in the last column, even if it spans beyond it.
+                let end = if right > left {
+                    let length = content.graphemes(true).count();
+                    (offset_at_column(content, right - 1, tab_width) + 1).min(length)
+                } else {
+                    start
+                };
+
+                Range::new(
+                    Position {
+                        line: number,
+                        offset: start,
+                    },
+                    Position {
+                        line: number,
+                        offset: end,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SelectBlockMode;
+    use scribe::buffer::{Position, Range};
+
+    #[test]
+    fn to_ranges_spans_columns_between_anchor_and_cursor_including_tabs() {
+        let mode = SelectBlockMode::new(Position { line: 2, offset: 3 });
+        let cursor = Position { line: 0, offset: 1 };
+
+        assert_eq!(
+            mode.to_ranges(&cursor, "a\tbc\nab\nabcd", 4),
+            vec![
+                Range::new(
+                    Position { line: 0, offset: 1 },
+                    Position { line: 0, offset: 2 }
+                ),
+                Range::new(
+                    Position { line: 1, offset: 1 },
+                    Position { line: 1, offset: 2 }
+                ),
+                Range::new(
+                    Position { line: 2, offset: 1 },
+                    Position { line: 2, offset: 3 }
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use scribe::buffer::Range;
+use std::fmt;
+
+/// Prompts for a shell command, whose output is used as described by the
+/// action the mode was entered with.
+#[derive(Default)]
+pub struct ShellCommandMode {
+    pub input: String,
+    pub action: ShellCommandAction,
+}
+
+/// What to do with a shell command's output.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ShellCommandAction {
+    /// Insert the output at the cursor.
+    #[default]
+    Insert,
+    /// Pipe the range selected before entering the mode (or the entire
+    /// buffer without one) through the command, replacing it with the output.
+    Filter(Option<Range>),
+}
+
+impl ShellCommandMode {
+    pub fn new() -> ShellCommandMode {
+        ShellCommandMode::default()
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.input.push(c);
+    }
+
+    pub fn pop_char(&mut self) {
+        self.input.pop();
+    }
+
+    pub fn reset(&mut self, action: ShellCommandAction) {
+        self.input.clear();
+        self.action = action;
+    }
+}
+
+impl fmt::Display for ShellCommandMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.action {
+            ShellCommandAction::Insert => write!(f, "INSERT OUTPUT"),
+            ShellCommandAction::Filter(_) => write!(f, "FILTER"),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use scribe::buffer::Range;
+use std::fmt;
+
+/// What to do with the delimiters chosen in surround mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SurroundAction {
+    #[default]
+    Add,
+    Change,
+    Delete,
+}
+
+#[derive(Default)]
+pub struct SurroundMode {
+    pub action: SurroundAction,
+    /// The text to surround when adding delimiters, or the existing pair
+    /// (including its delimiters) once it's been chosen, when changing them.
+    pub range: Option<Range>,
+    /// Whether added delimiters are placed on their own lines.
+    pub linewise: bool,
+    /// The tag being typed, when surrounding with an HTML tag.
+    pub tag: Option<String>,
+}
+
+impl SurroundMode {
+    pub fn new() -> SurroundMode {
+        SurroundMode::default()
+    }
+
+    pub fn reset(&mut self, action: SurroundAction, range: Option<Range>, linewise: bool) {
+        self.action = action;
+        self.range = range;
+        self.linewise = linewise;
+        self.tag = None;
+    }
+}
+
+impl fmt::Display for SurroundMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SURROUND")
+    }
+}
@@ -0,0 +1,33 @@
+use std::fmt;
+
+/// What to do with the text object chosen in text object mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TextObjectAction {
+    #[default]
+    Select,
+    Change,
+    Delete,
+}
+
+#[derive(Default)]
+pub struct TextObjectMode {
+    pub action: TextObjectAction,
+    pub around: bool,
+}
+
+impl TextObjectMode {
+    pub fn new() -> TextObjectMode {
+        TextObjectMode::default()
+    }
+
+    pub fn reset(&mut self, action: TextObjectAction, around: bool) {
+        self.action = action;
+        self.around = around;
+    }
+}
+
+impl fmt::Display for TextObjectMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TEXT OBJECT")
+    }
+}
@@ -0,0 +1,139 @@
+use crate::models::application::modes::{SearchSelectConfig, SearchSelectMode};
+use crate::models::application::UndoState;
+use crate::util::SelectableVec;
+use fragment;
+use fragment::matching::AsStr;
+use std::fmt;
+use std::slice::Iter;
+
+pub struct UndoTreeMode {
+    insert: bool,
+    input: String,
+    entries: Vec<UndoTreeEntry>,
+    results: SelectableVec<UndoTreeEntry>,
+    config: SearchSelectConfig,
+}
+
+/// A buffer's undo state, described by its number, age,
+/// and the change leading to it; the current state is starred.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UndoTreeEntry {
+    pub number: usize,
+    pub description: String,
+}
+
+impl UndoTreeEntry {
+    pub fn new(state: &UndoState, current: bool) -> UndoTreeEntry {
+        UndoTreeEntry {
+            number: state.number,
+            description: format!(
+                "{} {:>4}  {:>7}  {}",
+                if current { "*" } else { " " },
+                state.number,
+                state.age(),
+                state.summary()
+            ),
+        }
+    }
+}
+
+impl fmt::Display for UndoTreeEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", &self.description)
+    }
+}
+
+impl AsStr for UndoTreeEntry {
+    fn as_str(&self) -> &str {
+        &self.description
+    }
+}
+
+impl UndoTreeMode {
+    pub fn new(config: SearchSelectConfig) -> UndoTreeMode {
+        UndoTreeMode {
+            insert: true,
+            input: String::new(),
+            entries: Vec::new(),
+            results: SelectableVec::new(Vec::new()),
+            config,
+        }
+    }
+
+    /// Lists the states, most recently reached first.
+    pub fn reset(&mut self, states: &[UndoState], current: usize, config: SearchSelectConfig) {
+        self.input.clear();
+        self.insert = true;
+        self.entries = states
+            .iter()
+            .rev()
+            .map(|state| UndoTreeEntry::new(state, state.number == current))
+            .collect();
+        self.results = SelectableVec::new(Vec::new());
+        self.config = config;
+    }
+}
+
+impl fmt::Display for UndoTreeMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UNDO")
+    }
+}
+
+impl SearchSelectMode for UndoTreeMode {
+    type Item = UndoTreeEntry;
+
+    fn search(&mut self) {
+        // Find the states we're looking for using the query.
+        let results = if self.input.is_empty() {
+            self.entries
+                .iter()
+                .take(self.config.max_results)
+                .cloned()
+                .collect()
+        } else {
+            fragment::matching::find(&self.input, &self.entries, self.config.max_results)
+                .into_iter()
+                .map(|i| i.clone())
+                .collect()
+        };
+
+        self.results = SelectableVec::new(results);
+    }
+
+    fn query(&mut self) -> &mut String {
+        &mut self.input
+    }
+
+    fn insert_mode(&self) -> bool {
+        self.insert
+    }
+
+    fn set_insert_mode(&mut self, insert_mode: bool) {
+        self.insert = insert_mode;
+    }
+
+    fn results(&self) -> Iter<'_, UndoTreeEntry> {
+        self.results.iter()
+    }
+
+    fn selection(&self) -> Option<&UndoTreeEntry> {
+        self.results.selection()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.results.selected_index()
+    }
+
+    fn select_previous(&mut self) {
+        self.results.select_previous();
+    }
+
+    fn select_next(&mut self) {
+        self.results.select_next();
+    }
+
+    fn config(&self) -> &SearchSelectConfig {
+        &self.config
+    }
+}
@@ -2,9 +2,13 @@ use crate::errors::*;
 use crate::input::KeyMap;
 use crate::models::application::modes::open;
 use crate::models::application::modes::SearchSelectConfig;
+use crate::models::application::ClipboardBackend;
 use app_dirs2::{app_root, get_app_root, AppDataType, AppInfo};
 use bloodhound::ExclusionPattern;
+use regex::Regex;
 use scribe::Buffer;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fs::OpenOptions;
 use std::io::Read;
 use std::path::{Path, PathBuf};
@@ -17,17 +21,34 @@ const APP_INFO: AppInfo = AppInfo {
     author: "Jordan MacDonald",
 };
 const GIT_TOOL_KEY: &str = "git_tool";
+const AUTO_PAIRS_KEY: &str = "auto_pairs";
+const BLOCK_COMMENT_PREFIX_KEY: &str = "block_comment_prefix";
+const BLOCK_COMMENT_SUFFIX_KEY: &str = "block_comment_suffix";
+const CLIPBOARD_KEY: &str = "clipboard";
+const DEFAULT_AUTO_PAIRS: [(char, char); 6] = [
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('"', '"'),
+    ('\'', '\''),
+    ('`', '`'),
+];
 const FILE_MANAGER_KEY: &str = "file_manager";
 static FILE_MANAGER_TMP_FILE_PATH: LazyLock<String> =
     LazyLock::new(|| format!("/tmp/amp_selected_file_{}", process::id()));
 const FILE_NAME: &str = "config.yml";
 const FORMAT_TOOL_KEY: &str = "format_tool";
+const DECREASE_INDENT_PATTERN_KEY: &str = "decrease_indent_pattern";
+const INCREASE_INDENT_PATTERN_KEY: &str = "increase_indent_pattern";
 const LINE_COMMENT_PREFIX_KEY: &str = "line_comment_prefix";
 const LINE_LENGTH_GUIDE_KEY: &str = "line_length_guide";
 const LINE_WRAPPING_KEY: &str = "line_wrapping";
+const MACROS_FILE_NAME: &str = "macros.yml";
 const OPEN_MODE_KEY: &str = "open_mode";
 const OPEN_MODE_EXCLUSIONS_KEY: &str = "exclusions";
+const PERSISTENT_UNDO_KEY: &str = "persistent_undo";
 const SEARCH_SELECT_KEY: &str = "search_select";
+const SNIPPETS_FILE_NAME: &str = "snippets.yml";
 const SOFT_TABS_KEY: &str = "soft_tabs";
 const SYNTAX_PATH: &str = "syntaxes";
 const TAB_WIDTH_KEY: &str = "tab_width";
@@ -36,6 +57,11 @@ const TRANSPARENT_BACKGROUND_KEY: &str = "transparent_background";
 const THEME_PATH: &str = "themes";
 const TYPES_KEY: &str = "types";
 const TYPES_SYNTAX_KEY: &str = "syntax";
+const UNDO_HISTORY_PATH: &str = "undo_history";
+
+// Compiled type-specific patterns, keyed by extension and setting.
+type PatternCache =
+    HashMap<(String, &'static str), std::result::Result<Option<Regex>, regex::Error>>;
 
 /// Loads, creates, and provides default values for application preferences.
 /// Values are immutable once loaded, with the exception of those that provide
@@ -45,6 +71,7 @@ pub struct Preferences {
     data: Option<Yaml>,
     keymap: KeyMap,
     theme: Option<String>,
+    patterns: RefCell<PatternCache>,
 }
 
 impl Preferences {
@@ -55,6 +82,7 @@ impl Preferences {
             data,
             keymap: KeyMap::default().expect("Failed to load default keymap!"),
             theme: None,
+            patterns: RefCell::new(HashMap::new()),
         }
     }
 
@@ -69,6 +97,7 @@ impl Preferences {
             data,
             keymap,
             theme: None,
+            patterns: RefCell::new(HashMap::new()),
         })
     }
 
@@ -82,6 +111,7 @@ impl Preferences {
         self.data = data;
         self.keymap = keymap;
         self.theme = None;
+        self.patterns.get_mut().clear();
 
         Ok(())
     }
@@ -97,6 +127,22 @@ impl Preferences {
             .context("Couldn't create preferences directory or build a path to it.")
     }
 
+    /// A path pointing to the user's recorded macros, next to the config file.
+    pub fn macros_path() -> Result<PathBuf> {
+        Ok(Preferences::directory()?.join(MACROS_FILE_NAME))
+    }
+
+    /// A path pointing to the user's snippet templates, next to the config file.
+    pub fn snippets_path() -> Result<PathBuf> {
+        Ok(Preferences::directory()?.join(SNIPPETS_FILE_NAME))
+    }
+
+    /// A path pointing to the directory in which buffer undo history is persisted.
+    pub fn undo_history_path() -> Result<PathBuf> {
+        config_subdirectory(UNDO_HISTORY_PATH)
+            .context("Couldn't build a path to the undo history directory.")
+    }
+
     /// A path pointing to the user syntax definition directory.
     pub fn syntax_path() -> Result<PathBuf> {
         config_subdirectory(SYNTAX_PATH)
@@ -279,6 +325,29 @@ impl Preferences {
             })
     }
 
+    /// Whether undo history is saved for files, and restored when they're
+    /// next opened, provided they haven't been changed in the meantime.
+    pub fn persistent_undo(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|data| data[PERSISTENT_UNDO_KEY].as_bool())
+            .unwrap_or_else(|| {
+                self.default[PERSISTENT_UNDO_KEY]
+                    .as_bool()
+                    .expect("Couldn't find default persistent undo setting!")
+            })
+    }
+
+    /// The clipboard backend, if one has been configured; the clipboard
+    /// chooses one based on what's available otherwise.
+    pub fn clipboard_backend(&self) -> Result<Option<ClipboardBackend>> {
+        self.data
+            .as_ref()
+            .and_then(|data| data[CLIPBOARD_KEY].as_str())
+            .map(str::parse)
+            .transpose()
+    }
+
     pub fn tab_content(&self, path: Option<&PathBuf>) -> String {
         if self.soft_tabs(path) {
             format!("{:1$}", "", self.tab_width(path))
@@ -307,13 +376,84 @@ impl Preferences {
     }
 
     pub fn line_comment_prefix(&self, path: &PathBuf) -> Option<String> {
+        self.type_string(path, LINE_COMMENT_PREFIX_KEY)
+    }
+
+    pub fn block_comment_prefix(&self, path: &PathBuf) -> Option<String> {
+        self.type_string(path, BLOCK_COMMENT_PREFIX_KEY)
+    }
+
+    pub fn block_comment_suffix(&self, path: &PathBuf) -> Option<String> {
+        self.type_string(path, BLOCK_COMMENT_SUFFIX_KEY)
+    }
+
+    /// A pattern matching lines after which the indentation is increased,
+    /// for blocks that aren't opened by a bracket or recognized by scope.
+    pub fn increase_indent_pattern(&self, path: Option<&PathBuf>) -> Result<Option<Regex>> {
+        self.type_pattern(path, INCREASE_INDENT_PATTERN_KEY)
+    }
+
+    /// A pattern matching lines that close a block, which are outdented as
+    /// they're typed, the same way a closing bracket on its own line is.
+    pub fn decrease_indent_pattern(&self, path: Option<&PathBuf>) -> Result<Option<Regex>> {
+        self.type_pattern(path, DECREASE_INDENT_PATTERN_KEY)
+    }
+
+    // Patterns are compiled once per load, since they're used as lines are typed.
+    fn type_pattern(&self, path: Option<&PathBuf>, key: &'static str) -> Result<Option<Regex>> {
+        let Some(extension) = path_extension(path) else {
+            return Ok(None);
+        };
+
+        self.patterns
+            .borrow_mut()
+            .entry((extension.to_string(), key))
+            .or_insert_with(|| {
+                path.and_then(|path| self.type_string(path, key))
+                    .map(|pattern| Regex::new(&pattern))
+                    .transpose()
+            })
+            .clone()
+            .with_context(|| format!("Invalid {key} setting"))
+    }
+
+    // Looks up a type-specific string value, falling back to the default.
+    fn type_string(&self, path: &PathBuf, key: &str) -> Option<String> {
         let extension = path_extension(Some(path))?;
 
         self.data
             .as_ref()
-            .and_then(|data| data[TYPES_KEY][extension][LINE_COMMENT_PREFIX_KEY].as_str())
-            .or_else(|| self.default[TYPES_KEY][extension][LINE_COMMENT_PREFIX_KEY].as_str())
-            .map(|prefix| prefix.to_owned())
+            .and_then(|data| data[TYPES_KEY][extension][key].as_str())
+            .or_else(|| self.default[TYPES_KEY][extension][key].as_str())
+            .map(|value| value.to_owned())
+    }
+
+    /// The pairs whose closing character is inserted along with the opening
+    /// one. Auto-pairing is opt-in: "true" enables the default pairs, while
+    /// a list of two-character strings (e.g. "()") specifies them explicitly.
+    pub fn auto_pairs(&self, path: Option<&PathBuf>) -> Vec<(char, char)> {
+        let Some(extension) = path_extension(path) else {
+            return Vec::new();
+        };
+
+        match self
+            .data
+            .as_ref()
+            .map(|data| &data[TYPES_KEY][extension][AUTO_PAIRS_KEY])
+        {
+            Some(Yaml::Boolean(true)) => DEFAULT_AUTO_PAIRS.to_vec(),
+            Some(Yaml::Array(pairs)) => pairs
+                .iter()
+                .filter_map(|pair| {
+                    let mut characters = pair.as_str()?.chars();
+                    match (characters.next(), characters.next(), characters.next()) {
+                        (Some(open), Some(close), None) => Some((open, close)),
+                        _ => None,
+                    }
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
     }
 
     pub fn syntax_definition_name(&self, path: &Path) -> Option<String> {
@@ -495,6 +635,9 @@ fn path_extension(path: Option<&PathBuf>) -> Option<&str> {
 mod tests {
     use super::{ExclusionPattern, Preferences, YamlLoader};
     use crate::input::KeyMap;
+    use crate::models::application::ClipboardBackend;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
     use std::path::{Path, PathBuf};
     use std::process::{self, Command};
     use yaml_rust::yaml::{Hash, Yaml};
@@ -814,6 +957,34 @@ mod tests {
         assert!(preferences.open_mode_exclusions().unwrap().is_none());
     }
 
+    #[test]
+    fn auto_pairs_are_disabled_by_default() {
+        let preferences = Preferences::new(None);
+
+        assert!(preferences
+            .auto_pairs(Some(&PathBuf::from("preferences.rs")))
+            .is_empty());
+    }
+
+    #[test]
+    fn auto_pairs_returns_default_or_user_defined_type_specific_pairs() {
+        let data = YamlLoader::load_from_str(
+            "types:\n  rs:\n    auto_pairs: [\"()\", \"<>\", \"bad\"]\n  js:\n    auto_pairs: true",
+        )
+        .unwrap();
+        let preferences = Preferences::new(data.into_iter().next());
+
+        assert_eq!(
+            preferences.auto_pairs(Some(&PathBuf::from("preferences.rs"))),
+            vec![('(', ')'), ('<', '>')]
+        );
+        assert_eq!(
+            preferences.auto_pairs(Some(&PathBuf::from("preferences.js"))),
+            super::DEFAULT_AUTO_PAIRS.to_vec()
+        );
+        assert!(preferences.auto_pairs(None).is_empty());
+    }
+
     #[test]
     fn line_comment_prefix_returns_correct_default_type_specific_data() {
         let preferences = Preferences::new(None);
@@ -857,6 +1028,75 @@ mod tests {
         );
     }
 
+    #[test]
+    fn block_comment_delimiters_return_correct_default_type_specific_data() {
+        let preferences = Preferences::new(None);
+
+        assert_eq!(
+            preferences.block_comment_prefix(&PathBuf::from("index.html")),
+            Some("<!--".into())
+        );
+        assert_eq!(
+            preferences.block_comment_suffix(&PathBuf::from("index.html")),
+            Some("-->".into())
+        );
+    }
+
+    #[test]
+    fn block_comment_delimiters_return_correct_user_defined_type_specific_data() {
+        let data = YamlLoader::load_from_str(
+            "types:\n  abc:\n    block_comment_prefix: (*\n    block_comment_suffix: '*)'",
+        )
+        .unwrap();
+        let preferences = Preferences::new(data.into_iter().next());
+
+        assert_eq!(
+            preferences.block_comment_prefix(&PathBuf::from("preferences.abc")),
+            Some("(*".into())
+        );
+        assert_eq!(
+            preferences.block_comment_suffix(&PathBuf::from("preferences.abc")),
+            Some("*)".into())
+        );
+        assert_eq!(
+            preferences.block_comment_prefix(&PathBuf::from("preferences.xyz")),
+            None
+        );
+    }
+
+    #[test]
+    fn indent_patterns_return_correct_user_defined_type_specific_data() {
+        let data = YamlLoader::load_from_str(
+            "types:\n  abc:\n    increase_indent_pattern: 'begin$'\n    decrease_indent_pattern: '^\\s*end$'",
+        )
+        .unwrap();
+        let preferences = Preferences::new(data.into_iter().next());
+        let path = PathBuf::from("preferences.abc");
+
+        let increase_pattern = preferences
+            .increase_indent_pattern(Some(&path))
+            .unwrap()
+            .unwrap();
+        assert!(increase_pattern.is_match("begin"));
+        let decrease_pattern = preferences
+            .decrease_indent_pattern(Some(&path))
+            .unwrap()
+            .unwrap();
+        assert!(decrease_pattern.is_match("  end"));
+        assert!(preferences.increase_indent_pattern(None).unwrap().is_none());
+    }
+
+    #[test]
+    fn indent_patterns_return_an_error_when_invalid() {
+        let data =
+            YamlLoader::load_from_str("types:\n  abc:\n    increase_indent_pattern: '('").unwrap();
+        let preferences = Preferences::new(data.into_iter().next());
+
+        assert!(preferences
+            .increase_indent_pattern(Some(&PathBuf::from("preferences.abc")))
+            .is_err());
+    }
+
     #[test]
     fn reload_clears_in_memory_theme() {
         // Create an on-disk preferences file first, if one doesn't already exist.
@@ -886,6 +1126,7 @@ mod tests {
             data: None,
             keymap: KeyMap::from(&Hash::new()).unwrap(),
             theme: None,
+            patterns: RefCell::new(HashMap::new()),
         };
 
         // Reload the preferences, ensuring that it refreshes the keymap.
@@ -1004,4 +1245,20 @@ mod tests {
             format!("{:?}", expected_command)
         );
     }
+
+    #[test]
+    fn clipboard_backend_parses_the_configured_backend() {
+        let data = YamlLoader::load_from_str("clipboard: osc52").unwrap();
+        let preferences = Preferences::new(data.into_iter().next());
+        assert_eq!(
+            preferences.clipboard_backend().unwrap(),
+            Some(ClipboardBackend::Osc52)
+        );
+
+        let data = YamlLoader::load_from_str("clipboard: x11").unwrap();
+        let preferences = Preferences::new(data.into_iter().next());
+        assert!(preferences.clipboard_backend().is_err());
+
+        assert_eq!(Preferences::new(None).clipboard_backend().unwrap(), None);
+    }
 }
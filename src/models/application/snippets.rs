@@ -0,0 +1,384 @@
+use crate::errors::*;
+use crate::util::multi_cursor::{index_of, position_of};
+use crate::util::snippet::{Snippet, TabStop};
+use scribe::buffer::Range;
+use scribe::Buffer;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use unicode_segmentation::UnicodeSegmentation;
+use yaml_rust::yaml::Yaml;
+use yaml_rust::YamlLoader;
+
+/// User-defined snippet templates, keyed by file type (i.e. extension) and
+/// trigger word, along with the tab stops of the last expanded snippet.
+#[derive(Default)]
+pub struct Snippets {
+    types: HashMap<String, HashMap<String, String>>,
+    expansion: Option<Expansion>,
+}
+
+// An expanded snippet's tab stops (in order of appearance), located within
+// the buffer's content as of the last sync, so that edits made since then
+// can be found and copied to mirrors.
+struct Expansion {
+    buffer: usize,
+    content: String,
+    stops: Vec<TabStop>,
+    current: usize,
+    pristine: bool,
+}
+
+impl Snippets {
+    pub fn new() -> Snippets {
+        Snippets::default()
+    }
+
+    /// Loads snippets from the specified YAML file, if it exists.
+    pub fn load(path: PathBuf) -> Result<Snippets> {
+        let mut snippets = Snippets::new();
+        if !path.exists() {
+            return Ok(snippets);
+        }
+
+        let data = fs::read_to_string(&path).context("Couldn't read snippets file")?;
+        let document = YamlLoader::load_from_str(&data)
+            .context("Couldn't parse snippets file")?
+            .into_iter()
+            .next();
+
+        if let Some(Yaml::Hash(types)) = document {
+            for (file_type, templates) in types {
+                let file_type = file_type
+                    .as_str()
+                    .context("Couldn't parse snippet file type")?;
+                let templates = templates
+                    .as_hash()
+                    .with_context(|| format!("Couldn't parse snippets for \"{file_type}\""))?;
+
+                for (trigger, template) in templates {
+                    let (Some(trigger), Some(template)) = (trigger.as_str(), template.as_str())
+                    else {
+                        bail!("Couldn't parse snippets for \"{file_type}\"");
+                    };
+                    snippets.add(file_type, trigger, template);
+                }
+            }
+        }
+
+        Ok(snippets)
+    }
+
+    pub fn add(&mut self, file_type: &str, trigger: &str, template: &str) {
+        self.types
+            .entry(file_type.to_string())
+            .or_default()
+            .insert(trigger.to_string(), template.to_string());
+    }
+
+    /// The template triggered by the word for the path's file type, if any.
+    pub fn get(&self, path: Option<&PathBuf>, trigger: &str) -> Option<&str> {
+        let file_type = path
+            .and_then(|p| p.extension().or_else(|| p.file_name()))
+            .and_then(|e| e.to_str())?;
+
+        self.types
+            .get(file_type)
+            .and_then(|templates| templates.get(trigger))
+            .map(String::as_str)
+    }
+
+    /// Inserts the snippet at the cursor, moving it to the first tab stop.
+    pub fn expand(&mut self, buffer: &mut Buffer, snippet: Snippet) {
+        let start = index_of(&buffer.data(), &buffer.cursor);
+        buffer.insert(snippet.text);
+
+        let Some(id) = buffer.id else {
+            return;
+        };
+        let stops = snippet
+            .stops
+            .into_iter()
+            .map(|stop| TabStop {
+                number: stop.number,
+                start: start + stop.start,
+                end: start + stop.end,
+            })
+            .collect();
+        self.expansion = Some(Expansion {
+            buffer: id,
+            content: buffer.data(),
+            stops,
+            current: 0,
+            pristine: true,
+        });
+
+        let first = self.next_number(None);
+        self.visit(buffer, first);
+    }
+
+    /// Moves the cursor to the expanded snippet's next tab stop, returning
+    /// false if there isn't one, or the cursor has left the current one.
+    pub fn next_stop(&mut self, buffer: &mut Buffer) -> bool {
+        self.sync(buffer);
+        let Some(ref expansion) = self.expansion else {
+            return false;
+        };
+        if buffer.id != Some(expansion.buffer) {
+            return false;
+        }
+
+        let cursor = index_of(&expansion.content, &buffer.cursor);
+        let stop = &expansion.stops[expansion.current];
+        if cursor < stop.start || cursor > stop.end {
+            self.expansion = None;
+            return false;
+        }
+
+        let number = self.next_number(Some(stop.number));
+        self.visit(buffer, number);
+
+        true
+    }
+
+    /// Updates the expanded snippet's tab stops to reflect edits made to the
+    /// current one, copying them to its mirrors. Edits made anywhere else
+    /// end the expansion, since its stops can no longer be located.
+    pub fn sync(&mut self, buffer: &mut Buffer) {
+        let Some(ref mut expansion) = self.expansion else {
+            return;
+        };
+        if buffer.id != Some(expansion.buffer) {
+            return;
+        }
+
+        let content = buffer.data();
+        if content != expansion.content && !expansion.update(buffer, content) {
+            self.expansion = None;
+        }
+    }
+
+    // The stop number following the specified one (or the first), visiting
+    // them in ascending order, with the final stop (zero) last.
+    fn next_number(&self, number: Option<usize>) -> usize {
+        let current = number.unwrap_or(0);
+
+        self.expansion
+            .iter()
+            .flat_map(|expansion| expansion.stops.iter())
+            .map(|stop| stop.number)
+            .filter(|&number| number > current)
+            .min()
+            .unwrap_or(0)
+    }
+
+    fn visit(&mut self, buffer: &mut Buffer, number: usize) {
+        let Some(ref mut expansion) = self.expansion else {
+            return;
+        };
+        let Some(index) = expansion.stops.iter().position(|s| s.number == number) else {
+            return;
+        };
+
+        buffer.cursor.move_to(position_of(
+            &expansion.content,
+            expansion.stops[index].start,
+        ));
+        expansion.current = index;
+        expansion.pristine = true;
+
+        // There's nowhere left to go after the final stop.
+        if number == 0 {
+            self.expansion = None;
+        }
+    }
+}
+
+impl Expansion {
+    // Finds the edit made since the last sync, returning false if it isn't
+    // confined to the current stop.
+    fn update(&mut self, buffer: &mut Buffer, mut content: String) -> bool {
+        let stop = self.stops[self.current].clone();
+        let old: Vec<&str> = self.content.graphemes(true).collect();
+        let new: Vec<&str> = content.graphemes(true).collect();
+
+        let prefix = old
+            .iter()
+            .zip(new.iter())
+            .take_while(|(a, b)| a == b)
+            .count()
+            .min(stop.end);
+        let suffix = old[prefix..]
+            .iter()
+            .rev()
+            .zip(new[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        if prefix < stop.start || old.len() - suffix > stop.end {
+            return false;
+        }
+        let mut end = stop.end + new.len() - old.len();
+
+        // Typing at the start of an untouched placeholder replaces it.
+        if self.pristine
+            && stop.end > stop.start
+            && new.len() > old.len()
+            && old[stop.start..] == new[stop.start + new.len() - old.len()..]
+        {
+            let inserted = new.len() - old.len();
+            buffer.delete_range(Range::new(
+                position_of(&content, stop.start + inserted),
+                position_of(&content, end),
+            ));
+            end = stop.start + inserted;
+            content = buffer.data();
+        }
+        self.pristine = false;
+
+        // Shift the stops following the edited one.
+        let growth = end as isize - stop.end as isize;
+        self.stops[self.current].end = end;
+        for later in self.stops[self.current + 1..].iter_mut() {
+            later.start = (later.start as isize + growth) as usize;
+            later.end = (later.end as isize + growth) as usize;
+        }
+
+        // Copy the stop's content to its mirrors, keeping the cursor in place.
+        let text: String = content
+            .graphemes(true)
+            .skip(stop.start)
+            .take(end - stop.start)
+            .collect();
+        let length = text.graphemes(true).count();
+        let cursor = index_of(&content, &buffer.cursor);
+        let mut cursor_shift = 0;
+        for index in 0..self.stops.len() {
+            let mirror = self.stops[index].clone();
+            if index == self.current || mirror.number != stop.number {
+                continue;
+            }
+
+            let mirrored: String = content
+                .graphemes(true)
+                .skip(mirror.start)
+                .take(mirror.end - mirror.start)
+                .collect();
+            if mirrored == text {
+                continue;
+            }
+
+            let start = position_of(&content, mirror.start);
+            if mirror.end > mirror.start {
+                buffer.delete_range(Range::new(start, position_of(&content, mirror.end)));
+            }
+            buffer.cursor.move_to(start);
+            buffer.insert(text.clone());
+            content = buffer.data();
+
+            let growth = length as isize - (mirror.end - mirror.start) as isize;
+            self.stops[index].end = mirror.start + length;
+            for later in self.stops[index + 1..].iter_mut() {
+                later.start = (later.start as isize + growth) as usize;
+                later.end = (later.end as isize + growth) as usize;
+            }
+            if mirror.start < cursor {
+                cursor_shift += growth;
+            }
+            buffer.cursor.move_to(position_of(
+                &content,
+                (cursor as isize + cursor_shift) as usize,
+            ));
+        }
+
+        self.content = content;
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Snippets;
+    use crate::util::snippet::Snippet;
+    use scribe::buffer::Position;
+    use scribe::{Buffer, Workspace};
+    use std::path::Path;
+
+    fn expand(content: &str, template: &str) -> (Workspace, Snippets) {
+        let mut workspace = Workspace::new(Path::new(".")).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert(content);
+        workspace.add_buffer(buffer);
+
+        let mut snippets = Snippets::new();
+        let buffer = workspace.current_buffer.as_mut().unwrap();
+        snippets.expand(buffer, Snippet::parse(template, "").unwrap());
+
+        (workspace, snippets)
+    }
+
+    fn type_text(buffer: &mut Buffer, snippets: &mut Snippets, text: &str) {
+        for character in text.chars() {
+            buffer.insert(character.to_string());
+            buffer.cursor.move_right();
+            snippets.sync(buffer);
+        }
+    }
+
+    #[test]
+    fn next_stop_visits_stops_in_order_ending_with_the_final_one() {
+        let (mut workspace, mut snippets) = expand("\namp", "fn $2($1) {\n    $0\n}");
+        let buffer = workspace.current_buffer.as_mut().unwrap();
+
+        assert_eq!(*buffer.cursor, Position { line: 0, offset: 4 });
+        type_text(buffer, &mut snippets, "amp: Editor");
+        assert!(snippets.next_stop(buffer));
+        assert_eq!(*buffer.cursor, Position { line: 0, offset: 3 });
+        type_text(buffer, &mut snippets, "run");
+        assert!(snippets.next_stop(buffer));
+        assert_eq!(*buffer.cursor, Position { line: 1, offset: 4 });
+        assert_eq!(buffer.data(), "fn run(amp: Editor) {\n    \n}\namp");
+
+        assert!(!snippets.next_stop(buffer));
+    }
+
+    #[test]
+    fn typing_replaces_placeholders_and_updates_mirrors() {
+        let (mut workspace, mut snippets) =
+            expand("", "impl ${1:Type} {\n    fn new() -> $1 {}\n}");
+        let buffer = workspace.current_buffer.as_mut().unwrap();
+
+        type_text(buffer, &mut snippets, "Ed");
+        assert_eq!(buffer.data(), "impl Ed {\n    fn new() -> Ed {}\n}");
+        assert_eq!(*buffer.cursor, Position { line: 0, offset: 7 });
+
+        type_text(buffer, &mut snippets, "itor");
+        assert_eq!(buffer.data(), "impl Editor {\n    fn new() -> Editor {}\n}");
+
+        assert!(snippets.next_stop(buffer));
+        assert_eq!(*buffer.cursor, Position { line: 2, offset: 1 });
+    }
+
+    #[test]
+    fn moving_to_the_next_stop_keeps_untouched_placeholders() {
+        let (mut workspace, mut snippets) = expand("", "let ${1:name} = ${2:value};");
+        let buffer = workspace.current_buffer.as_mut().unwrap();
+
+        assert!(snippets.next_stop(buffer));
+        type_text(buffer, &mut snippets, "1");
+        assert_eq!(buffer.data(), "let name = 1;");
+    }
+
+    #[test]
+    fn editing_outside_of_the_current_stop_ends_the_expansion() {
+        let (mut workspace, mut snippets) = expand("amp", "${1:one} ${2:two} ");
+        let buffer = workspace.current_buffer.as_mut().unwrap();
+
+        buffer.cursor.move_to(Position {
+            line: 0,
+            offset: 10,
+        });
+        type_text(buffer, &mut snippets, "!");
+        assert!(!snippets.next_stop(buffer));
+    }
+}
@@ -0,0 +1,481 @@
+use crate::errors::*;
+use crate::util::undo_history::Change;
+use scribe::Buffer;
+use std::collections::HashMap;
+use std::time::SystemTime;
+use unicode_segmentation::UnicodeSegmentation;
+
+const SUMMARY_LENGTH: usize = 50;
+
+/// Every state that each buffer's content has been in, kept as a tree so
+/// that undoing and then making a change branches off instead of discarding
+/// the undone states. States are numbered in the order they were reached,
+/// which is the order followed when moving to earlier or later states.
+#[derive(Default)]
+pub struct UndoTree {
+    buffers: HashMap<usize, BufferStates>,
+}
+
+/// A recorded buffer state, along with the change leading to it from its parent.
+pub struct UndoState {
+    pub number: usize,
+    pub parent: Option<usize>,
+    pub time: SystemTime,
+    change: Option<Change>,
+}
+
+struct BufferStates {
+    states: Vec<UndoState>,
+    current: usize,
+    content: String,
+    revision: Option<usize>,
+}
+
+impl UndoTree {
+    pub fn new() -> UndoTree {
+        UndoTree::default()
+    }
+
+    /// Starts tracking the buffer's content, if it isn't already. The buffer's
+    /// revision (see `View::buffer_revision`) is used to skip recording it
+    /// again until it's changed.
+    pub fn track(&mut self, buffer: &Buffer, revision: Option<usize>) {
+        if let Some(id) = buffer.id {
+            self.buffers.entry(id).or_insert_with(|| BufferStates {
+                revision,
+                ..BufferStates::new(buffer.data())
+            });
+        }
+    }
+
+    /// Records the buffer's content as a new state branching from the current
+    /// one, even if it matches an earlier state (e.g. after undoing), so that
+    /// states stay in the order they were reached. Nothing is recorded if the
+    /// buffer's revision is the same as when it was last recorded, while
+    /// buffers without one are always compared against the current state.
+    pub fn record(&mut self, buffer: &Buffer, revision: Option<usize>) {
+        let Some(id) = buffer.id else {
+            return;
+        };
+
+        match self.buffers.get_mut(&id) {
+            Some(states) if revision.is_some() && states.revision == revision => (),
+            Some(states) => {
+                states.record(buffer.data());
+                states.revision = revision;
+            }
+            None => self.track(buffer, revision),
+        }
+    }
+
+    /// Stops tracking the buffer (e.g. once it's been closed).
+    pub fn forget(&mut self, buffer: &Buffer) {
+        if let Some(id) = buffer.id {
+            self.buffers.remove(&id);
+        }
+    }
+
+    /// Starts tracking the buffer with history restored from a previous session,
+    /// as a chain of states made by the changes leading to its current content.
+    pub fn restore(&mut self, buffer: &Buffer, changes: Vec<Change>) -> Result<()> {
+        let Some(id) = buffer.id else {
+            return Ok(());
+        };
+
+        let mut content = buffer.data();
+        for change in changes.iter().rev() {
+            content = change.revert(&content)?;
+        }
+        let mut states = BufferStates::new(content);
+        for change in changes {
+            let content = change.replay(&states.content)?;
+            states.push(change, content);
+        }
+        self.buffers.insert(id, states);
+
+        Ok(())
+    }
+
+    /// The changes leading from the buffer's original content to its
+    /// current state, oldest first, if the buffer is being tracked.
+    pub fn changes(&self, buffer: &Buffer) -> Option<Vec<&Change>> {
+        let states = self.buffers.get(&buffer.id?)?;
+        let mut changes = Vec::new();
+        let mut number = Some(states.current);
+        while let Some(state) = number.map(|number| &states.states[number]) {
+            changes.extend(state.change.as_ref());
+            number = state.parent;
+        }
+        changes.reverse();
+
+        Some(changes)
+    }
+
+    /// The states recorded for the buffer, in the order they were reached.
+    pub fn states(&self, buffer: &Buffer) -> &[UndoState] {
+        buffer
+            .id
+            .and_then(|id| self.buffers.get(&id))
+            .map_or(&[], |states| &states.states)
+    }
+
+    /// The number of the buffer's current state.
+    pub fn current(&self, buffer: &Buffer) -> Option<usize> {
+        buffer
+            .id
+            .and_then(|id| self.buffers.get(&id))
+            .map(|states| states.current)
+    }
+
+    /// Moves the buffer to the state reached before the current one.
+    pub fn earlier(&mut self, buffer: &mut Buffer) -> Result<()> {
+        self.record(buffer, None);
+        let number = self
+            .current(buffer)
+            .and_then(|current| current.checked_sub(1))
+            .context("Already at the earliest change")?;
+
+        self.jump(buffer, number)
+    }
+
+    /// Moves the buffer to the state reached after the current one.
+    pub fn later(&mut self, buffer: &mut Buffer) -> Result<()> {
+        self.record(buffer, None);
+        let number = self
+            .current(buffer)
+            .map(|current| current + 1)
+            .filter(|&number| number < self.states(buffer).len())
+            .context("Already at the latest change")?;
+
+        self.jump(buffer, number)
+    }
+
+    /// Moves the buffer to the specified state as a single operation, which
+    /// can itself be undone, leaving the cursor at the start of the change.
+    pub fn jump(&mut self, buffer: &mut Buffer, number: usize) -> Result<()> {
+        self.record(buffer, None);
+        let states = buffer
+            .id
+            .and_then(|id| self.buffers.get_mut(&id))
+            .context("The buffer has no undo history")?;
+        if number >= states.states.len() {
+            bail!("The buffer has no undo state {number}");
+        }
+        if number == states.current {
+            return Ok(());
+        }
+
+        let content = states.content_at(number)?;
+        Change::between(&states.content, &content).apply(buffer);
+        states.content = content;
+        states.current = number;
+
+        Ok(())
+    }
+}
+
+impl UndoState {
+    /// A one-line description of the change leading to this state.
+    pub fn summary(&self) -> String {
+        let Some(ref change) = self.change else {
+            return String::from("original content");
+        };
+
+        // Changes span whole lines, so narrow them down to what differs.
+        let prefix = common_length(change.deleted.chars(), change.inserted.chars());
+        let suffix = common_length(
+            change.deleted[prefix..].chars().rev(),
+            change.inserted[prefix..].chars().rev(),
+        );
+        let deleted = &change.deleted[prefix..change.deleted.len() - suffix];
+        let inserted = &change.inserted[prefix..change.inserted.len() - suffix];
+        let (action, content, difference) = match (deleted.is_empty(), inserted.is_empty()) {
+            (_, true) => ("deleted", &change.deleted, deleted),
+            (true, false) => ("inserted", &change.inserted, inserted),
+            _ => ("changed", &change.inserted, inserted),
+        };
+
+        // Describe the lines containing the difference.
+        let first_line = content[..prefix].matches('\n').count();
+        let lines = difference.lines().count().max(1);
+        let line = content
+            .lines()
+            .skip(first_line)
+            .take(lines)
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("");
+        let mut excerpt: String = line.graphemes(true).take(SUMMARY_LENGTH).collect();
+        if excerpt.len() < line.len() {
+            excerpt.push_str("...");
+        }
+
+        let line_number = change.line + first_line + 1;
+        match lines {
+            1 => format!("line {line_number}: {action} {excerpt:?}"),
+            _ => format!(
+                "lines {}-{}: {} {:?}",
+                line_number,
+                line_number + lines - 1,
+                action,
+                excerpt
+            ),
+        }
+    }
+
+    /// How long ago the state was reached (e.g. "5m ago").
+    pub fn age(&self) -> String {
+        let seconds = self.time.elapsed().map_or(0, |age| age.as_secs());
+
+        match seconds {
+            0..=59 => format!("{seconds}s ago"),
+            60..=3599 => format!("{}m ago", seconds / 60),
+            3600..=86399 => format!("{}h ago", seconds / 3600),
+            _ => format!("{}d ago", seconds / 86400),
+        }
+    }
+}
+
+impl BufferStates {
+    fn new(content: String) -> BufferStates {
+        BufferStates {
+            states: vec![UndoState {
+                number: 0,
+                parent: None,
+                time: SystemTime::now(),
+                change: None,
+            }],
+            current: 0,
+            content,
+            revision: None,
+        }
+    }
+
+    fn record(&mut self, content: String) {
+        if content != self.content {
+            self.push(Change::between(&self.content, &content), content);
+        }
+    }
+
+    // Adds a state branching from the current one, and makes it current.
+    fn push(&mut self, change: Change, content: String) {
+        let number = self.states.len();
+        self.states.push(UndoState {
+            number,
+            parent: Some(self.current),
+            time: SystemTime::now(),
+            change: Some(change),
+        });
+        self.current = number;
+        self.content = content;
+    }
+
+    // Rebuilds the content of a state by reverting changes from the current
+    // state back to the nearest common ancestor, and replaying them from there.
+    fn content_at(&self, number: usize) -> Result<String> {
+        let mut path = vec![number];
+        while let Some(parent) = self.states[path[path.len() - 1]].parent {
+            path.push(parent);
+        }
+
+        let mut content = self.content.clone();
+        let mut number = self.current;
+        while !path.contains(&number) {
+            let state = &self.states[number];
+            if let Some(ref change) = state.change {
+                content = change.revert(&content)?;
+            }
+            number = state.parent.context("Undo history is missing a state")?;
+        }
+
+        let ancestor = path
+            .iter()
+            .position(|&state| state == number)
+            .context("Undo history is missing a state")?;
+        for &state in path[..ancestor].iter().rev() {
+            if let Some(ref change) = self.states[state].change {
+                content = change.replay(&content)?;
+            }
+        }
+
+        Ok(content)
+    }
+}
+
+// The length in bytes of the characters the sequences have in common.
+fn common_length(a: impl Iterator<Item = char>, b: impl Iterator<Item = char>) -> usize {
+    a.zip(b)
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UndoTree;
+    use crate::util::undo_history::Change;
+    use scribe::buffer::{Position, Range};
+    use scribe::{Buffer, Workspace};
+    use std::path::Path;
+
+    fn set_up(content: &str) -> (Workspace, UndoTree) {
+        let mut workspace = Workspace::new(Path::new(".")).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert(content);
+        workspace.add_buffer(buffer);
+
+        let mut tree = UndoTree::new();
+        tree.track(workspace.current_buffer.as_ref().unwrap(), None);
+
+        (workspace, tree)
+    }
+
+    #[test]
+    fn undone_states_remain_reachable_after_making_changes() {
+        let (mut workspace, mut tree) = set_up("amp\n");
+        let buffer = workspace.current_buffer.as_mut().unwrap();
+
+        buffer.insert("fn good() {}\n");
+        tree.record(buffer, None);
+        buffer.undo();
+        tree.record(buffer, None);
+        buffer.insert("x");
+        tree.record(buffer, None);
+
+        assert_eq!(tree.states(buffer).len(), 4);
+        assert_eq!(tree.states(buffer)[3].parent, Some(2));
+
+        tree.earlier(buffer).unwrap();
+        tree.earlier(buffer).unwrap();
+        assert_eq!(buffer.data(), "fn good() {}\namp\n");
+        assert_eq!(tree.current(buffer), Some(1));
+
+        tree.later(buffer).unwrap();
+        tree.later(buffer).unwrap();
+        assert_eq!(buffer.data(), "xamp\n");
+        assert!(tree.later(buffer).is_err());
+    }
+
+    #[test]
+    fn undoing_and_redoing_record_states_in_the_order_they_were_reached() {
+        let (mut workspace, mut tree) = set_up("amp");
+        let buffer = workspace.current_buffer.as_mut().unwrap();
+
+        buffer.insert("editor ");
+        tree.record(buffer, None);
+        buffer.undo();
+        tree.record(buffer, None);
+        buffer.redo();
+        tree.record(buffer, None);
+        assert_eq!(tree.current(buffer), Some(3));
+        assert_eq!(tree.states(buffer).len(), 4);
+
+        // Moving earlier revisits the undone content, rather than
+        // skipping back to the state it first matched.
+        tree.earlier(buffer).unwrap();
+        assert_eq!(buffer.data(), "amp");
+        assert_eq!(tree.current(buffer), Some(2));
+    }
+
+    #[test]
+    fn jumping_to_a_state_can_be_undone() {
+        let (mut workspace, mut tree) = set_up("amp");
+        let buffer = workspace.current_buffer.as_mut().unwrap();
+
+        buffer.insert("editor\n");
+        tree.record(buffer, None);
+        tree.jump(buffer, 0).unwrap();
+        assert_eq!(buffer.data(), "amp");
+
+        buffer.undo();
+        assert_eq!(buffer.data(), "editor\namp");
+    }
+
+    #[test]
+    fn summary_describes_the_change_leading_to_a_state() {
+        let (mut workspace, mut tree) = set_up("amp\n");
+        let buffer = workspace.current_buffer.as_mut().unwrap();
+
+        buffer.insert("  let editor = amp;\n\n");
+        tree.record(buffer, None);
+        buffer.delete_range(Range::new(
+            Position { line: 0, offset: 0 },
+            Position { line: 1, offset: 0 },
+        ));
+        tree.record(buffer, None);
+        buffer.delete_range(Range::new(
+            Position { line: 1, offset: 1 },
+            Position { line: 1, offset: 2 },
+        ));
+        buffer.cursor.move_to(Position { line: 1, offset: 1 });
+        buffer.insert("p");
+        tree.record(buffer, None);
+
+        let states = tree.states(buffer);
+        assert_eq!(states[0].summary(), "original content");
+        assert_eq!(
+            states[1].summary(),
+            "lines 1-2: inserted \"let editor = amp;\""
+        );
+        assert_eq!(states[2].summary(), "line 1: deleted \"let editor = amp;\"");
+        assert_eq!(states[3].summary(), "line 2: changed \"app\"");
+    }
+
+    #[test]
+    fn changes_lead_from_the_original_content_to_the_current_state() {
+        let (mut workspace, mut tree) = set_up("amp\n");
+        let buffer = workspace.current_buffer.as_mut().unwrap();
+
+        buffer.insert("editor\n");
+        tree.record(buffer, None);
+        tree.jump(buffer, 0).unwrap();
+        buffer.insert("x");
+        tree.record(buffer, None);
+
+        // The branch jumped away from isn't part of the current state's history.
+        let changes = tree.changes(buffer).unwrap();
+        assert_eq!(changes, [&Change::between("amp\n", "xamp\n")]);
+    }
+
+    #[test]
+    fn restored_changes_are_tracked_as_a_chain_of_states() {
+        let (mut workspace, mut tree) = set_up("");
+        let buffer = workspace.current_buffer.as_mut().unwrap();
+        buffer.insert("amp\neditor\n");
+        let changes = vec![
+            Change::between("", "amp\n"),
+            Change::between("amp\n", "amp\neditor\n"),
+        ];
+
+        tree.restore(buffer, changes).unwrap();
+
+        assert_eq!(tree.states(buffer).len(), 3);
+        assert_eq!(tree.current(buffer), Some(2));
+        tree.earlier(buffer).unwrap();
+        assert_eq!(buffer.data(), "amp\n");
+    }
+
+    #[test]
+    fn record_skips_buffers_whose_revision_is_unchanged() {
+        let (mut workspace, mut tree) = set_up("amp");
+        let buffer = workspace.current_buffer.as_mut().unwrap();
+        tree.record(buffer, Some(1));
+
+        buffer.insert("editor ");
+        tree.record(buffer, Some(1));
+        assert_eq!(tree.states(buffer).len(), 1);
+
+        tree.record(buffer, Some(2));
+        assert_eq!(tree.states(buffer).len(), 2);
+    }
+
+    #[test]
+    fn forget_stops_tracking_the_buffer() {
+        let (mut workspace, mut tree) = set_up("amp");
+        let buffer = workspace.current_buffer.as_mut().unwrap();
+
+        tree.forget(buffer);
+
+        assert!(tree.current(buffer).is_none());
+    }
+}
@@ -0,0 +1,81 @@
+use crate::util::completion;
+use scribe::Buffer;
+use std::collections::HashMap;
+
+/// The number of times each word appears in each buffer, used to complete
+/// words from buffers other than the current one. Counts are kept until the
+/// buffer's revision (see `View::buffer_revision`) changes, so that buffers
+/// aren't recounted every time a word is completed.
+#[derive(Default)]
+pub struct WordCounts {
+    buffers: HashMap<usize, (Option<usize>, HashMap<String, usize>)>,
+}
+
+impl WordCounts {
+    pub fn new() -> WordCounts {
+        WordCounts::default()
+    }
+
+    /// Adds the buffer's word counts to the specified ones, counting its
+    /// words again only if it's changed. Buffers without a revision are
+    /// always counted again.
+    pub fn add(
+        &mut self,
+        buffer: &Buffer,
+        revision: Option<usize>,
+        words: &mut HashMap<String, usize>,
+    ) {
+        let Some(id) = buffer.id else {
+            merge(words, &completion::word_counts(&buffer.data()));
+            return;
+        };
+
+        let (counted_revision, counts) = self.buffers.entry(id).or_default();
+        if revision.is_none() || *counted_revision != revision {
+            *counts = completion::word_counts(&buffer.data());
+            *counted_revision = revision;
+        }
+        merge(words, counts);
+    }
+
+    /// Discards the buffer's counts (e.g. once it's been closed).
+    pub fn forget(&mut self, buffer: &Buffer) {
+        if let Some(id) = buffer.id {
+            self.buffers.remove(&id);
+        }
+    }
+}
+
+fn merge(words: &mut HashMap<String, usize>, counts: &HashMap<String, usize>) {
+    for (word, count) in counts {
+        *words.entry(word.clone()).or_default() += count;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WordCounts;
+    use scribe::Buffer;
+    use std::collections::HashMap;
+
+    #[test]
+    fn add_only_counts_words_again_once_the_revision_changes() {
+        let mut word_counts = WordCounts::new();
+        let mut buffer = Buffer::new();
+        buffer.id = Some(1);
+        buffer.insert("amp amp");
+
+        let mut words = HashMap::new();
+        word_counts.add(&buffer, Some(1), &mut words);
+        assert_eq!(words.get("amp"), Some(&2));
+
+        buffer.insert("editor ");
+        let mut words = HashMap::new();
+        word_counts.add(&buffer, Some(1), &mut words);
+        assert_eq!(words.get("editor"), None);
+
+        let mut words = HashMap::new();
+        word_counts.add(&buffer, Some(2), &mut words);
+        assert_eq!(words.get("editor"), Some(&1));
+    }
+}
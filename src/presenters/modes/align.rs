@@ -0,0 +1,60 @@
+use crate::errors::*;
+use crate::models::application::modes::AlignMode;
+use crate::view::{Colors, CursorType, StatusLineData, Style, View};
+use scribe::buffer::Position;
+use scribe::Workspace;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub fn display(
+    workspace: &mut Workspace,
+    mode: &AlignMode,
+    view: &mut View,
+    error: &Option<Error>,
+) -> Result<()> {
+    let mut presenter = view.build_presenter()?;
+
+    // Draw the visible set of tokens to the terminal.
+    let buffer = workspace.current_buffer.as_ref().context(BUFFER_MISSING)?;
+    let data = buffer.data();
+    presenter.print_buffer(buffer, &data, &workspace.syntax_set, None, None)?;
+
+    let mode_display = format!(" {mode} ");
+    let delimiter_input = format!(" {}", mode.input);
+
+    let cursor_offset =
+        mode_display.graphemes(true).count() + delimiter_input.graphemes(true).count();
+
+    if let Some(e) = error {
+        presenter.print_error(e.to_string());
+    } else {
+        presenter.print_status_line(&[
+            StatusLineData {
+                content: mode_display,
+                style: Style::Default,
+                colors: Colors::PathMode,
+            },
+            StatusLineData {
+                content: delimiter_input,
+                style: Style::Default,
+                colors: Colors::Focused,
+            },
+        ]);
+    }
+
+    // Move the cursor to the end of the delimiter input.
+    {
+        let cursor_line = presenter.height() - 1;
+        presenter.set_cursor(Some(Position {
+            line: cursor_line,
+            offset: cursor_offset,
+        }));
+    }
+
+    // Show a blinking, vertical bar indicating input.
+    presenter.set_cursor_type(CursorType::BlinkingBar);
+
+    // Render the changes to the screen.
+    presenter.present()?;
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::errors::*;
+use crate::models::application::modes::CompletionMode;
+use crate::models::application::Cursors;
+use crate::presenters::current_buffer_status_line_data;
+use crate::view::{Colors, CursorType, StatusLineData, Style, View};
+use scribe::Workspace;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub fn display(
+    workspace: &mut Workspace,
+    mode: &CompletionMode,
+    cursors: &Cursors,
+    view: &mut View,
+    error: &Option<Error>,
+) -> Result<()> {
+    let mut presenter = view.build_presenter()?;
+    let buffer_status = current_buffer_status_line_data(workspace);
+    let buf = workspace.current_buffer.as_ref().context(BUFFER_MISSING)?;
+    let data = buf.data();
+    let cursor_highlights = cursors.highlights(buf);
+
+    // Draw the visible set of tokens to the terminal.
+    presenter.print_buffer(
+        buf,
+        &data,
+        &workspace.syntax_set,
+        Some(&cursor_highlights),
+        None,
+    )?;
+
+    // List the candidates under the word being completed.
+    let candidates: Vec<&str> = mode.results().map(String::as_str).collect();
+    presenter.print_popup(
+        &candidates,
+        mode.selected_index(),
+        mode.prefix.graphemes(true).count(),
+    );
+
+    if let Some(e) = error {
+        presenter.print_error(e.to_string());
+    } else {
+        presenter.print_status_line(&[
+            StatusLineData {
+                content: format!(" {mode} "),
+                style: Style::Default,
+                colors: Colors::Insert,
+            },
+            StatusLineData {
+                content: format!(" {}/{}", mode.selected_index() + 1, candidates.len()),
+                style: Style::Default,
+                colors: Colors::Focused,
+            },
+            buffer_status,
+        ]);
+    }
+
+    // Show a blinking, vertical bar indicating input.
+    presenter.set_cursor_type(CursorType::BlinkingBar);
+
+    // Render the changes to the screen.
+    presenter.present()?;
+
+    Ok(())
+}
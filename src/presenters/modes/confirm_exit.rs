@@ -0,0 +1,68 @@
+use crate::errors::*;
+use crate::models::application::modes::ConfirmExitMode;
+use crate::view::{Colors, CursorType, StatusLineData, Style, View};
+use scribe::buffer::Position;
+use scribe::Workspace;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub fn display(
+    workspace: &mut Workspace,
+    mode: &ConfirmExitMode,
+    view: &mut View,
+    error: &Option<Error>,
+) -> Result<()> {
+    let data;
+    let mut presenter = view.build_presenter()?;
+
+    if let Some(buf) = workspace.current_buffer.as_ref() {
+        data = buf.data();
+        presenter.print_buffer(buf, &data, &workspace.syntax_set, None, None)?;
+    }
+
+    // List the modified buffers at the top of the screen,
+    // ensuring content doesn't exceed the screen width.
+    for (line, path) in mode.modified_buffers.iter().enumerate() {
+        let content: String = format!("  {path}*")
+            .graphemes(true)
+            .take(presenter.width())
+            .collect();
+
+        let padded_content = format!("{:width$}", content, width = presenter.width());
+
+        presenter.print(
+            &Position { line, offset: 0 },
+            Style::Bold,
+            Colors::Default,
+            padded_content,
+        );
+    }
+
+    if let Some(e) = error {
+        presenter.print_error(e.to_string());
+    } else {
+        presenter.print_status_line(&[
+            StatusLineData {
+                content: format!(" {mode} "),
+                style: Style::Default,
+                colors: Colors::Warning,
+            },
+            StatusLineData {
+                content: format!(
+                    " {} modified buffer(s): (s)ave all, (d)iscard all, (c)ancel",
+                    mode.modified_buffers.len()
+                ),
+                style: Style::Bold,
+                colors: Colors::Warning,
+            },
+        ]);
+    }
+
+    // Hide the cursor; we're waiting on a decision, not input.
+    presenter.set_cursor(None);
+    presenter.set_cursor_type(CursorType::Block);
+
+    // Render the changes to the screen.
+    presenter.present()?;
+
+    Ok(())
+}
@@ -1,16 +1,29 @@
 use crate::errors::*;
+use crate::models::application::Cursors;
 use crate::presenters::current_buffer_status_line_data;
 use crate::view::{Colors, CursorType, StatusLineData, Style, View};
 use scribe::Workspace;
 
-pub fn display(workspace: &mut Workspace, view: &mut View, error: &Option<Error>) -> Result<()> {
+pub fn display(
+    workspace: &mut Workspace,
+    cursors: &Cursors,
+    view: &mut View,
+    error: &Option<Error>,
+) -> Result<()> {
     let mut presenter = view.build_presenter()?;
     let buffer_status = current_buffer_status_line_data(workspace);
     let buf = workspace.current_buffer.as_ref().context(BUFFER_MISSING)?;
     let data = buf.data();
+    let cursor_highlights = cursors.highlights(buf);
 
     // Draw the visible set of tokens to the terminal.
-    presenter.print_buffer(buf, &data, &workspace.syntax_set, None, None)?;
+    presenter.print_buffer(
+        buf,
+        &data,
+        &workspace.syntax_set,
+        Some(&cursor_highlights),
+        None,
+    )?;
 
     if let Some(e) = error {
         presenter.print_error(&e.to_string());
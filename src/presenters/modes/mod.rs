@@ -1,12 +1,21 @@
+pub mod align;
+pub mod completion;
 pub mod confirm;
+pub mod confirm_exit;
 pub mod insert;
 pub mod jump;
 pub mod line_jump;
+pub mod macro_register;
 pub mod normal;
 pub mod open;
 pub mod paste;
 pub mod path;
+pub mod register;
 pub mod search;
 pub mod search_select;
 pub mod select;
+pub mod select_block;
 pub mod select_line;
+pub mod shell_command;
+pub mod surround;
+pub mod text_object;
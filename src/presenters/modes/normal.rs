@@ -1,4 +1,6 @@
 use crate::errors::*;
+use crate::models::application::modes::NormalMode;
+use crate::models::application::Cursors;
 use crate::presenters::{current_buffer_status_line_data, git_status_line_data};
 use crate::view::{Colors, CursorType, StatusLineData, Style, View};
 use git2::Repository;
@@ -7,6 +9,9 @@ use scribe::Workspace;
 
 pub fn display(
     workspace: &mut Workspace,
+    cursors: &Cursors,
+    mode: &NormalMode,
+    recording: Option<char>,
     view: &mut View,
     repo: &Option<Repository>,
     error: &Option<Error>,
@@ -17,7 +22,14 @@ pub fn display(
     if let Some(buf) = workspace.current_buffer.as_ref() {
         // Draw the visible set of tokens to the terminal.
         let data = buf.data();
-        presenter.print_buffer(buf, &data, &workspace.syntax_set, None, None)?;
+        let cursor_highlights = cursors.highlights(buf);
+        presenter.print_buffer(
+            buf,
+            &data,
+            &workspace.syntax_set,
+            Some(&cursor_highlights),
+            None,
+        )?;
 
         // Determine mode display color based on buffer modification status.
         let colors = if buf.modified() {
@@ -29,16 +41,30 @@ pub fn display(
         if let Some(e) = error {
             presenter.print_error(&e.to_string());
         } else {
-            // Build the status line mode and buffer title display.
-            presenter.print_status_line(&[
-                StatusLineData {
-                    content: " NORMAL ".to_string(),
-                    style: Style::Default,
-                    colors,
-                },
-                buffer_status,
-                git_status_line_data(repo, &buf.path),
-            ]);
+            // Build the status line mode and buffer title display, including
+            // the macro being recorded and pending count prefix, if any.
+            let mut status_line_data = vec![StatusLineData {
+                content: " NORMAL ".to_string(),
+                style: Style::Default,
+                colors,
+            }];
+            if let Some(register) = recording {
+                status_line_data.push(StatusLineData {
+                    content: format!(" RECORDING @{register} "),
+                    style: Style::Bold,
+                    colors: Colors::Warning,
+                });
+            }
+            if let Some(count) = mode.count {
+                status_line_data.push(StatusLineData {
+                    content: format!(" {count} "),
+                    style: Style::Bold,
+                    colors: Colors::Focused,
+                });
+            }
+            status_line_data.push(buffer_status);
+            status_line_data.push(git_status_line_data(repo, &buf.path));
+            presenter.print_status_line(&status_line_data);
         }
 
         // Restore the default cursor, suggesting non-input mode.
@@ -0,0 +1,43 @@
+use crate::errors::*;
+use crate::models::application::modes::RegisterMode;
+use crate::view::{Colors, CursorType, StatusLineData, Style, View};
+use scribe::Workspace;
+
+pub fn display(
+    workspace: &mut Workspace,
+    mode: &RegisterMode,
+    view: &mut View,
+    error: &Option<Error>,
+) -> Result<()> {
+    let mut presenter = view.build_presenter()?;
+    let buf = workspace.current_buffer.as_ref().context(BUFFER_MISSING)?;
+    let data = buf.data();
+
+    // Draw the visible set of tokens to the terminal.
+    presenter.print_buffer(buf, &data, &workspace.syntax_set, None, None)?;
+
+    if let Some(e) = error {
+        presenter.print_error(e.to_string());
+    } else {
+        presenter.print_status_line(&[
+            StatusLineData {
+                content: format!(" {mode} "),
+                style: Style::Default,
+                colors: Colors::Inverted,
+            },
+            StatusLineData {
+                content: " Use register:".to_string(),
+                style: Style::Bold,
+                colors: Colors::Focused,
+            },
+        ]);
+    }
+
+    // Restore the default cursor, suggesting non-input mode.
+    presenter.set_cursor_type(CursorType::Block);
+
+    // Render the changes to the screen.
+    presenter.present()?;
+
+    Ok(())
+}
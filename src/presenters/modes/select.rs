@@ -1,5 +1,6 @@
 use crate::errors::*;
 use crate::models::application::modes::SelectMode;
+use crate::models::application::Cursors;
 use crate::presenters::current_buffer_status_line_data;
 use crate::view::{Colors, CursorType, StatusLineData, Style, View};
 use scribe::buffer::Range;
@@ -7,6 +8,7 @@ use scribe::Workspace;
 
 pub fn display(
     workspace: &mut Workspace,
+    cursors: &Cursors,
     mode: &SelectMode,
     view: &mut View,
     error: &Option<Error>,
@@ -14,7 +16,13 @@ pub fn display(
     let mut presenter = view.build_presenter()?;
     let buffer_status = current_buffer_status_line_data(workspace);
     let buf = workspace.current_buffer.as_ref().context(BUFFER_MISSING)?;
-    let selected_range = Range::new(mode.anchor, *buf.cursor.clone());
+    let mut selected_ranges = vec![Range::new(mode.anchor, *buf.cursor.clone())];
+    selected_ranges.extend(
+        cursors
+            .for_buffer(buf)
+            .iter()
+            .map(|cursor| Range::new(cursor.anchor, cursor.position)),
+    );
     let data = buf.data();
 
     // Draw the visible set of tokens to the terminal.
@@ -22,7 +30,7 @@ pub fn display(
         buf,
         &data,
         &workspace.syntax_set,
-        Some(&[selected_range]),
+        Some(&selected_ranges),
         None,
     )?;
 
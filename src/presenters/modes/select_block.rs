@@ -0,0 +1,51 @@
+use crate::errors::*;
+use crate::models::application::modes::SelectBlockMode;
+use crate::models::application::Preferences;
+use crate::presenters::current_buffer_status_line_data;
+use crate::view::{Colors, CursorType, StatusLineData, Style, View};
+use scribe::Workspace;
+
+pub fn display(
+    workspace: &mut Workspace,
+    mode: &SelectBlockMode,
+    preferences: &Preferences,
+    view: &mut View,
+    error: &Option<Error>,
+) -> Result<()> {
+    let mut presenter = view.build_presenter()?;
+    let buffer_status = current_buffer_status_line_data(workspace);
+    let buf = workspace.current_buffer.as_ref().context(BUFFER_MISSING)?;
+    let data = buf.data();
+    let tab_width = preferences.tab_width(buf.path.as_ref());
+    let selected_ranges = mode.to_ranges(&buf.cursor, &data, tab_width);
+
+    // Draw the visible set of tokens to the terminal.
+    presenter.print_buffer(
+        buf,
+        &data,
+        &workspace.syntax_set,
+        Some(&selected_ranges),
+        None,
+    )?;
+
+    if let Some(e) = error {
+        presenter.print_error(&e.to_string());
+    } else {
+        presenter.print_status_line(&[
+            StatusLineData {
+                content: " SELECT BLOCK ".to_string(),
+                style: Style::Default,
+                colors: Colors::SelectMode,
+            },
+            buffer_status,
+        ]);
+    }
+
+    // Show a vertical bar to allow unambiguous/precise selection.
+    presenter.set_cursor_type(CursorType::Bar);
+
+    // Render the changes to the screen.
+    presenter.present()?;
+
+    Ok(())
+}
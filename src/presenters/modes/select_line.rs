@@ -1,11 +1,14 @@
 use crate::errors::*;
 use crate::models::application::modes::SelectLineMode;
+use crate::models::application::Cursors;
 use crate::presenters::current_buffer_status_line_data;
 use crate::view::{Colors, StatusLineData, Style, View};
+use scribe::buffer::LineRange;
 use scribe::Workspace;
 
 pub fn display(
     workspace: &mut Workspace,
+    cursors: &Cursors,
     mode: &SelectLineMode,
     view: &mut View,
     error: &Option<Error>,
@@ -13,7 +16,10 @@ pub fn display(
     let mut presenter = view.build_presenter()?;
     let buffer_status = current_buffer_status_line_data(workspace);
     let buf = workspace.current_buffer.as_ref().context(BUFFER_MISSING)?;
-    let selected_range = mode.to_range(&buf.cursor);
+    let mut selected_ranges = vec![mode.to_range(&buf.cursor)];
+    selected_ranges.extend(cursors.for_buffer(buf).iter().map(|cursor| {
+        LineRange::new(cursor.anchor.line, cursor.position.line).to_inclusive_range()
+    }));
     let data = buf.data();
 
     // Draw the visible set of tokens to the terminal.
@@ -21,7 +27,7 @@ pub fn display(
         buf,
         &data,
         &workspace.syntax_set,
-        Some(&[selected_range]),
+        Some(&selected_ranges),
         None,
     )?;
 
@@ -0,0 +1,72 @@
+use crate::errors::*;
+use crate::models::application::modes::{SurroundAction, SurroundMode};
+use crate::view::{Colors, CursorType, StatusLineData, Style, View};
+use scribe::buffer::Position;
+use scribe::Workspace;
+
+pub fn display(
+    workspace: &mut Workspace,
+    mode: &SurroundMode,
+    view: &mut View,
+    error: &Option<Error>,
+) -> Result<()> {
+    let mut presenter = view.build_presenter()?;
+    let buf = workspace.current_buffer.as_ref().context(BUFFER_MISSING)?;
+    let data = buf.data();
+
+    // Draw the visible set of tokens to the terminal.
+    presenter.print_buffer(buf, &data, &workspace.syntax_set, None, None)?;
+
+    if let Some(e) = error {
+        presenter.print_error(e.to_string());
+    } else if let Some(ref tag) = mode.tag {
+        // Draw the status line as an input prompt.
+        let input_prompt = format!("Surround with tag: <{tag}");
+        let input_prompt_len = input_prompt.chars().count();
+        presenter.print_status_line(&[StatusLineData {
+            content: input_prompt,
+            style: Style::Default,
+            colors: Colors::Default,
+        }]);
+
+        // Move the cursor to the end of the tag input.
+        let cursor_line = presenter.height() - 1;
+        presenter.set_cursor(Some(Position {
+            line: cursor_line,
+            offset: input_prompt_len,
+        }));
+    } else {
+        let prompt = match (mode.action, mode.range.is_some()) {
+            (SurroundAction::Add, _) | (SurroundAction::Change, true) => {
+                " Surround with: ( [ { < \" ' ` or t(ag)"
+            }
+            (SurroundAction::Change, false) => " Change surrounding: ( [ { < \" ' `",
+            (SurroundAction::Delete, _) => " Delete surrounding: ( [ { < \" ' `",
+        };
+
+        presenter.print_status_line(&[
+            StatusLineData {
+                content: format!(" {mode} "),
+                style: Style::Default,
+                colors: Colors::Inverted,
+            },
+            StatusLineData {
+                content: prompt.to_string(),
+                style: Style::Bold,
+                colors: Colors::Focused,
+            },
+        ]);
+    }
+
+    // Show a blinking, vertical bar while typing a tag.
+    presenter.set_cursor_type(if mode.tag.is_some() {
+        CursorType::BlinkingBar
+    } else {
+        CursorType::Block
+    });
+
+    // Render the changes to the screen.
+    presenter.present()?;
+
+    Ok(())
+}
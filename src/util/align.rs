@@ -0,0 +1,141 @@
+use crate::errors::*;
+use crate::util::column::display_column;
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Builds a pattern matching the delimiter, which is taken literally
+/// unless it's wrapped in slashes (e.g. `/:|=/`) as a regex.
+pub fn delimiter(input: &str) -> Result<Regex> {
+    let pattern = match input.strip_prefix('/').and_then(|i| i.strip_suffix('/')) {
+        Some(pattern) if !pattern.is_empty() => pattern.to_string(),
+        _ => regex::escape(input),
+    };
+
+    Regex::new(&pattern).context("Invalid delimiter pattern")
+}
+
+/// Pads each line so that every occurrence of the delimiter lines up with the
+/// corresponding occurrence on the other lines, one column at a time. Text
+/// before each delimiter is trimmed first, keeping a single space before it
+/// if the longest text had one, so that realigning lines doesn't widen them.
+/// Leading whitespace before the first delimiter (e.g. an indented table) is
+/// kept as is.
+pub fn align(lines: &[&str], delimiter: &Regex, tab_width: usize) -> Vec<String> {
+    // Split each line into the text before each delimiter,
+    // the delimiters themselves, and whatever remains.
+    let mut cells: Vec<Vec<(&str, &str)>> = Vec::new();
+    let mut remainders = Vec::new();
+    for line in lines {
+        let mut start = 0;
+        let mut line_cells = Vec::new();
+        for found in delimiter.find_iter(line).filter(|m| !m.as_str().is_empty()) {
+            line_cells.push((&line[start..found.start()], found.as_str()));
+            start = found.end();
+        }
+        cells.push(line_cells);
+        remainders.push(&line[start..]);
+    }
+
+    let mut aligned = vec![String::new(); lines.len()];
+    let columns = cells.iter().map(Vec::len).max().unwrap_or_default();
+    for column in 0..columns {
+        // Find the furthest column a delimiter would start at,
+        // and whether it was preceded by a space on those lines.
+        let mut width = 0;
+        let mut spaced = false;
+        for (line, line_cells) in aligned.iter_mut().zip(cells.iter()) {
+            if let Some((text, _)) = line_cells.get(column) {
+                let kept = if column == 0 && text.trim().is_empty() {
+                    text
+                } else {
+                    text.trim_end()
+                };
+                line.push_str(kept);
+                let line_width = display_width(line, tab_width);
+                let line_spaced = kept.len() < text.len();
+                if line_width > width {
+                    width = line_width;
+                    spaced = line_spaced;
+                } else if line_width == width {
+                    spaced |= line_spaced;
+                }
+            }
+        }
+
+        for (line, line_cells) in aligned.iter_mut().zip(cells.iter()) {
+            if let Some((_, found)) = line_cells.get(column) {
+                let padding = width - display_width(line, tab_width) + usize::from(spaced);
+                line.push_str(&" ".repeat(padding));
+                line.push_str(found);
+            }
+        }
+    }
+
+    for (line, remainder) in aligned.iter_mut().zip(remainders) {
+        line.push_str(remainder);
+    }
+
+    aligned
+}
+
+fn display_width(line: &str, tab_width: usize) -> usize {
+    display_column(line, line.graphemes(true).count(), tab_width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{align, delimiter};
+
+    #[test]
+    fn align_pads_lines_so_that_delimiters_line_up() {
+        let lines = ["  amp = 1;", "  editor = 2;", "  // no delimiter", "  x=3;"];
+
+        assert_eq!(
+            align(&lines, &delimiter("=").unwrap(), 2),
+            vec![
+                "  amp    = 1;",
+                "  editor = 2;",
+                "  // no delimiter",
+                "  x      =3;"
+            ]
+        );
+    }
+
+    #[test]
+    fn align_lines_up_every_occurrence_counting_tabs_and_graphemes() {
+        let lines = ["a|bb|c", "\tä|b|c|d", "aaaaaaaaa|b"];
+
+        assert_eq!(
+            align(&lines, &delimiter("|").unwrap(), 4),
+            vec!["a        |bb|c", "\tä    |b |c|d", "aaaaaaaaa|b"]
+        );
+    }
+
+    #[test]
+    fn align_keeps_the_indentation_before_the_first_delimiter() {
+        let lines = ["    | a | b |", "    | cc | d |"];
+
+        assert_eq!(
+            align(&lines, &delimiter("|").unwrap(), 2),
+            vec!["    | a  | b |", "    | cc | d |"]
+        );
+    }
+
+    #[test]
+    fn align_is_idempotent() {
+        let lines = ["name:   amp", "version: 1"];
+        let aligned = align(&lines, &delimiter(":").unwrap(), 2);
+        let realigned: Vec<&str> = aligned.iter().map(String::as_str).collect();
+
+        assert_eq!(aligned, vec!["name   :   amp", "version: 1"]);
+        assert_eq!(align(&realigned, &delimiter(":").unwrap(), 2), aligned);
+    }
+
+    #[test]
+    fn delimiter_treats_input_wrapped_in_slashes_as_a_regex() {
+        assert!(delimiter("/=>?/").unwrap().is_match("a => b"));
+        assert!(delimiter("=>?").unwrap().is_match("=>?"));
+        assert!(!delimiter("=>?").unwrap().is_match("=>"));
+        assert!(delimiter("/(/").is_err());
+    }
+}
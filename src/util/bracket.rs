@@ -0,0 +1,347 @@
+use crate::errors::*;
+use scribe::buffer::{Lexeme, Position};
+use scribe::util::LineIterator;
+use syntect::easy::ScopeRangeIterator;
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxReference, SyntaxSet};
+use unicode_segmentation::UnicodeSegmentation;
+
+const PAIRS: [(&str, &str); 3] = [("(", ")"), ("[", "]"), ("{", "}")];
+
+/// Whether the grapheme is one of the brackets we can match.
+pub fn is_bracket(grapheme: &str) -> bool {
+    PAIRS
+        .iter()
+        .any(|&(open, close)| grapheme == open || grapheme == close)
+}
+
+/// Whether the grapheme is a closing bracket.
+pub fn is_closing_bracket(grapheme: &str) -> bool {
+    PAIRS.iter().any(|&(_, close)| grapheme == close)
+}
+
+/// Whether the grapheme is an opening bracket.
+pub fn is_opening_bracket(grapheme: &str) -> bool {
+    PAIRS.iter().any(|&(open, _)| grapheme == open)
+}
+
+/// The parser's state at the start of a line, from which the rest of
+/// the buffer can be parsed without revisiting the lines before it.
+pub struct LineState {
+    pub line: usize,
+    pub parser: ParseState,
+    pub scopes: ScopeStack,
+}
+
+impl LineState {
+    /// The state at the start of a buffer using the specified syntax.
+    pub fn new(syntax: &SyntaxReference) -> LineState {
+        LineState {
+            line: 0,
+            parser: ParseState::new(syntax),
+            scopes: ScopeStack::new(),
+        }
+    }
+}
+
+/// Parses the buffer's lines into scoped lexemes, from the line
+/// state's line up to (but not including) the specified end line.
+pub fn lexemes<'a>(
+    data: &'a str,
+    state: LineState,
+    end_line: usize,
+    syntax_set: &SyntaxSet,
+) -> Result<Vec<Lexeme<'a>>> {
+    let LineState {
+        line: start_line,
+        mut parser,
+        mut scopes,
+    } = state;
+    let mut lexemes = Vec::new();
+
+    let lines = LineIterator::new(data)
+        .skip(start_line)
+        .take(end_line.saturating_sub(start_line));
+    for (line_number, line) in lines {
+        let events = parser
+            .parse_line(line, syntax_set)
+            .context(BUFFER_PARSE_FAILED)?;
+        let mut offset = 0;
+
+        for (range, event) in ScopeRangeIterator::new(&events, line) {
+            scopes.apply(event).context(BUFFER_PARSE_FAILED)?;
+
+            // Trailing newlines aren't part of any lexeme.
+            let value = line[range].trim_end_matches('\n');
+            if value.is_empty() {
+                continue;
+            }
+
+            lexemes.push(Lexeme {
+                value,
+                scope: scopes.clone(),
+                position: Position {
+                    line: line_number,
+                    offset,
+                },
+            });
+
+            // Lexeme positions are grapheme-based, not byte-based.
+            offset += value.graphemes(true).count();
+        }
+    }
+
+    Ok(lexemes)
+}
+
+/// Finds the bracket paired with the one at the specified position, if any.
+/// Brackets in string and comment scopes are ignored, as are closing
+/// brackets that don't match the innermost open bracket.
+pub fn matching_bracket(lexemes: &[Lexeme], position: &Position) -> Result<Option<Position>> {
+    let ignored_scopes = ignored_scopes()?;
+    let mut open_brackets: Vec<(Position, &str)> = Vec::new();
+
+    // The depth of the open bracket at the specified position, once found.
+    let mut target_depth = None;
+
+    for lexeme in lexemes {
+        let ignored = has_scope(&lexeme.scope, &ignored_scopes);
+
+        for (offset, grapheme) in lexeme.value.graphemes(true).enumerate() {
+            let current = Position {
+                line: lexeme.position.line,
+                offset: lexeme.position.offset + offset,
+            };
+
+            // There's nothing to match if the position isn't a bracket.
+            if target_depth.is_none() && current > *position {
+                return Ok(None);
+            }
+            if ignored {
+                continue;
+            }
+
+            if let Some(&(open, _)) = PAIRS.iter().find(|&&(open, _)| grapheme == open) {
+                open_brackets.push((current, open));
+
+                if current == *position {
+                    target_depth = Some(open_brackets.len());
+                }
+            } else if let Some(&(open, _)) = PAIRS.iter().find(|&&(_, close)| grapheme == close) {
+                match open_brackets.last() {
+                    Some(&(open_position, last_open)) if last_open == open => {
+                        if current == *position {
+                            return Ok(Some(open_position));
+                        } else if target_depth == Some(open_brackets.len()) {
+                            return Ok(Some(current));
+                        }
+
+                        open_brackets.pop();
+                    }
+                    _ => {
+                        if current == *position {
+                            return Ok(None);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Whether the position is within a string or comment, where brackets are
+/// left alone. The position is scoped by the lexeme at it, unless it opens
+/// a string or comment, or by the one before it at the end of a line,
+/// unless that closes a string.
+pub fn in_string_or_comment(lexemes: &[Lexeme], position: &Position) -> Result<bool> {
+    let ignored_scopes = ignored_scopes()?;
+    let opening_delimiters = [
+        scope("punctuation.definition.string.begin")?,
+        scope("punctuation.definition.comment")?,
+    ];
+    let closing_delimiters = [scope("punctuation.definition.string.end")?];
+
+    let lexeme_at_position = lexemes.iter().find(|lexeme| {
+        lexeme.position.line == position.line
+            && lexeme.position.offset <= position.offset
+            && position.offset < lexeme.position.offset + lexeme.value.graphemes(true).count()
+    });
+    if let Some(lexeme) = lexeme_at_position {
+        let opens = lexeme.position == *position && has_scope(&lexeme.scope, &opening_delimiters);
+
+        return Ok(!opens && has_scope(&lexeme.scope, &ignored_scopes));
+    }
+
+    let preceding_lexeme = lexemes
+        .iter()
+        .take_while(|lexeme| lexeme.position < *position)
+        .last();
+
+    Ok(preceding_lexeme.is_some_and(|lexeme| {
+        !has_scope(&lexeme.scope, &closing_delimiters) && has_scope(&lexeme.scope, &ignored_scopes)
+    }))
+}
+
+fn ignored_scopes() -> Result<[Scope; 2]> {
+    Ok([scope("string")?, scope("comment")?])
+}
+
+fn scope(name: &str) -> Result<Scope> {
+    Scope::new(name).map_err(|e| anyhow!(e.to_string()))
+}
+
+fn has_scope(stack: &ScopeStack, scopes: &[Scope]) -> bool {
+    stack
+        .as_slice()
+        .iter()
+        .any(|scope| scopes.iter().any(|other| other.is_prefix_of(*scope)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LineState;
+    use scribe::buffer::{Lexeme, Position};
+    use scribe::{Buffer, Workspace};
+    use std::path::Path;
+
+    fn with_lexemes<T>(content: &str, f: impl FnOnce(&[Lexeme]) -> T) -> T {
+        let mut workspace = Workspace::new(Path::new(".")).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.path = Some(Path::new("test.rs").to_path_buf());
+        buffer.insert(content);
+        workspace.add_buffer(buffer);
+        let buffer = workspace.current_buffer.as_ref().unwrap();
+        let data = buffer.data();
+        let state = LineState::new(buffer.syntax_definition.as_ref().unwrap());
+        let lexemes = super::lexemes(&data, state, usize::MAX, &workspace.syntax_set).unwrap();
+
+        f(&lexemes)
+    }
+
+    fn matching_bracket(content: &str, position: Position) -> Option<Position> {
+        with_lexemes(content, |lexemes| {
+            super::matching_bracket(lexemes, &position).unwrap()
+        })
+    }
+
+    fn in_string_or_comment(content: &str, line: usize, offset: usize) -> bool {
+        with_lexemes(content, |lexemes| {
+            super::in_string_or_comment(lexemes, &Position { line, offset }).unwrap()
+        })
+    }
+
+    #[test]
+    fn matching_bracket_finds_nested_pairs_in_both_directions() {
+        let content = "fn amp() {\n    vec![(1, 2)];\n}\n";
+
+        assert_eq!(
+            matching_bracket(content, Position { line: 0, offset: 9 }),
+            Some(Position { line: 2, offset: 0 })
+        );
+        assert_eq!(
+            matching_bracket(content, Position { line: 2, offset: 0 }),
+            Some(Position { line: 0, offset: 9 })
+        );
+        assert_eq!(
+            matching_bracket(
+                content,
+                Position {
+                    line: 1,
+                    offset: 15
+                }
+            ),
+            Some(Position { line: 1, offset: 8 })
+        );
+    }
+
+    #[test]
+    fn matching_bracket_ignores_brackets_in_strings_and_comments() {
+        let content = "amp(\")\", // )\n    1)";
+
+        assert_eq!(
+            matching_bracket(content, Position { line: 0, offset: 3 }),
+            Some(Position { line: 1, offset: 5 })
+        );
+        assert_eq!(
+            matching_bracket(content, Position { line: 0, offset: 5 }),
+            None
+        );
+    }
+
+    #[test]
+    fn matching_bracket_returns_none_for_unbalanced_brackets() {
+        assert_eq!(
+            matching_bracket("amp(", Position { line: 0, offset: 3 }),
+            None
+        );
+        assert_eq!(
+            matching_bracket("amp]", Position { line: 0, offset: 3 }),
+            None
+        );
+        assert_eq!(
+            matching_bracket("amp", Position { line: 0, offset: 1 }),
+            None
+        );
+    }
+
+    #[test]
+    fn lexemes_resume_from_a_line_state() {
+        let mut workspace = Workspace::new(Path::new(".")).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.path = Some(Path::new("test.rs").to_path_buf());
+        buffer.insert("/* amp\n(editor */\n(a)\n");
+        workspace.add_buffer(buffer);
+        let buffer = workspace.current_buffer.as_ref().unwrap();
+        let data = buffer.data();
+
+        // Parse the first line separately, and resume from the second.
+        let mut state = LineState::new(buffer.syntax_definition.as_ref().unwrap());
+        let events = state
+            .parser
+            .parse_line("/* amp\n", &workspace.syntax_set)
+            .unwrap();
+        for (_, event) in events {
+            state.scopes.apply(&event).unwrap();
+        }
+        state.line = 1;
+        let lexemes = super::lexemes(&data, state, 3, &workspace.syntax_set).unwrap();
+
+        assert_eq!(lexemes[0].position, Position { line: 1, offset: 0 });
+        // The comment's scope carries over, so its bracket is ignored.
+        assert_eq!(
+            super::matching_bracket(&lexemes, &Position { line: 1, offset: 0 }).unwrap(),
+            None
+        );
+        assert_eq!(
+            super::matching_bracket(&lexemes, &Position { line: 2, offset: 0 }).unwrap(),
+            Some(Position { line: 2, offset: 2 })
+        );
+    }
+
+    #[test]
+    fn in_string_or_comment_uses_the_scope_at_the_position() {
+        let content = "amp(\"editor\", x); // (\n\"open";
+
+        // Before the opening quote and after the closing one.
+        assert!(!in_string_or_comment(content, 0, 4));
+        assert!(!in_string_or_comment(content, 0, 12));
+
+        // Within the string, including just before its closing quote.
+        assert!(in_string_or_comment(content, 0, 5));
+        assert!(in_string_or_comment(content, 0, 11));
+
+        // Before, within, and at the end of a comment.
+        assert!(!in_string_or_comment(content, 0, 17));
+        assert!(in_string_or_comment(content, 0, 20));
+        assert!(in_string_or_comment(content, 0, 22));
+
+        // At the end of an unterminated string.
+        assert!(in_string_or_comment(content, 1, 5));
+    }
+
+    #[test]
+    fn in_string_or_comment_is_false_at_the_end_of_a_closed_string() {
+        assert!(!in_string_or_comment("amp(\"editor\"", 0, 12));
+    }
+}
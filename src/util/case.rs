@@ -0,0 +1,196 @@
+/// A letter case or identifier naming convention that text can be converted to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Case {
+    Upper,
+    Lower,
+    Title,
+    Snake,
+    Camel,
+    Pascal,
+    Kebab,
+}
+
+/// Converts text to the specified case.
+///
+/// Upper and lower case apply to the text as a whole, and title case to each
+/// of its words. Naming conventions are applied to each identifier in the
+/// text (a run of letters, digits, underscores and hyphens), splitting it into
+/// words at separators and changes in case; surrounding separators are kept.
+pub fn convert(text: &str, case: Case) -> String {
+    match case {
+        Case::Upper => text.to_uppercase(),
+        Case::Lower => text.to_lowercase(),
+        Case::Title => map_runs(text, char::is_alphanumeric, capitalize),
+        _ => map_runs(text, is_identifier_char, |identifier| {
+            convert_identifier(identifier, case)
+        }),
+    }
+}
+
+// Replaces each maximal run of characters matching the predicate,
+// leaving everything in between untouched.
+fn map_runs<P, F>(text: &str, predicate: P, convert: F) -> String
+where
+    P: Fn(char) -> bool,
+    F: Fn(&str) -> String,
+{
+    let mut result = String::with_capacity(text.len());
+    let mut run_start = None;
+
+    for (index, character) in text.char_indices() {
+        match (predicate(character), run_start) {
+            (true, None) => run_start = Some(index),
+            (false, Some(start)) => {
+                result.push_str(&convert(&text[start..index]));
+                result.push(character);
+                run_start = None;
+            }
+            (false, None) => result.push(character),
+            (true, Some(_)) => (),
+        }
+    }
+    if let Some(start) = run_start {
+        result.push_str(&convert(&text[start..]));
+    }
+
+    result
+}
+
+fn convert_identifier(identifier: &str, case: Case) -> String {
+    // Keep leading and trailing separators (e.g. "_private" or "__init__").
+    let core = identifier.trim_matches(is_separator);
+    if core.is_empty() {
+        return identifier.to_string();
+    }
+    let prefix_length = identifier.len() - identifier.trim_start_matches(is_separator).len();
+    let (prefix, suffix) = (
+        &identifier[..prefix_length],
+        &identifier[prefix_length + core.len()..],
+    );
+
+    let words = split_words(core);
+    let converted = match case {
+        Case::Snake => join_words(&words, "_", str::to_lowercase),
+        Case::Kebab => join_words(&words, "-", str::to_lowercase),
+        Case::Pascal => join_words(&words, "", capitalize),
+        Case::Camel => {
+            let (first, rest) = words.split_first().unwrap();
+            first.to_lowercase() + &join_words(rest, "", capitalize)
+        }
+        _ => core.to_string(),
+    };
+
+    format!("{prefix}{converted}{suffix}")
+}
+
+// Splits an identifier into words at separators, lowercase to uppercase
+// transitions (fooBar), and before the last capital of an acronym (HTTPServer).
+fn split_words(identifier: &str) -> Vec<&str> {
+    let characters: Vec<(usize, char)> = identifier.char_indices().collect();
+    let mut words = Vec::new();
+    let mut word_start = None;
+
+    for (index, &(offset, character)) in characters.iter().enumerate() {
+        if is_separator(character) {
+            if let Some(start) = word_start.take() {
+                words.push(&identifier[start..offset]);
+            }
+            continue;
+        }
+
+        if let Some(start) = word_start {
+            let previous = characters[index - 1].1;
+            let next = characters.get(index + 1).map(|&(_, next)| next);
+            let boundary = character.is_uppercase()
+                && (previous.is_lowercase()
+                    || previous.is_numeric()
+                    || (previous.is_uppercase() && next.is_some_and(char::is_lowercase)));
+
+            if boundary {
+                words.push(&identifier[start..offset]);
+                word_start = Some(offset);
+            }
+        } else {
+            word_start = Some(offset);
+        }
+    }
+    if let Some(start) = word_start {
+        words.push(&identifier[start..]);
+    }
+
+    words
+}
+
+fn join_words<F>(words: &[&str], separator: &str, convert: F) -> String
+where
+    F: Fn(&str) -> String,
+{
+    words
+        .iter()
+        .map(|word| convert(word))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut characters = word.chars();
+    match characters.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(characters.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+pub fn is_identifier_char(character: char) -> bool {
+    character.is_alphanumeric() || is_separator(character)
+}
+
+fn is_separator(character: char) -> bool {
+    character == '_' || character == '-'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, Case};
+
+    #[test]
+    fn convert_changes_the_case_of_all_text() {
+        assert_eq!(convert("amp editor_9", Case::Upper), "AMP EDITOR_9");
+        assert_eq!(convert("AMP Editor", Case::Lower), "amp editor");
+    }
+
+    #[test]
+    fn convert_capitalizes_each_word_in_title_case() {
+        assert_eq!(
+            convert("the AMP text-editor", Case::Title),
+            "The Amp Text-Editor"
+        );
+    }
+
+    #[test]
+    fn convert_splits_identifiers_at_separators_and_case_changes() {
+        let identifier = "parseHTTPResponse_body-v2";
+
+        assert_eq!(
+            convert(identifier, Case::Snake),
+            "parse_http_response_body_v2"
+        );
+        assert_eq!(
+            convert(identifier, Case::Kebab),
+            "parse-http-response-body-v2"
+        );
+        assert_eq!(convert(identifier, Case::Camel), "parseHttpResponseBodyV2");
+        assert_eq!(convert(identifier, Case::Pascal), "ParseHttpResponseBodyV2");
+    }
+
+    #[test]
+    fn convert_preserves_surrounding_text_and_separators() {
+        assert_eq!(
+            convert("self.__fooBar__ = bazQux();", Case::Snake),
+            "self.__foo_bar__ = baz_qux();"
+        );
+        assert_eq!(convert("a - b", Case::Camel), "a - b");
+    }
+}
@@ -0,0 +1,53 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Maps a grapheme offset in a line to its on-screen column, expanding tabs.
+pub fn display_column(line: &str, offset: usize, tab_width: usize) -> usize {
+    line.graphemes(true)
+        .take(offset)
+        .fold(0, |column, grapheme| {
+            next_column(column, grapheme, tab_width)
+        })
+}
+
+/// Maps an on-screen column to the offset of the grapheme displayed there,
+/// including a tab that spans it, or the end of the line if it's too short.
+pub fn offset_at_column(line: &str, column: usize, tab_width: usize) -> usize {
+    let mut current_column = 0;
+
+    for (offset, grapheme) in line.graphemes(true).enumerate() {
+        current_column = next_column(current_column, grapheme, tab_width);
+        if current_column > column {
+            return offset;
+        }
+    }
+
+    line.graphemes(true).count()
+}
+
+fn next_column(column: usize, grapheme: &str, tab_width: usize) -> usize {
+    if grapheme == "\t" {
+        (column / tab_width + 1) * tab_width
+    } else {
+        column + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{display_column, offset_at_column};
+
+    #[test]
+    fn display_column_expands_tabs_to_the_next_tab_stop() {
+        assert_eq!(display_column("a\tb", 1, 4), 1);
+        assert_eq!(display_column("a\tb", 2, 4), 4);
+        assert_eq!(display_column("ab\t\tc", 4, 4), 8);
+    }
+
+    #[test]
+    fn offset_at_column_includes_tabs_spanning_the_column() {
+        assert_eq!(offset_at_column("a\tb", 0, 4), 0);
+        assert_eq!(offset_at_column("a\tb", 2, 4), 1);
+        assert_eq!(offset_at_column("a\tb", 4, 4), 2);
+        assert_eq!(offset_at_column("a\tb", 10, 4), 3);
+    }
+}
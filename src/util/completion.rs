@@ -0,0 +1,121 @@
+use scribe::buffer::Position;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The partial word (i.e. letters, digits and underscores) ending at the cursor.
+pub fn prefix(data: &str, cursor: &Position) -> String {
+    let line = data.lines().nth(cursor.line).unwrap_or_default();
+    let preceding: String = line.graphemes(true).take(cursor.offset).collect();
+    let start = preceding
+        .char_indices()
+        .rev()
+        .take_while(|&(_, c)| is_word_character(c))
+        .last()
+        .map_or(preceding.len(), |(index, _)| index);
+
+    preceding[start..].to_string()
+}
+
+/// Words that extend the prefix, ranked by their proximity to the cursor in
+/// the current buffer and then how often they appear, followed by those
+/// found only in the other buffers, ranked by how often they appear there.
+pub fn candidates(
+    prefix: &str,
+    data: &str,
+    cursor: &Position,
+    others: &HashMap<String, usize>,
+) -> Vec<String> {
+    let mut nearby: HashMap<&str, (usize, usize)> = HashMap::new();
+    for (line, content) in data.lines().enumerate() {
+        for word in words(content, prefix) {
+            let distance = line.abs_diff(cursor.line);
+            let (closest, count) = nearby.entry(word).or_insert((distance, 0));
+            *closest = distance.min(*closest);
+            *count += 1;
+        }
+    }
+
+    let mut elsewhere: Vec<_> = others
+        .iter()
+        .filter(|&(word, _)| extends(word, prefix) && !nearby.contains_key(word.as_str()))
+        .collect();
+    let mut nearby: Vec<_> = nearby.into_iter().collect();
+    nearby.sort_by_key(|&(word, (distance, count))| (distance, Reverse(count), word));
+    elsewhere.sort_by_key(|&(word, count)| (Reverse(count), word));
+
+    nearby
+        .into_iter()
+        .map(|(word, _)| word)
+        .chain(elsewhere.into_iter().map(|(word, _)| word.as_str()))
+        .map(String::from)
+        .collect()
+}
+
+/// Counts the occurrences of each word in the content, so that those in
+/// buffers other than the current one can be collected once and reused.
+pub fn word_counts(content: &str) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for word in words(content, "") {
+        *counts.entry(word.to_string()).or_default() += 1;
+    }
+
+    counts
+}
+
+// The words in the content that start with (and are longer than) the prefix.
+fn words<'a>(content: &'a str, prefix: &'a str) -> impl Iterator<Item = &'a str> {
+    content
+        .split(|c| !is_word_character(c))
+        .filter(move |word| extends(word, prefix))
+}
+
+fn extends(word: &str, prefix: &str) -> bool {
+    word.len() > prefix.len()
+        && word.starts_with(prefix)
+        && !word.starts_with(|c: char| c.is_numeric())
+}
+
+fn is_word_character(character: char) -> bool {
+    character.is_alphanumeric() || character == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use scribe::buffer::Position;
+
+    #[test]
+    fn prefix_returns_the_partial_word_before_the_cursor() {
+        let data = "let editor = amp::edi";
+        let position = |offset| Position { line: 0, offset };
+
+        assert_eq!(super::prefix(data, &position(21)), "edi");
+        assert_eq!(super::prefix(data, &position(7)), "edi");
+        assert_eq!(super::prefix(data, &position(11)), "");
+    }
+
+    #[test]
+    fn candidates_rank_nearby_and_frequent_words_first() {
+        let data = "amp_view\namp_buffer amp_buffer\n\namp\namp_editor amp_view\n";
+        let others = super::word_counts("amp_workspace amp_tests amp_workspace amp_view");
+
+        assert_eq!(
+            super::candidates("amp", data, &Position { line: 3, offset: 3 }, &others),
+            vec![
+                "amp_view",
+                "amp_editor",
+                "amp_buffer",
+                "amp_workspace",
+                "amp_tests"
+            ]
+        );
+    }
+    #[test]
+    fn word_counts_skip_numbers() {
+        let counts = super::word_counts("amp_view, amp_view(2, amp)");
+
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts["amp_view"], 2);
+        assert_eq!(counts["amp"], 1);
+    }
+}
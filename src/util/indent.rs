@@ -0,0 +1,164 @@
+use crate::errors::*;
+use crate::util::bracket;
+use regex::Regex;
+use scribe::buffer::Lexeme;
+use std::str::FromStr;
+use syntect::highlighting::ScopeSelectors;
+use syntect::parsing::ScopeStack;
+use unicode_segmentation::UnicodeSegmentation;
+
+// Scopes of the tokens that open a block without a bracket (e.g. the
+// colon ending a Python statement, or the "do" starting a Ruby block).
+const BLOCK_START_SCOPES: &str = "punctuation.section.block - punctuation.section.block.end, \
+    punctuation.section.class.begin, punctuation.section.function.begin, \
+    keyword.control.start-block";
+const IGNORED_SCOPES: &str = "string, comment";
+
+/// Whether lines following the specified one should be indented a further
+/// level: when it leaves a bracket open, ends with a token whose scope
+/// opens a block, or matches the buffer type's increase_indent_pattern.
+///
+/// Only the line's content is considered, allowing it to be truncated at
+/// the cursor. Without lexemes (e.g. for plain text), only brackets count.
+pub fn increases_indent(
+    lexemes: Option<&[Lexeme]>,
+    line: usize,
+    content: &str,
+    pattern: Option<&Regex>,
+) -> Result<bool> {
+    if pattern.is_some_and(|pattern| pattern.is_match(content)) {
+        return Ok(true);
+    }
+
+    let block_start_scopes = scope_selectors(BLOCK_START_SCOPES)?;
+    let ignored_scopes = scope_selectors(IGNORED_SCOPES)?;
+    let mut open_brackets: usize = 0;
+    let mut last_scope = None;
+
+    for (value, scope) in line_lexemes(lexemes, line, content) {
+        if ignored_scopes.does_match(scope.as_slice()).is_some() {
+            continue;
+        }
+
+        for grapheme in value.graphemes(true) {
+            if bracket::is_opening_bracket(grapheme) {
+                open_brackets += 1;
+            } else if bracket::is_closing_bracket(grapheme) {
+                // Brackets opened on previous lines can be closed here.
+                open_brackets = open_brackets.saturating_sub(1);
+            }
+        }
+        if !value.trim().is_empty() {
+            last_scope = Some(scope);
+        }
+    }
+
+    Ok(open_brackets > 0
+        || last_scope
+            .is_some_and(|scope| block_start_scopes.does_match(scope.as_slice()).is_some()))
+}
+
+/// Whether the line closes a block, and should be outdented as a result:
+/// when it's a lone closing bracket, or matches the buffer type's
+/// decrease_indent_pattern.
+pub fn decreases_indent(line: &str, pattern: Option<&Regex>) -> bool {
+    let mut graphemes = line.trim().graphemes(true);
+    let closing_bracket = matches!(
+        (graphemes.next(), graphemes.next()),
+        (Some(grapheme), None) if bracket::is_closing_bracket(grapheme)
+    );
+
+    closing_bracket || pattern.is_some_and(|pattern| pattern.is_match(line))
+}
+
+// The values and scopes of the lexemes on a line, up to the end of its content.
+fn line_lexemes(
+    lexemes: Option<&[Lexeme]>,
+    line: usize,
+    content: &str,
+) -> Vec<(String, ScopeStack)> {
+    let Some(lexemes) = lexemes else {
+        return vec![(content.to_string(), ScopeStack::new())];
+    };
+    let length = content.graphemes(true).count();
+
+    lexemes
+        .iter()
+        .filter(|lexeme| lexeme.position.line == line && lexeme.position.offset < length)
+        .map(|lexeme| {
+            let value = lexeme
+                .value
+                .graphemes(true)
+                .take(length - lexeme.position.offset)
+                .collect();
+
+            (value, lexeme.scope.clone())
+        })
+        .collect()
+}
+
+fn scope_selectors(selectors: &str) -> Result<ScopeSelectors> {
+    ScopeSelectors::from_str(selectors).map_err(|e| anyhow!(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::bracket::{self, LineState};
+    use regex::Regex;
+    use scribe::{Buffer, Workspace};
+    use std::path::Path;
+
+    fn increases_indent(path: &str, content: &str) -> bool {
+        let mut workspace = Workspace::new(Path::new(".")).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.path = Some(Path::new(path).to_path_buf());
+        buffer.insert(content);
+        workspace.add_buffer(buffer);
+        let buffer = workspace.current_buffer.as_ref().unwrap();
+        let state = LineState::new(buffer.syntax_definition.as_ref().unwrap());
+        let line = content.lines().count() - 1;
+        let lexemes = bracket::lexemes(content, state, line + 1, &workspace.syntax_set).unwrap();
+
+        super::increases_indent(Some(&lexemes), line, content.lines().last().unwrap(), None)
+            .unwrap()
+    }
+
+    #[test]
+    fn increases_indent_after_unclosed_brackets() {
+        assert!(increases_indent("test.rs", "fn amp() {"));
+        assert!(increases_indent("test.rs", "fn amp() {\n    } else {"));
+        assert!(increases_indent("test.rs", "amp(editor,"));
+        assert!(!increases_indent("test.rs", "amp(editor);"));
+        assert!(!increases_indent("test.rs", "fn amp() {\n    })"));
+    }
+
+    #[test]
+    fn increases_indent_ignores_brackets_in_strings_and_comments() {
+        assert!(!increases_indent("test.rs", "let amp = \"{\";"));
+        assert!(!increases_indent("test.rs", "amp(); // {"));
+    }
+
+    #[test]
+    fn increases_indent_after_block_start_scopes() {
+        assert!(increases_indent("test.py", "def amp(editor):"));
+        assert!(increases_indent("test.py", "if amp:  # editor"));
+        assert!(!increases_indent("test.py", "amp = {'a': 1}"));
+        assert!(increases_indent("test.rb", "[1].each do"));
+    }
+
+    #[test]
+    fn increases_indent_only_considers_brackets_without_lexemes() {
+        assert!(super::increases_indent(None, 0, "amp {", None).unwrap());
+        assert!(!super::increases_indent(None, 0, "amp:", None).unwrap());
+    }
+
+    #[test]
+    fn decreases_indent_for_lone_closing_brackets_and_matching_lines() {
+        let pattern = Regex::new(r"^\s*end\b").unwrap();
+
+        assert!(super::decreases_indent("    }", None));
+        assert!(!super::decreases_indent("    });", None));
+        assert!(!super::decreases_indent("    end", None));
+        assert!(super::decreases_indent("    end", Some(&pattern)));
+    }
+}
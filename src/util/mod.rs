@@ -1,13 +1,27 @@
 pub use self::selectable_vec::SelectableVec;
 
+pub mod align;
+pub mod bracket;
+pub mod case;
+pub mod column;
+pub mod completion;
+pub mod indent;
 pub mod movement_lexer;
+pub mod multi_cursor;
+pub mod number;
 pub mod reflow;
 mod selectable_vec;
+pub mod snippet;
+pub mod text_object;
 pub mod token;
+pub mod undo_history;
 
+use crate::commands;
 use crate::errors::*;
+use crate::models::application::Mode;
 use crate::models::Application;
 use scribe::buffer::{Buffer, LineRange, Position, Range};
+use scribe::Workspace;
 use std::path::Path;
 
 /// Translates a line range to a regular range, including its last line.
@@ -59,10 +73,19 @@ pub fn open_buffer(path: &Path, app: &mut Application) -> Result<()> {
         .syntax_definition_name(&path)
         .and_then(|name| app.workspace.syntax_set.find_syntax_by_name(&name).cloned());
 
+    let buffer_count = app.workspace.buffer_paths().len();
     app.workspace
         .open_buffer(&path)
         .context("Couldn't open a buffer for the specified path.")?;
 
+    // Restore the undo history of newly opened buffers. The buffer is still
+    // usable without it, so we finish opening it before reporting a failure.
+    let restored = if app.workspace.buffer_paths().len() > buffer_count {
+        undo_history::restore(app).context("Couldn't restore undo history")
+    } else {
+        Ok(())
+    };
+
     let buffer = app.workspace.current_buffer.as_mut().unwrap();
 
     // Only override the default syntax definition if the user provided
@@ -73,7 +96,7 @@ pub fn open_buffer(path: &Path, app: &mut Application) -> Result<()> {
 
     app.view.initialize_buffer(buffer)?;
 
-    Ok(())
+    restored
 }
 
 /// Convenience method to add/initialize an in-memory buffer in the workspace.
@@ -85,6 +108,50 @@ pub fn add_buffer(buffer: Buffer, app: &mut Application) -> Result<()> {
     Ok(())
 }
 
+/// Cycles through the workspace's buffers, collecting the paths of those with
+/// unsaved changes. Empty buffers are skipped, matching the close command's
+/// confirmation rules. The original buffer is selected again when finished.
+pub fn modified_buffer_paths(workspace: &mut Workspace) -> Vec<String> {
+    let mut paths = Vec::new();
+
+    for _ in 0..workspace.buffer_paths().len() {
+        if let Some(buf) = workspace.current_buffer.as_ref() {
+            if buf.modified() && !buf.data().is_empty() {
+                paths.push(
+                    workspace
+                        .current_buffer_path()
+                        .unwrap_or(Path::new("untitled"))
+                        .to_string_lossy()
+                        .into_owned(),
+                );
+            }
+        }
+
+        workspace.next_buffer();
+    }
+
+    paths
+}
+
+/// Runs a command, grouping its buffer changes as a single undoable operation,
+/// including those that the command (or any it runs) would group on its own.
+pub fn group_changes<F>(app: &mut Application, command: F) -> Result<()>
+where
+    F: FnOnce(&mut Application) -> Result<()>,
+{
+    commands::buffer::start_command_group(app)?;
+    let already_held = std::mem::replace(&mut app.command_group_held, true);
+    let result = command(app);
+    app.command_group_held = already_held;
+
+    // Insert and paste modes manage their own operation group.
+    if !already_held && !matches!(app.mode, Mode::Insert | Mode::Paste) {
+        commands::buffer::end_command_group(app)?;
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use scribe::buffer::{LineRange, Position, Range};
@@ -0,0 +1,231 @@
+use crate::commands::{self, Command};
+use crate::errors::*;
+use crate::models::application::{Application, Mode, SecondaryCursor};
+use crate::util;
+use scribe::buffer::Position;
+use std::cmp::Ordering;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Runs a command at the current buffer's primary cursor, as well as at each
+/// of its secondary cursors, grouping any resulting edits as a single
+/// operation. Without secondary cursors, the command is simply run once.
+///
+/// Cursors are visited in reverse document order, so that an edit made at
+/// one cursor can't displace those yet to be visited. Cursors that have
+/// already been visited follow the edit, and are shifted by the change in
+/// the buffer's line count, as well as the change in length of the line
+/// the edit ended on, for those on it.
+pub fn apply(app: &mut Application, command: Command) -> Result<()> {
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+
+    // Take the secondary cursors out for the duration; this also ensures
+    // nested calls (e.g. a command composed of other commands) run once.
+    let secondary_cursors = app.cursors.take(buffer);
+    if secondary_cursors.is_empty() {
+        return command(app);
+    }
+
+    let primary_cursor = SecondaryCursor {
+        position: *buffer.cursor,
+        anchor: selection_anchor(&app.mode).unwrap_or(*buffer.cursor),
+    };
+    let mut cursors: Vec<(SecondaryCursor, bool)> = std::iter::once((primary_cursor, true))
+        .chain(secondary_cursors.into_iter().map(|c| (c, false)))
+        .collect();
+    cursors.sort_by(|(a, _), (b, _)| start(b).partial_cmp(&start(a)).unwrap_or(Ordering::Equal));
+
+    let result = util::group_changes(app, |app| {
+        let mut result = Ok(());
+        let mut data = None;
+        for index in 0..cursors.len() {
+            let (cursor, primary) = cursors[index];
+            let buffer = app
+                .workspace
+                .current_buffer
+                .as_mut()
+                .context(BUFFER_MISSING)?;
+
+            // Move to the cursor and run the command, holding on to the first
+            // error; a motion failing for one cursor shouldn't strand the others.
+            buffer.cursor.move_to(cursor.position);
+            set_selection_anchor(&mut app.mode, cursor.anchor);
+            if let Err(error) = command(app) {
+                if result.is_ok() {
+                    result = Err(error);
+                }
+            }
+
+            let buffer = app
+                .workspace
+                .current_buffer
+                .as_ref()
+                .context(BUFFER_MISSING)?;
+            let position = *buffer.cursor;
+            let anchor = selection_anchor(&app.mode).unwrap_or(position);
+            cursors[index] = (SecondaryCursor { position, anchor }, primary);
+
+            // Visited cursors sit after this one; the first of them is on the
+            // line the edit ended on, so compare that line (and the line count)
+            // to how it was before the command, and shift them accordingly.
+            let new_data = buffer.data();
+            let visited = cursors[..index].last().map(|(cursor, _)| start(cursor));
+            if let (Some(first), Some(data)) = (visited, data.as_deref()) {
+                let line_delta = line_count(&new_data) as isize - line_count(data) as isize;
+                let new_line = first.line.saturating_add_signed(line_delta);
+                let offset_delta = line_length(&new_data, new_line) as isize
+                    - line_length(data, first.line) as isize;
+
+                for (visited, _) in cursors[..index].iter_mut() {
+                    for position in [&mut visited.position, &mut visited.anchor] {
+                        if position.line == first.line {
+                            position.offset = position.offset.saturating_add_signed(offset_delta);
+                        }
+                        position.line = position.line.saturating_add_signed(line_delta);
+                    }
+                }
+            }
+            data = Some(new_data);
+        }
+
+        result
+    });
+
+    // Restore the primary cursor and store the secondary cursors.
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    let mut secondary_cursors = Vec::new();
+    for (cursor, primary) in cursors.into_iter().rev() {
+        if primary {
+            buffer.cursor.move_to(cursor.position);
+            set_selection_anchor(&mut app.mode, cursor.anchor);
+        } else {
+            secondary_cursors.push(cursor);
+        }
+    }
+    app.cursors.replace(buffer, secondary_cursors);
+    commands::view::scroll_to_cursor(app)?;
+
+    result
+}
+
+/// The selection anchor for the current mode, if it has one.
+pub fn selection_anchor(mode: &Mode) -> Option<Position> {
+    match *mode {
+        Mode::Select(ref mode) => Some(mode.anchor),
+        Mode::SelectLine(ref mode) => Some(Position {
+            line: mode.anchor,
+            offset: 0,
+        }),
+        _ => None,
+    }
+}
+
+fn set_selection_anchor(mode: &mut Mode, anchor: Position) {
+    match *mode {
+        Mode::Select(ref mut mode) => mode.anchor = anchor,
+        Mode::SelectLine(ref mut mode) => mode.anchor = anchor.line,
+        _ => (),
+    }
+}
+
+// The earlier of the cursor's position and anchor.
+fn start(cursor: &SecondaryCursor) -> Position {
+    if cursor.anchor < cursor.position {
+        cursor.anchor
+    } else {
+        cursor.position
+    }
+}
+
+// The number of lines in the data.
+fn line_count(data: &str) -> usize {
+    data.matches('\n').count() + 1
+}
+
+// The length of the line, in graphemes (excluding its line break).
+fn line_length(data: &str, line: usize) -> usize {
+    data.split('\n').nth(line).map_or(0, |line| {
+        line.trim_end_matches('\r').graphemes(true).count()
+    })
+}
+
+/// Maps a position to its grapheme index within the data.
+pub fn index_of(data: &str, position: &Position) -> usize {
+    let mut current = Position::new();
+
+    for (index, grapheme) in data.graphemes(true).enumerate() {
+        if current >= *position {
+            return index;
+        }
+
+        if is_line_break(grapheme) {
+            if current.line == position.line {
+                // The offset is beyond the end of the line; clamp it.
+                return index;
+            }
+
+            current.line += 1;
+            current.offset = 0;
+        } else {
+            current.offset += 1;
+        }
+    }
+
+    data.graphemes(true).count()
+}
+
+/// Maps a grapheme index within the data to its position.
+pub fn position_of(data: &str, index: usize) -> Position {
+    let mut position = Position::new();
+
+    for grapheme in data.graphemes(true).take(index) {
+        if is_line_break(grapheme) {
+            position.line += 1;
+            position.offset = 0;
+        } else {
+            position.offset += 1;
+        }
+    }
+
+    position
+}
+
+// CRLF line endings are a single grapheme.
+fn is_line_break(grapheme: &str) -> bool {
+    grapheme == "\n" || grapheme == "\r\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use scribe::buffer::Position;
+
+    #[test]
+    fn index_of_and_position_of_are_inverses() {
+        let data = "amp\neditör\n";
+
+        for index in 0..=11 {
+            let position = super::position_of(data, index);
+            assert_eq!(super::index_of(data, &position), index);
+        }
+        assert_eq!(super::position_of(data, 8), Position { line: 1, offset: 4 });
+    }
+
+    #[test]
+    fn index_of_and_position_of_treat_crlf_as_a_line_break() {
+        let data = "amp\r\neditor\r\n";
+
+        assert_eq!(super::index_of(data, &Position { line: 1, offset: 2 }), 6);
+        assert_eq!(super::position_of(data, 6), Position { line: 1, offset: 2 });
+        assert_eq!(
+            super::position_of(data, 11),
+            Position { line: 2, offset: 0 }
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// An integer literal found on a line, spanning the
+/// [start, end) grapheme offsets, including its sign and prefix.
+#[derive(Debug, PartialEq)]
+pub struct Number {
+    pub start: usize,
+    pub end: usize,
+    value: i128,
+    prefix: String,
+    radix: u32,
+    digits: usize,
+    padded: bool,
+    uppercase: bool,
+}
+
+impl Number {
+    /// Renders the literal with the amount added, keeping its prefix, letter
+    /// case and zero-padded width. Hex and binary literals can't go below
+    /// zero, and yield None if they would.
+    pub fn add(&self, amount: i128) -> Option<String> {
+        let value = self.value.checked_add(amount)?;
+        if self.radix != 10 && value < 0 {
+            return None;
+        }
+
+        let magnitude = value.unsigned_abs();
+        let digits = match self.radix {
+            16 if self.uppercase => format!("{magnitude:X}"),
+            16 => format!("{magnitude:x}"),
+            2 => format!("{magnitude:b}"),
+            _ => magnitude.to_string(),
+        };
+        let width = if self.padded { self.digits } else { 0 };
+        let prefix = &self.prefix;
+        let sign = if value < 0 { "-" } else { "" };
+
+        Some(format!("{sign}{prefix}{digits:0>width$}"))
+    }
+}
+
+/// Finds the integer literal under, or otherwise after, the offset on a line.
+/// Decimal literals can be negative, as long as the minus sign isn't part of
+/// a preceding word (e.g. "x-1"), which is more likely to be a subtraction.
+pub fn find(line: &str, offset: usize) -> Option<Number> {
+    let graphemes: Vec<&str> = line.graphemes(true).collect();
+    let mut index = 0;
+
+    while index < graphemes.len() {
+        match parse(&graphemes, index) {
+            Some(number) if number.end > offset => return Some(number),
+            Some(number) => index = number.end,
+            None => index += 1,
+        }
+    }
+
+    None
+}
+
+fn parse(graphemes: &[&str], start: usize) -> Option<Number> {
+    let at = |index: usize| graphemes.get(index).copied().unwrap_or("");
+    let negative =
+        at(start) == "-" && is_digit(at(start + 1), 10) && (start == 0 || !is_word(at(start - 1)));
+    let digits_start = if negative { start + 1 } else { start };
+
+    if !is_digit(at(digits_start), 10) {
+        return None;
+    }
+
+    // Hex and binary literals are only recognized when
+    // their prefix is followed by at least one digit.
+    let radix = match (at(digits_start), at(digits_start + 1)) {
+        ("0", "x" | "X") if !negative && is_digit(at(digits_start + 2), 16) => 16,
+        ("0", "b" | "B") if !negative && is_digit(at(digits_start + 2), 2) => 2,
+        _ => 10,
+    };
+    let prefix = if radix == 10 {
+        String::new()
+    } else {
+        graphemes[digits_start..digits_start + 2].concat()
+    };
+    let digits_start = digits_start + prefix.len();
+    let digits_end = (digits_start..=graphemes.len())
+        .find(|&index| !is_digit(at(index), radix))
+        .unwrap_or(graphemes.len());
+    let digits = graphemes[digits_start..digits_end].concat();
+    let magnitude = i128::from_str_radix(&digits, radix).ok()?;
+
+    Some(Number {
+        start,
+        end: digits_end,
+        value: if negative { -magnitude } else { magnitude },
+        prefix,
+        radix,
+        digits: digits.len(),
+        padded: digits.len() > 1 && digits.starts_with('0'),
+        uppercase: digits
+            .chars()
+            .any(|character| character.is_ascii_uppercase()),
+    })
+}
+
+fn is_digit(grapheme: &str, radix: u32) -> bool {
+    let mut characters = grapheme.chars();
+    matches!(
+        (characters.next(), characters.next()),
+        (Some(character), None) if character.is_digit(radix)
+    )
+}
+
+fn is_word(grapheme: &str) -> bool {
+    grapheme
+        .chars()
+        .all(|character| character.is_alphanumeric() || character == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find;
+
+    fn add(line: &str, offset: usize, amount: i128) -> Option<String> {
+        find(line, offset).and_then(|number| number.add(amount))
+    }
+
+    #[test]
+    fn find_prefers_the_number_under_the_cursor_over_the_next_one() {
+        let number = find("a = 123 + 45", 5).unwrap();
+        assert_eq!((number.start, number.end), (4, 7));
+
+        let number = find("a = 123 + 45", 7).unwrap();
+        assert_eq!((number.start, number.end), (10, 12));
+
+        assert!(find("a = 123", 7).is_none());
+    }
+
+    #[test]
+    fn add_keeps_the_radix_case_and_zero_padding_of_literals() {
+        assert_eq!(add("9", 0, 1), Some("10".to_string()));
+        assert_eq!(add("007", 0, 1), Some("008".to_string()));
+        assert_eq!(add("099", 0, 1), Some("100".to_string()));
+        assert_eq!(add("0X0F", 0, 1), Some("0X10".to_string()));
+        assert_eq!(add("0xff", 0, 1), Some("0x100".to_string()));
+        assert_eq!(add("0b0111", 0, 1), Some("0b1000".to_string()));
+        assert_eq!(add("0x00", 0, -1), None);
+    }
+
+    #[test]
+    fn add_handles_negative_numbers_but_not_subtraction() {
+        assert_eq!(add("x = -1", 0, 2), Some("1".to_string()));
+        assert_eq!(add("x = 0", 0, -1), Some("-1".to_string()));
+        assert_eq!(add("x-1", 0, 1), Some("2".to_string()));
+    }
+}
@@ -0,0 +1,225 @@
+use crate::errors::*;
+use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A snippet template, parsed into the text it expands to and its tab stops.
+///
+/// Stops are written as `$1` or `${1:placeholder}`, and visited in ascending
+/// order, ending at `$0` (or the end of the snippet, without one). Stops that
+/// share a number mirror the first of them. `$`, `}` and `\` are escaped
+/// with a backslash.
+#[derive(Debug, PartialEq)]
+pub struct Snippet {
+    pub text: String,
+    pub stops: Vec<TabStop>,
+}
+
+/// A tab stop's location within the snippet, in graphemes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TabStop {
+    pub number: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+enum Segment {
+    Text(String),
+    Stop(usize, String),
+}
+
+impl Snippet {
+    /// Parses the template, indenting lines after the first one so
+    /// that they line up with the line the snippet is inserted on.
+    pub fn parse(template: &str, indent: &str) -> Result<Snippet> {
+        // YAML block scalars are terminated with a newline; drop it.
+        let template = template.strip_suffix('\n').unwrap_or(template);
+        let segments = segments(template, indent)?;
+
+        // Mirrors take on the first placeholder given for their stop.
+        let mut placeholders = HashMap::new();
+        for segment in segments.iter() {
+            if let Segment::Stop(number, ref placeholder) = *segment {
+                let entry = placeholders.entry(number).or_insert(placeholder);
+                if entry.is_empty() {
+                    *entry = placeholder;
+                }
+            }
+        }
+
+        let mut text = String::new();
+        let mut length = 0;
+        let mut stops = Vec::new();
+        for segment in segments.iter() {
+            match *segment {
+                Segment::Text(ref content) => {
+                    text.push_str(content);
+                    length += content.graphemes(true).count();
+                }
+                Segment::Stop(number, _) => {
+                    let placeholder = placeholders[&number];
+                    let start = length;
+                    text.push_str(placeholder);
+                    length += placeholder.graphemes(true).count();
+                    stops.push(TabStop {
+                        number,
+                        start,
+                        end: length,
+                    });
+                }
+            }
+        }
+
+        // Without a final stop, finish at the end of the snippet.
+        if !stops.iter().any(|stop| stop.number == 0) {
+            stops.push(TabStop {
+                number: 0,
+                start: length,
+                end: length,
+            });
+        }
+
+        Ok(Snippet { text, stops })
+    }
+}
+
+// Splits the template into literal text and tab stops, unescaping
+// characters and indenting the lines that follow newlines.
+fn segments(template: &str, indent: &str) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut characters = template.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => match characters.peek() {
+                Some(&escaped @ ('$' | '}' | '\\')) => {
+                    text.push(escaped);
+                    characters.next();
+                }
+                _ => text.push(character),
+            },
+            '$' => {
+                let braced = characters.next_if_eq(&'{').is_some();
+                let mut number = String::new();
+                while let Some(digit) = characters.next_if(char::is_ascii_digit) {
+                    number.push(digit);
+                }
+                if number.is_empty() {
+                    if braced {
+                        bail!("Snippet tab stops must be numbered");
+                    }
+                    text.push(character);
+                    continue;
+                }
+
+                let mut placeholder = String::new();
+                if braced {
+                    if characters.next_if_eq(&':').is_some() {
+                        loop {
+                            match characters.next() {
+                                Some('}') => break,
+                                Some('\\')
+                                    if matches!(characters.peek(), Some('$' | '}' | '\\')) =>
+                                {
+                                    placeholder.extend(characters.next());
+                                }
+                                Some('\n') => {
+                                    placeholder.push('\n');
+                                    placeholder.push_str(indent);
+                                }
+                                Some(character) => placeholder.push(character),
+                                None => bail!("Snippet placeholder is missing a closing brace"),
+                            }
+                        }
+                    } else if characters.next_if_eq(&'}').is_none() {
+                        bail!("Snippet tab stop is missing a closing brace");
+                    }
+                }
+
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                let number = number.parse().context("Couldn't parse snippet tab stop")?;
+                segments.push(Segment::Stop(number, placeholder));
+            }
+            '\n' => {
+                text.push('\n');
+                text.push_str(indent);
+            }
+            _ => text.push(character),
+        }
+    }
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Snippet, TabStop};
+
+    #[test]
+    fn parse_finds_stops_and_placeholders() {
+        let snippet = Snippet::parse("fn ${1:name}($2) {\n    $0\n}\n", "").unwrap();
+
+        assert_eq!(snippet.text, "fn name() {\n    \n}");
+        assert_eq!(
+            snippet.stops,
+            vec![
+                TabStop {
+                    number: 1,
+                    start: 3,
+                    end: 7
+                },
+                TabStop {
+                    number: 2,
+                    start: 8,
+                    end: 8
+                },
+                TabStop {
+                    number: 0,
+                    start: 16,
+                    end: 16
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_fills_mirrors_with_their_placeholder_and_adds_a_final_stop() {
+        let snippet = Snippet::parse("impl $1 for ${1:Type} {}", "").unwrap();
+
+        assert_eq!(snippet.text, "impl Type for Type {}");
+        assert_eq!(snippet.stops[0].start..snippet.stops[0].end, 5..9);
+        assert_eq!(snippet.stops[1].start..snippet.stops[1].end, 14..18);
+        assert_eq!(
+            snippet.stops[2],
+            TabStop {
+                number: 0,
+                start: 21,
+                end: 21
+            }
+        );
+    }
+
+    #[test]
+    fn parse_indents_lines_and_unescapes_characters() {
+        let snippet = Snippet::parse("match $1 {\n    ${2:_ => {\\}}\n}", "  ").unwrap();
+
+        assert_eq!(snippet.text, "match  {\n      _ => {}\n  }");
+        assert_eq!(snippet.stops[1].start..snippet.stops[1].end, 15..22);
+        assert_eq!(
+            Snippet::parse("\\$1 costs $5", "").unwrap().text,
+            "$1 costs "
+        );
+    }
+
+    #[test]
+    fn parse_rejects_unterminated_stops() {
+        assert!(Snippet::parse("${1:name", "").is_err());
+        assert!(Snippet::parse("${1", "").is_err());
+        assert!(Snippet::parse("${name}", "").is_err());
+    }
+}
@@ -0,0 +1,331 @@
+use scribe::buffer::{Position, Range};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A region of text that can be selected as a unit,
+/// either including or excluding its delimiters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextObject {
+    Pair(char, char),
+    Quote(char),
+    Paragraph,
+}
+
+impl TextObject {
+    /// Maps a key to its text object, using either delimiter for
+    /// brackets, as well as "b"/"B" for parentheses/braces.
+    pub fn from_char(character: char) -> Option<TextObject> {
+        match character {
+            '(' | ')' | 'b' => Some(TextObject::Pair('(', ')')),
+            '[' | ']' => Some(TextObject::Pair('[', ']')),
+            '{' | '}' | 'B' => Some(TextObject::Pair('{', '}')),
+            '<' | '>' => Some(TextObject::Pair('<', '>')),
+            '"' | '\'' | '`' => Some(TextObject::Quote(character)),
+            'p' => Some(TextObject::Paragraph),
+            _ => None,
+        }
+    }
+}
+
+/// Finds the text object nearest to the cursor, returning its range
+/// inside (or, if around is set, including) its delimiters.
+///
+/// Pairs are found by looking outwards from the cursor for the innermost
+/// enclosing delimiters. Quotes are limited to the cursor's line, preferring
+/// a pair containing the cursor, and falling back to the next one.
+pub fn find(data: &str, cursor: &Position, object: TextObject, around: bool) -> Option<Range> {
+    match object {
+        TextObject::Pair(open, close) => find_pair(data, cursor, open, close, around),
+        TextObject::Quote(quote) => find_quotes(data, cursor, quote, around),
+        TextObject::Paragraph => Some(find_paragraph(data, cursor, around)),
+    }
+}
+
+fn find_pair(
+    data: &str,
+    cursor: &Position,
+    open: char,
+    close: char,
+    around: bool,
+) -> Option<Range> {
+    let graphemes = positioned_graphemes(data);
+    let index = graphemes
+        .iter()
+        .position(|(position, _)| position >= cursor)
+        .unwrap_or(graphemes.len());
+    let (open, close) = (open.to_string(), close.to_string());
+
+    // Look backwards for an unmatched opening delimiter. A closing
+    // delimiter under the cursor belongs to the pair we're looking for.
+    let mut depth = 0;
+    let mut start = None;
+    for current in (0..graphemes.len().min(index + 1)).rev() {
+        let grapheme = graphemes[current].1;
+
+        if grapheme == close && current != index {
+            depth += 1;
+        } else if grapheme == open {
+            if depth == 0 {
+                start = Some(current);
+                break;
+            }
+            depth -= 1;
+        }
+    }
+    let start = start?;
+
+    // Look forwards for its matching closing delimiter.
+    let mut depth = 0;
+    let mut end = None;
+    for (current, &(_, grapheme)) in graphemes.iter().enumerate().skip(start + 1) {
+        if grapheme == open {
+            depth += 1;
+        } else if grapheme == close {
+            if depth == 0 {
+                end = Some(current);
+                break;
+            }
+            depth -= 1;
+        }
+    }
+    let end = end?;
+
+    Some(delimited_range(&graphemes, start, end, around))
+}
+
+fn find_quotes(data: &str, cursor: &Position, quote: char, around: bool) -> Option<Range> {
+    let graphemes = positioned_graphemes(data);
+    let quote = quote.to_string();
+
+    // Find unescaped quotes on the cursor's line.
+    let quotes: Vec<usize> = graphemes
+        .iter()
+        .enumerate()
+        .filter(|(index, (position, grapheme))| {
+            position.line == cursor.line
+                && *grapheme == quote
+                && (*index == 0 || graphemes[index - 1].1 != "\\")
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+    // Pair them up, looking for one containing the cursor, or the next one.
+    let (start, end) = quotes
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .find(|&(_, end)| graphemes[end].0 >= *cursor)?;
+
+    Some(delimited_range(&graphemes, start, end, around))
+}
+
+fn find_paragraph(data: &str, cursor: &Position, around: bool) -> Range {
+    let lines: Vec<&str> = data.lines().collect();
+    let blank = |line: usize| lines.get(line).is_none_or(|l| l.trim().is_empty());
+    let cursor_blank = blank(cursor.line);
+
+    // A paragraph is a run of lines that are all either blank or non-blank.
+    let mut first = cursor.line;
+    while first > 0 && blank(first - 1) == cursor_blank {
+        first -= 1;
+    }
+    let mut last = cursor.line;
+    while last + 1 < lines.len() && blank(last + 1) == cursor_blank {
+        last += 1;
+    }
+
+    // Around a paragraph includes its trailing blank
+    // lines, or its leading ones if there aren't any.
+    if around {
+        if last + 1 < lines.len() {
+            while last + 1 < lines.len() && blank(last + 1) != cursor_blank {
+                last += 1;
+            }
+        } else {
+            while first > 0 && blank(first - 1) != cursor_blank {
+                first -= 1;
+            }
+        }
+    }
+
+    // Include the last line's newline, unless it's at the end of the data.
+    let end = if last + 1 < lines.len() || data.ends_with('\n') {
+        Position {
+            line: (last + 1).min(lines.len()),
+            offset: 0,
+        }
+    } else {
+        Position {
+            line: last,
+            offset: lines
+                .get(last)
+                .map(|l| l.graphemes(true).count())
+                .unwrap_or(0),
+        }
+    };
+
+    Range::new(
+        Position {
+            line: first,
+            offset: 0,
+        },
+        end,
+    )
+}
+
+// Builds the range between two delimiters, optionally including them.
+fn delimited_range(
+    graphemes: &[(Position, &str)],
+    start: usize,
+    end: usize,
+    around: bool,
+) -> Range {
+    if around {
+        Range::new(graphemes[start].0, after(&graphemes[end]))
+    } else {
+        Range::new(after(&graphemes[start]), graphemes[end].0)
+    }
+}
+
+// The position immediately following a grapheme.
+fn after(&(position, grapheme): &(Position, &str)) -> Position {
+    if grapheme == "\n" {
+        Position {
+            line: position.line + 1,
+            offset: 0,
+        }
+    } else {
+        Position {
+            line: position.line,
+            offset: position.offset + 1,
+        }
+    }
+}
+
+// Pairs each of the data's graphemes with its position.
+fn positioned_graphemes(data: &str) -> Vec<(Position, &str)> {
+    let mut position = Position::new();
+
+    data.graphemes(true)
+        .map(|grapheme| {
+            let current = position;
+            if grapheme == "\n" {
+                position.line += 1;
+                position.offset = 0;
+            } else {
+                position.offset += 1;
+            }
+
+            (current, grapheme)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find, TextObject};
+    use scribe::buffer::{Position, Range};
+
+    fn range(start: (usize, usize), end: (usize, usize)) -> Option<Range> {
+        Some(Range::new(
+            Position {
+                line: start.0,
+                offset: start.1,
+            },
+            Position {
+                line: end.0,
+                offset: end.1,
+            },
+        ))
+    }
+
+    #[test]
+    fn find_selects_the_innermost_enclosing_pair() {
+        let data = "call(a, (b), c)";
+        let cursor = Position {
+            line: 0,
+            offset: 12,
+        };
+        let parens = TextObject::Pair('(', ')');
+
+        assert_eq!(find(data, &cursor, parens, false), range((0, 5), (0, 14)));
+        assert_eq!(find(data, &cursor, parens, true), range((0, 4), (0, 15)));
+    }
+
+    #[test]
+    fn find_treats_delimiters_under_the_cursor_as_part_of_the_pair() {
+        let data = "[a, [b]]";
+        let parens = TextObject::Pair('[', ']');
+
+        assert_eq!(
+            find(data, &Position { line: 0, offset: 4 }, parens, false),
+            range((0, 5), (0, 6))
+        );
+        assert_eq!(
+            find(data, &Position { line: 0, offset: 7 }, parens, false),
+            range((0, 1), (0, 7))
+        );
+    }
+
+    #[test]
+    fn find_returns_none_without_an_enclosing_pair() {
+        let data = "a (b) c";
+        let cursor = Position { line: 0, offset: 6 };
+
+        assert_eq!(find(data, &cursor, TextObject::Pair('(', ')'), false), None);
+    }
+
+    #[test]
+    fn find_selects_quotes_containing_or_following_the_cursor() {
+        let data = "let s = \"a \\\" b\"; 'c'";
+        let quotes = TextObject::Quote('"');
+
+        assert_eq!(
+            find(
+                data,
+                &Position {
+                    line: 0,
+                    offset: 10
+                },
+                quotes,
+                false
+            ),
+            range((0, 9), (0, 15))
+        );
+        assert_eq!(
+            find(data, &Position { line: 0, offset: 0 }, quotes, true),
+            range((0, 8), (0, 16))
+        );
+        assert_eq!(
+            find(
+                data,
+                &Position { line: 0, offset: 0 },
+                TextObject::Quote('\''),
+                false
+            ),
+            range((0, 19), (0, 20))
+        );
+    }
+
+    #[test]
+    fn find_selects_paragraphs_and_their_trailing_blank_lines() {
+        let data = "a\nb\n\nc\n";
+        let cursor = Position { line: 1, offset: 0 };
+
+        assert_eq!(
+            find(data, &cursor, TextObject::Paragraph, false),
+            range((0, 0), (2, 0))
+        );
+        assert_eq!(
+            find(data, &cursor, TextObject::Paragraph, true),
+            range((0, 0), (3, 0))
+        );
+        assert_eq!(
+            find(
+                data,
+                &Position { line: 3, offset: 0 },
+                TextObject::Paragraph,
+                true
+            ),
+            range((2, 0), (4, 0))
+        );
+    }
+}
@@ -0,0 +1,519 @@
+use crate::errors::*;
+use crate::models::application::{Application, Preferences};
+use scribe::buffer::{Position, Range};
+use scribe::Buffer;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+use unicode_segmentation::UnicodeSegmentation;
+use yaml_rust::yaml::{Hash, Yaml};
+use yaml_rust::{YamlEmitter, YamlLoader};
+
+const CONTENT_HASH_KEY: &str = "content_hash";
+const CHANGES_KEY: &str = "changes";
+const LINE_KEY: &str = "line";
+const DELETED_KEY: &str = "deleted";
+const INSERTED_KEY: &str = "inserted";
+
+/// The most change data (in bytes) persisted for a buffer;
+/// older changes beyond this are dropped from its history.
+const MAX_HISTORY_SIZE: usize = 1024 * 1024;
+
+/// The most data (in bytes) kept in the undo history directory;
+/// the least recently saved histories beyond this are removed.
+const MAX_DIRECTORY_SIZE: u64 = 32 * 1024 * 1024;
+
+/// Writes the current buffer's undo history, as tracked by the application's
+/// undo tree, to the undo history directory, keyed by its path and content,
+/// if persistent undo is enabled. Buffers with unsaved changes are skipped,
+/// since their content wouldn't match the file when it's next opened.
+pub fn save(app: &mut Application) -> Result<()> {
+    if !app.preferences.borrow().persistent_undo() {
+        return Ok(());
+    }
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_ref()
+        .context(BUFFER_MISSING)?;
+    let Some(ref path) = buffer.path else {
+        return Ok(());
+    };
+    if buffer.modified() {
+        return Ok(());
+    }
+
+    // Buffers that haven't been tracked have no history to save, and
+    // saving it would overwrite any restored from a previous session.
+    if app.undo_tree.current(buffer).is_none() {
+        return Ok(());
+    }
+    app.undo_tree
+        .record(buffer, app.view.buffer_revision(buffer));
+    let changes = app.undo_tree.changes(buffer).unwrap_or_default();
+
+    save_history(
+        path,
+        &buffer.data(),
+        &changes,
+        &Preferences::undo_history_path()?,
+    )
+}
+
+/// Restores the current buffer's undo history, if persistent undo is enabled
+/// and history was saved for its path when the file had the same content.
+/// The restored changes are tracked by the application's undo tree, too.
+pub fn restore(app: &mut Application) -> Result<()> {
+    if !app.preferences.borrow().persistent_undo() {
+        return Ok(());
+    }
+    let buffer = app
+        .workspace
+        .current_buffer
+        .as_mut()
+        .context(BUFFER_MISSING)?;
+    if buffer.path.is_none() {
+        return Ok(());
+    }
+
+    let changes = restore_buffer_history(buffer, &Preferences::undo_history_path()?)?;
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    app.undo_tree.restore(buffer, changes)
+}
+
+/// A change to a buffer's content. Changes span whole lines, so
+/// that their positions don't depend on grapheme boundaries.
+#[derive(Debug, PartialEq)]
+pub struct Change {
+    pub line: usize,
+    pub deleted: String,
+    pub inserted: String,
+}
+
+impl Change {
+    /// The lines that differ between the older and newer content.
+    pub fn between(older: &str, newer: &str) -> Change {
+        let prefix_length = older
+            .bytes()
+            .zip(newer.bytes())
+            .take_while(|(older, newer)| older == newer)
+            .count();
+        let prefix_length = older[..floor_char_boundary(older, prefix_length)]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+
+        let suffix_length = older[prefix_length..]
+            .bytes()
+            .rev()
+            .zip(newer[prefix_length..].bytes().rev())
+            .take_while(|(older, newer)| older == newer)
+            .count();
+        let suffix = &older[ceil_char_boundary(older, older.len() - suffix_length)..];
+        let suffix_length = suffix
+            .find('\n')
+            .map_or(0, |index| suffix.len() - index - 1);
+
+        Change {
+            line: older[..prefix_length].matches('\n').count(),
+            deleted: older[prefix_length..older.len() - suffix_length].to_string(),
+            inserted: newer[prefix_length..newer.len() - suffix_length].to_string(),
+        }
+    }
+
+    /// Makes the change to the buffer as a single operation.
+    pub fn apply(&self, buffer: &mut Buffer) {
+        let start = Position {
+            line: self.line,
+            offset: 0,
+        };
+
+        buffer.start_operation_group();
+        buffer.cursor.move_to(start);
+        if !self.deleted.is_empty() {
+            buffer.delete_range(Range::new(start, end_position(start, &self.deleted)));
+        }
+        if !self.inserted.is_empty() {
+            buffer.insert(self.inserted.as_str());
+        }
+        buffer.end_operation_group();
+    }
+
+    /// The content from before the change, given the content after it.
+    pub fn revert(&self, content: &str) -> Result<String> {
+        swap_lines(content, self.line, &self.inserted, &self.deleted)
+    }
+
+    /// The content from after the change, given the content before it.
+    pub fn replay(&self, content: &str) -> Result<String> {
+        swap_lines(content, self.line, &self.deleted, &self.inserted)
+    }
+
+    fn size(&self) -> usize {
+        self.deleted.len() + self.inserted.len()
+    }
+}
+
+// Writes the changes leading to the content, limited to the most recent
+// ones that fit within the maximum history size, making room for them by
+// removing the least recently saved histories if needed.
+fn save_history(path: &Path, content: &str, changes: &[&Change], directory: &Path) -> Result<()> {
+    let mut size = 0;
+    let recent_changes = changes.iter().rev().take_while(|change| {
+        size += change.size();
+        size <= MAX_HISTORY_SIZE
+    });
+    let mut serialized_changes: Vec<Yaml> = recent_changes
+        .map(|change| {
+            let mut data = Hash::new();
+            data.insert(
+                Yaml::String(LINE_KEY.to_string()),
+                Yaml::Integer(change.line as i64),
+            );
+            data.insert(
+                Yaml::String(DELETED_KEY.to_string()),
+                Yaml::String(change.deleted.clone()),
+            );
+            data.insert(
+                Yaml::String(INSERTED_KEY.to_string()),
+                Yaml::String(change.inserted.clone()),
+            );
+
+            Yaml::Hash(data)
+        })
+        .collect();
+    serialized_changes.reverse();
+
+    let mut document = Hash::new();
+    document.insert(
+        Yaml::String(CONTENT_HASH_KEY.to_string()),
+        Yaml::String(hash(content)),
+    );
+    document.insert(
+        Yaml::String(CHANGES_KEY.to_string()),
+        Yaml::Array(serialized_changes),
+    );
+
+    let mut data = String::new();
+    YamlEmitter::new(&mut data)
+        .dump(&Yaml::Hash(document))
+        .context("Couldn't serialize undo history")?;
+    fs::create_dir_all(directory).context("Couldn't create undo history directory")?;
+    fs::write(history_path(directory, path), data).context("Couldn't write undo history file")?;
+
+    limit_directory_size(directory, MAX_DIRECTORY_SIZE)
+}
+
+// Removes the least recently saved history files until the
+// directory's combined file size is within the specified limit.
+fn limit_directory_size(directory: &Path, limit: u64) -> Result<()> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(directory).context("Couldn't read undo history directory")? {
+        let entry = entry.context("Couldn't read undo history directory")?;
+        let metadata = entry
+            .metadata()
+            .context("Couldn't read undo history file metadata")?;
+        if metadata.is_file() {
+            files.push((metadata.modified().ok(), metadata.len(), entry.path()));
+        }
+    }
+
+    let mut size: u64 = files.iter().map(|(_, length, _)| length).sum();
+    files.sort();
+    for (_, length, path) in files {
+        if size <= limit {
+            break;
+        }
+
+        fs::remove_file(path).context("Couldn't remove undo history file")?;
+        size -= length;
+    }
+
+    Ok(())
+}
+
+// Replaces the buffer with one whose history is made up of the saved
+// changes, returning them, provided they lead to the buffer's content.
+fn restore_buffer_history(buffer: &mut Buffer, directory: &Path) -> Result<Vec<Change>> {
+    let path = buffer.path.clone().context(BUFFER_PATH_MISSING)?;
+    let history_path = history_path(directory, &path);
+    if !history_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let data = fs::read_to_string(&history_path).context("Couldn't read undo history file")?;
+    let document = YamlLoader::load_from_str(&data)
+        .context("Couldn't parse undo history file")?
+        .into_iter()
+        .next()
+        .context("Undo history file is empty")?;
+
+    // History saved for other content doesn't apply.
+    let content = buffer.data();
+    if document[CONTENT_HASH_KEY].as_str() != Some(hash(&content).as_str()) {
+        return Ok(Vec::new());
+    }
+
+    let changes = document[CHANGES_KEY]
+        .as_vec()
+        .context("Couldn't parse undo history changes")?
+        .iter()
+        .map(|change| {
+            Some(Change {
+                line: change[LINE_KEY].as_i64()?.try_into().ok()?,
+                deleted: change[DELETED_KEY].as_str()?.to_string(),
+                inserted: change[INSERTED_KEY].as_str()?.to_string(),
+            })
+        })
+        .collect::<Option<Vec<Change>>>()
+        .context("Couldn't parse undo history change")?;
+    if changes.is_empty() {
+        return Ok(changes);
+    }
+
+    // Rebuild the buffer from the content the history starts from,
+    // making each change to it to populate its history.
+    let mut original_content = content.clone();
+    for change in changes.iter().rev() {
+        original_content = change.revert(&original_content)?;
+    }
+    let mut restored_buffer = buffer_with_content(&original_content)?;
+    for change in &changes {
+        change.apply(&mut restored_buffer);
+    }
+    if restored_buffer.data() != content {
+        bail!("Undo history doesn't reproduce the buffer's content");
+    }
+
+    // Point the buffer at its file, and mark it as unmodified by reloading
+    // it; the content is unchanged, so no operation is added to its history.
+    restored_buffer.id = buffer.id;
+    restored_buffer.path = Some(path);
+    restored_buffer.syntax_definition = buffer.syntax_definition.clone();
+    restored_buffer.reload().context(BUFFER_RELOAD_FAILED)?;
+    restored_buffer.cursor.move_to(*buffer.cursor.clone());
+    *buffer = restored_buffer;
+
+    Ok(changes)
+}
+
+// Buffers loaded from a file are the only ones that start with content
+// but without any history, so we go through a temporary file, created
+// exclusively (and readable only by us) under an unpredictable name.
+fn buffer_with_content(content: &str) -> Result<Buffer> {
+    let mut file = NamedTempFile::new().context("Couldn't create temporary undo history file")?;
+    file.write_all(content.as_bytes())
+        .context("Couldn't write temporary undo history file")?;
+
+    Buffer::from_file(file.path()).context("Couldn't read temporary undo history file")
+}
+
+// Replaces the lines found at the specified line with others.
+fn swap_lines(content: &str, line: usize, found: &str, replacement: &str) -> Result<String> {
+    let start = line_offset(content, line).context("Undo history is out of range")?;
+    let end = start + found.len();
+    if content.get(start..end) != Some(found) {
+        bail!("Undo history doesn't match the buffer's content");
+    }
+
+    Ok(format!(
+        "{}{}{}",
+        &content[..start],
+        replacement,
+        &content[end..]
+    ))
+}
+
+// History files are named using a hash of the buffer's path.
+fn history_path(directory: &Path, path: &Path) -> PathBuf {
+    directory.join(format!("{}.yml", hash(&path.to_string_lossy())))
+}
+
+/// A 64-bit FNV-1a hash, which unlike the standard library's
+/// hasher is guaranteed to be stable across Rust releases.
+pub fn hash(data: &str) -> String {
+    let hash = data.bytes().fold(0xcbf29ce484222325, |hash: u64, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{hash:016x}")
+}
+
+// The byte offset at which a line starts.
+fn line_offset(content: &str, line: usize) -> Option<usize> {
+    if line == 0 {
+        return Some(0);
+    }
+
+    content
+        .match_indices('\n')
+        .nth(line - 1)
+        .map(|(index, _)| index + 1)
+}
+
+// The position at the end of content inserted at the start position.
+fn end_position(start: Position, content: &str) -> Position {
+    match content.rfind('\n') {
+        Some(index) => Position {
+            line: start.line + content.matches('\n').count(),
+            offset: content[index + 1..].graphemes(true).count(),
+        },
+        None => Position {
+            line: start.line,
+            offset: start.offset + content.graphemes(true).count(),
+        },
+    }
+}
+
+fn floor_char_boundary(content: &str, mut index: usize) -> usize {
+    while !content.is_char_boundary(index) {
+        index -= 1;
+    }
+
+    index
+}
+
+fn ceil_char_boundary(content: &str, mut index: usize) -> usize {
+    while !content.is_char_boundary(index) {
+        index += 1;
+    }
+
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Change;
+    use scribe::Buffer;
+    use std::env;
+    use std::fs::{self, File};
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    fn set_up_file(name: &str, content: &str) -> (PathBuf, PathBuf) {
+        let directory = env::temp_dir().join(format!("amp_undo_history_test_{name}"));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("amp.txt");
+        fs::write(&path, content).unwrap();
+
+        (path, directory.join("history"))
+    }
+
+    #[test]
+    fn change_between_spans_the_lines_that_differ() {
+        assert_eq!(
+            Change::between("amp\neditor\nbuffer\n", "amp\nedit0r\nbuffer\n"),
+            Change {
+                line: 1,
+                deleted: "editor\n".to_string(),
+                inserted: "edit0r\n".to_string()
+            }
+        );
+        assert_eq!(
+            Change::between("amp", "amp\neditor"),
+            Change {
+                line: 0,
+                deleted: "amp".to_string(),
+                inserted: "amp\neditor".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn saved_history_is_restored_for_matching_content() {
+        let (path, history_directory) = set_up_file("restore", "é\neditortext amp\n");
+        let changes = [
+            Change::between("amp\n", "text amp\n"),
+            Change::between("text amp\n", "é\neditortext amp\n"),
+        ];
+        super::save_history(
+            &path,
+            "é\neditortext amp\n",
+            &changes.iter().collect::<Vec<_>>(),
+            &history_directory,
+        )
+        .unwrap();
+
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        let restored_changes =
+            super::restore_buffer_history(&mut buffer, &history_directory).unwrap();
+        assert_eq!(restored_changes, changes);
+        assert_eq!(buffer.path, Some(path.canonicalize().unwrap()));
+        assert!(!buffer.modified());
+
+        buffer.undo();
+        assert_eq!(buffer.data(), "text amp\n");
+        buffer.undo();
+        assert_eq!(buffer.data(), "amp\n");
+        buffer.undo();
+        assert_eq!(buffer.data(), "amp\n");
+        buffer.redo();
+        buffer.redo();
+        assert_eq!(buffer.data(), "é\neditortext amp\n");
+        assert!(!buffer.modified());
+    }
+
+    #[test]
+    fn saved_history_is_limited_to_the_most_recent_changes() {
+        let (path, history_directory) = set_up_file("limit", "amp\neditor\n");
+        let large_line = format!("{}\n", "a".repeat(super::MAX_HISTORY_SIZE));
+        let changes = [
+            Change::between("", &large_line),
+            Change::between(&large_line, "amp\n"),
+            Change::between("amp\n", "amp\neditor\n"),
+        ];
+        super::save_history(
+            &path,
+            "amp\neditor\n",
+            &changes.iter().collect::<Vec<_>>(),
+            &history_directory,
+        )
+        .unwrap();
+
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        let restored_changes =
+            super::restore_buffer_history(&mut buffer, &history_directory).unwrap();
+        assert_eq!(restored_changes, changes[2..]);
+    }
+
+    #[test]
+    fn saved_history_is_ignored_for_changed_content() {
+        let (path, history_directory) = set_up_file("changed", "text amp\n");
+        let change = Change::between("amp\n", "text amp\n");
+        super::save_history(&path, "text amp\n", &[&change], &history_directory).unwrap();
+        fs::write(&path, "editor\n").unwrap();
+
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        super::restore_buffer_history(&mut buffer, &history_directory).unwrap();
+        buffer.undo();
+
+        assert_eq!(buffer.data(), "editor\n");
+    }
+
+    #[test]
+    fn limit_directory_size_removes_the_least_recently_saved_histories() {
+        let (_, history_directory) = set_up_file("directory_limit", "");
+        fs::create_dir_all(&history_directory).unwrap();
+        let now = SystemTime::now();
+        for (name, age) in [("newest", 0), ("oldest", 20), ("older", 10)] {
+            let path = history_directory.join(name);
+            fs::write(&path, "0123456789").unwrap();
+            File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(now - Duration::from_secs(age))
+                .unwrap();
+        }
+
+        super::limit_directory_size(&history_directory, 20).unwrap();
+
+        assert!(!history_directory.join("oldest").exists());
+        assert!(history_directory.join("older").exists());
+        assert!(history_directory.join("newest").exists());
+    }
+}
@@ -28,6 +28,7 @@ pub struct BufferRenderer<'a, 'p> {
     stylist: Highlighter<'a>,
     current_style: ThemeStyle,
     line_numbers: LineNumbers,
+    matching_bracket: Option<Position>,
     preferences: &'a Preferences,
     render_cache: &'a Rc<RefCell<HashMap<usize, RenderState>>>,
     screen_position: Position,
@@ -67,6 +68,7 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
             stylist,
             current_style,
             line_numbers,
+            matching_bracket: None,
             buffer_position: Position { line: 0, offset: 0 },
             preferences,
             render_cache,
@@ -79,6 +81,11 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
         }
     }
 
+    /// Highlights the bracket paired with the one under the cursor.
+    pub fn set_matching_bracket(&mut self, position: Option<Position>) {
+        self.matching_bracket = position;
+    }
+
     fn on_cursor_line(&self) -> bool {
         self.buffer_position.line == self.buffer.cursor.line
     }
@@ -159,14 +166,22 @@ impl<'a, 'p> BufferRenderer<'a, 'p> {
 
                 // We aren't inside one of the highlighted areas.
                 // Fall back to other styling considerations.
-                (Style::Default, self.token_colors(token_fg, token_bg))
+                self.unhighlighted_char_style(token_fg, token_bg)
             }
-            None => (Style::Default, self.token_colors(token_fg, token_bg)),
+            None => self.unhighlighted_char_style(token_fg, token_bg),
         };
 
         (style, colors)
     }
 
+    fn unhighlighted_char_style(&self, token_fg: RGBColor, token_bg: RGBColor) -> (Style, Colors) {
+        if self.matching_bracket == Some(self.buffer_position) {
+            (Style::Bold, Colors::Inverted)
+        } else {
+            (Style::Default, self.token_colors(token_fg, token_bg))
+        }
+    }
+
     fn token_colors(&self, token_fg: RGBColor, token_bg: RGBColor) -> Colors {
         let theme_bg = theme_background(self.theme);
 
@@ -1030,6 +1045,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn render_highlights_matching_bracket() {
+        let mut workspace = Workspace::new(Path::new(".")).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.insert("(x)");
+        workspace.add_buffer(buffer);
+
+        let terminal = build_terminal().unwrap();
+        let mut terminal_buffer = TerminalBuffer::new(terminal.width(), terminal.height());
+        let theme_set = ThemeSet::load_defaults();
+        let theme = &theme_set.themes["base16-ocean.dark"];
+        let preferences = Preferences::new(None);
+        let render_cache = Rc::new(RefCell::new(HashMap::new()));
+        let data = workspace.current_buffer.as_ref().unwrap().data();
+        let lines = LineIterator::new(&data);
+
+        let mut renderer = BufferRenderer::new(
+            workspace.current_buffer.as_ref().unwrap(),
+            None,
+            0,
+            &**terminal,
+            theme,
+            &preferences,
+            &render_cache,
+            &workspace.syntax_set,
+            &mut terminal_buffer,
+        );
+        renderer.set_matching_bracket(Some(Position { line: 0, offset: 2 }));
+        renderer.render(lines, None).unwrap();
+
+        let closing_bracket = terminal_buffer
+            .iter()
+            .find(|(_, cell)| cell.content == ")")
+            .map(|(_, cell)| (cell.style, cell.colors))
+            .unwrap();
+        assert_eq!(closing_bracket, (Style::Bold, Colors::Inverted));
+    }
+
     #[test]
     fn render_selection_overrides_token_colors() {
         let mut workspace = Workspace::new(Path::new(".")).unwrap();
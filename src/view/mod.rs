@@ -22,9 +22,10 @@ use self::theme_loader::ThemeLoader;
 use crate::errors::*;
 use crate::input::Key;
 use crate::models::application::{Event, Preferences};
+use crate::util::bracket::LineState;
 use log::debug;
 use scribe::buffer::Buffer;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::cmp;
 use std::collections::HashMap;
 use std::ops::Drop;
@@ -41,6 +42,7 @@ pub struct View {
     terminal: Arc<Box<dyn Terminal + Sync + Send + 'static>>,
     scrollable_regions: HashMap<usize, ScrollableRegion>,
     render_caches: HashMap<usize, Rc<RefCell<HashMap<usize, RenderState>>>>,
+    revisions: HashMap<usize, Rc<Cell<usize>>>,
     pub theme_set: ThemeSet,
     preferences: Rc<RefCell<Preferences>>,
     pub last_key: Option<Key>,
@@ -66,6 +68,7 @@ impl View {
             preferences,
             scrollable_regions: HashMap::new(),
             render_caches: HashMap::new(),
+            revisions: HashMap::new(),
             theme_set,
             event_channel,
             event_listener_killswitch: killswitch_tx,
@@ -126,10 +129,43 @@ impl View {
     pub fn forget_buffer(&mut self, buffer: &Buffer) -> Result<()> {
         self.scrollable_regions.remove(&buffer_key(buffer)?);
         self.render_caches.remove(&buffer_key(buffer)?);
+        self.revisions.remove(&buffer_key(buffer)?);
 
         Ok(())
     }
 
+    /// The number of changes made to the buffer since it was initialized,
+    /// which is a cheap way to tell whether it's been modified. Uninitialized
+    /// buffers don't have one.
+    pub fn buffer_revision(&self, buffer: &Buffer) -> Option<usize> {
+        let revision = self.revisions.get(&buffer.id?)?;
+
+        Some(revision.get())
+    }
+
+    /// The parser state cached while rendering the buffer that's closest to
+    /// (without passing) the specified line, so that parsing can resume
+    /// there instead of at the top of the buffer.
+    pub fn line_state(&self, buffer: &Buffer, line: usize) -> Result<LineState> {
+        let syntax = buffer
+            .syntax_definition
+            .as_ref()
+            .context("Buffer has no syntax definition")?;
+        let cached_state = self
+            .get_render_cache(buffer)?
+            .borrow()
+            .iter()
+            .filter(|(cached_line, _)| **cached_line <= line)
+            .max_by_key(|(cached_line, _)| **cached_line)
+            .map(|(cached_line, state)| LineState {
+                line: *cached_line,
+                parser: state.parse.clone(),
+                scopes: state.highlight.path.clone(),
+            });
+
+        Ok(cached_state.unwrap_or_else(|| LineState::new(syntax)))
+    }
+
     // Tries to fetch a scrollable region for the specified buffer,
     // inserting (and returning a reference to) a new one if not.
     fn get_region(&mut self, buffer: &Buffer) -> Result<&mut ScrollableRegion> {
@@ -165,6 +201,11 @@ impl View {
         Ok(status)
     }
 
+    /// A handle to the terminal, for writing to it outside of rendering.
+    pub fn terminal(&self) -> Arc<Box<dyn Terminal + Sync + Send + 'static>> {
+        self.terminal.clone()
+    }
+
     pub fn last_key(&self) -> &Option<Key> {
         &self.last_key
     }
@@ -176,11 +217,20 @@ impl View {
         self.render_caches
             .insert(buffer_key(buffer)?, render_cache.clone());
 
+        // Keep counting changes if the buffer is being re-initialized,
+        // so that its revision isn't mistaken for an earlier one.
+        let revision = self
+            .revisions
+            .entry(buffer_key(buffer)?)
+            .or_default()
+            .clone();
+
         // Wire up the buffer's change callback to invalidate the render cache.
         buffer.change_callback = Some(Box::new(move |change_position| {
             render_cache
                 .borrow_mut()
                 .invalidate_from(change_position.line);
+            revision.set(revision.get() + 1);
         }));
 
         Ok(())
@@ -296,6 +346,28 @@ mod tests {
         assert!(view.render_caches.get(&buffer.id.unwrap()).is_some());
     }
 
+    #[test]
+    fn buffer_revision_counts_changes_to_initialized_buffers() {
+        let preferences = Rc::new(RefCell::new(Preferences::new(None)));
+        let (tx, _) = mpsc::channel();
+        let mut view = View::new(preferences, tx).unwrap();
+        let mut buffer = Buffer::new();
+        buffer.id = Some(1);
+
+        assert_eq!(view.buffer_revision(&buffer), None);
+        view.initialize_buffer(&mut buffer).unwrap();
+        assert_eq!(view.buffer_revision(&buffer), Some(0));
+
+        buffer.insert("amp");
+        buffer.undo();
+        assert_eq!(view.buffer_revision(&buffer), Some(2));
+
+        // Re-initializing the buffer doesn't reset its revision.
+        view.initialize_buffer(&mut buffer).unwrap();
+        buffer.redo();
+        assert_eq!(view.buffer_revision(&buffer), Some(3));
+    }
+
     #[test]
     fn initialize_buffer_sets_change_callback_to_clear_render_cache() {
         let preferences = Rc::new(RefCell::new(Preferences::new(None)));
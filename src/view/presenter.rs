@@ -1,4 +1,5 @@
 use crate::errors::*;
+use crate::util::bracket;
 use crate::view::buffer::{BufferRenderer, LexemeMapper};
 use crate::view::color::{ColorMap, Colors};
 use crate::view::style::Style;
@@ -11,6 +12,9 @@ use scribe::util::LineIterator;
 use std::borrow::Cow;
 use syntect::highlighting::Theme;
 use syntect::parsing::SyntaxSet;
+use unicode_segmentation::UnicodeSegmentation;
+
+const POPUP_HEIGHT: usize = 8;
 
 /// The `Presenter` type forms the main view API for mode-specific presenters.
 /// It provides the ability to read view dimensions, draw individual character
@@ -111,18 +115,22 @@ impl<'p> Presenter<'p> {
 
         debug!("rendering buffer");
 
-        self.cursor_position = BufferRenderer::new(
+        let matching_bracket =
+            matching_bracket(self.view, buffer, buffer_data, syntax_set, scroll_offset);
+        let preferences = self.view.preferences.borrow();
+        let mut renderer = BufferRenderer::new(
             buffer,
             highlights,
             scroll_offset,
             &**self.view.terminal,
             &self.theme,
-            &self.view.preferences.borrow(),
+            &preferences,
             self.view.get_render_cache(buffer)?,
             syntax_set,
             &mut self.terminal_buffer,
-        )
-        .render(lines, lexeme_mapper)?;
+        );
+        renderer.set_matching_bracket(matching_bracket);
+        self.cursor_position = renderer.render(lines, lexeme_mapper)?;
 
         Ok(())
     }
@@ -187,6 +195,65 @@ impl<'p> Presenter<'p> {
         }]);
     }
 
+    /// Draws the entries in a box below the cursor (or above it, if there's
+    /// more room there), lined up with the text `indent` columns before it.
+    /// Only a window of entries around the selected one is shown.
+    pub fn print_popup(&mut self, entries: &[&str], selected: usize, indent: usize) {
+        let Some(cursor) = self.cursor_position else {
+            return;
+        };
+        if entries.is_empty() {
+            return;
+        }
+
+        // Leave the status line alone.
+        let below = self.height().saturating_sub(cursor.line + 2);
+        let above = cursor.line;
+        let rows = entries.len().min(POPUP_HEIGHT).min(below.max(above));
+        if rows == 0 {
+            return;
+        }
+        let first_line = if rows <= below {
+            cursor.line + 1
+        } else {
+            cursor.line - rows
+        };
+
+        let width = entries
+            .iter()
+            .map(|entry| entry.graphemes(true).count())
+            .max()
+            .unwrap_or_default()
+            .min(self.width().saturating_sub(2));
+        let offset = cursor
+            .offset
+            .saturating_sub(indent + 1)
+            .min(self.width().saturating_sub(width + 2));
+
+        debug!("rendering popup");
+
+        let scroll = selected.saturating_sub(rows - 1);
+        let visible = entries.iter().enumerate().skip(scroll).take(rows);
+        for (row, (index, entry)) in visible.enumerate() {
+            let (style, colors) = if index == selected {
+                (Style::Bold, Colors::Inverted)
+            } else {
+                (Style::Default, Colors::Focused)
+            };
+            let content: String = entry.graphemes(true).take(width).collect();
+
+            self.print(
+                &Position {
+                    line: first_line + row,
+                    offset,
+                },
+                style,
+                colors,
+                format!(" {content:width$} "),
+            );
+        }
+    }
+
     pub fn print<C>(&mut self, position: &Position, style: Style, colors: Colors, content: C)
     where
         C: Into<Cow<'p, str>>,
@@ -204,10 +271,39 @@ impl<'p> Presenter<'p> {
     }
 }
 
+// Finds the bracket paired with the one under the buffer's cursor. Since it's
+// done on every render, only the visible lines are parsed (resuming from the
+// view's cached parser state), and only when there's a bracket to match.
+fn matching_bracket(
+    view: &View,
+    buffer: &Buffer,
+    data: &str,
+    syntax_set: &SyntaxSet,
+    scroll_offset: usize,
+) -> Option<Position> {
+    let under_cursor = data
+        .lines()
+        .nth(buffer.cursor.line)?
+        .graphemes(true)
+        .nth(buffer.cursor.offset)?;
+    if !bracket::is_bracket(under_cursor) {
+        return None;
+    }
+
+    let state = view.line_state(buffer, scroll_offset).ok()?;
+    let visible_end = scroll_offset + view.terminal.height();
+    let lexemes = bracket::lexemes(data, state, visible_end, syntax_set).ok()?;
+
+    bracket::matching_bracket(&lexemes, &buffer.cursor)
+        .ok()
+        .flatten()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::models::application::Preferences;
     use crate::view::View;
+    use scribe::buffer::Position;
     use scribe::{Buffer, Workspace};
     use std::cell::RefCell;
     use std::path::{Path, PathBuf};
@@ -264,4 +360,22 @@ mod tests {
             .unwrap();
         assert_ne!(cache.borrow().iter().count(), 0);
     }
+
+    #[test]
+    fn print_popup_lists_entries_below_the_cursor_within_the_screen() {
+        let preferences = Rc::new(RefCell::new(Preferences::new(None)));
+        let (tx, _) = mpsc::channel();
+        let mut view = View::new(preferences, tx).unwrap();
+        let mut presenter = view.build_presenter().unwrap();
+
+        presenter.set_cursor(Some(Position { line: 0, offset: 5 }));
+        presenter.print_popup(&["amp_view", "amp_editor"], 1, 3);
+
+        let content = presenter.terminal_buffer.content();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[0].trim(), "");
+        assert_eq!(lines[1], " amp_view ");
+        assert_eq!(lines[2], " amp_edit ");
+        assert_eq!(lines[3].trim(), "");
+    }
 }
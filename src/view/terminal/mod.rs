@@ -3,6 +3,7 @@ mod buffer_iterator;
 mod cell;
 mod cursor;
 mod input_parser;
+mod osc52;
 mod termion_terminal;
 
 #[cfg(test)]
@@ -36,6 +37,7 @@ pub trait Terminal {
     fn height(&self) -> usize;
     fn set_cursor(&self, _: Option<Position>);
     fn set_cursor_type(&self, _: CursorType);
+    fn set_clipboard(&self, _: &str);
     fn print(&self, _: &Position, _: Style, _: Colors, _: &str) -> Result<()>;
     fn suspend(&self);
     fn replace(&self, _: &mut Command) -> Result<()>;
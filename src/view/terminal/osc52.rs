@@ -0,0 +1,91 @@
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+use std::env;
+
+/// The OSC 52 escape sequence that sets the terminal's clipboard to the
+/// specified text. Terminals forward it to the clipboard of the machine
+/// they're running on, which makes it work over SSH, too. Within tmux,
+/// it's wrapped so that tmux passes it through to the outer terminal.
+pub fn sequence(text: &str) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+
+    if env::var_os("TMUX").is_some() {
+        tmux_passthrough(&sequence)
+    } else {
+        sequence
+    }
+}
+
+// Wraps the sequence in a DCS passthrough sequence, doubling its escape
+// characters; tmux (with allow-passthrough enabled) unwraps and forwards it.
+fn tmux_passthrough(sequence: &str) -> String {
+    format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+}
+
+fn base64(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let group = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+
+        // Each chunk of 1-3 bytes is encoded using 2-4 characters,
+        // padded out to 4 characters using equals signs.
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let value = (group >> (18 - index * 6)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[value as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    #[test]
+    fn base64_pads_partial_chunks() {
+        assert_eq!(super::base64(b""), "");
+        assert_eq!(super::base64(b"a"), "YQ==");
+        assert_eq!(super::base64(b"am"), "YW0=");
+        assert_eq!(super::base64(b"amp"), "YW1w");
+        assert_eq!(super::base64("amp é\n".as_bytes()), "YW1wIMOpCg==");
+    }
+
+    #[test]
+    fn base64_encodes_every_bit_of_partial_chunks() {
+        assert_eq!(super::base64(&[0xff]), "/w==");
+        assert_eq!(super::base64(&[0xff, 0xfe]), "//4=");
+        assert_eq!(super::base64(&[0xfb, 0xff, 0xbf, 0x03]), "+/+/Aw==");
+        assert_eq!(super::base64(&[0x00, 0x10, 0x83, 0x10, 0x51]), "ABCDEFE=");
+    }
+
+    #[test]
+    fn sequence_wraps_encoded_text_in_an_osc_52_escape_sequence() {
+        let sequence = "\x1b]52;c;YW1w\x07";
+
+        // Tests run within tmux get the wrapped sequence.
+        match env::var_os("TMUX") {
+            Some(_) => assert_eq!(super::sequence("amp"), super::tmux_passthrough(sequence)),
+            None => assert_eq!(super::sequence("amp"), sequence),
+        }
+    }
+
+    #[test]
+    fn tmux_passthrough_wraps_the_sequence_and_doubles_its_escapes() {
+        assert_eq!(
+            super::tmux_passthrough("\x1b]52;c;YW1w\x07"),
+            "\x1bPtmux;\x1b\x1b]52;c;YW1w\x07\x1b\\"
+        );
+    }
+}
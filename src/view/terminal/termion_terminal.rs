@@ -6,7 +6,7 @@ use self::termion::raw::{IntoRawMode, RawTerminal};
 use self::termion::screen::{AlternateScreen, IntoAlternateScreen};
 use self::termion::style;
 use self::termion::{color, cursor};
-use super::{InputParser, Terminal};
+use super::{osc52, InputParser, Terminal};
 use crate::errors::*;
 use crate::view::{Colors, CursorType, Style};
 use log::{debug, trace};
@@ -301,6 +301,15 @@ impl Terminal for TermionTerminal {
         }
     }
 
+    fn set_clipboard(&self, content: &str) {
+        if let Ok(mut output) = self.output.lock() {
+            if let Some(t) = output.as_mut() {
+                let _ = write!(t, "{}", osc52::sequence(content));
+                let _ = t.flush();
+            }
+        }
+    }
+
     fn print<'a>(
         &self,
         target_position: &Position,
@@ -94,6 +94,7 @@ impl Terminal for TestTerminal {
         *cursor = position;
     }
     fn set_cursor_type(&self, _: CursorType) {}
+    fn set_clipboard(&self, _: &str) {}
     fn suspend(&self) {}
     fn replace(&self, command: &mut Command) -> Result<()> {
         command